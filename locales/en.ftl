@@ -0,0 +1,319 @@
+## Formatting
+
+number-decimal-separator = .
+number-group-separator = ,
+# chrono strftime patterns
+date-format = %b %-d, %Y
+datetime-format = %b %-d, %Y %H:%M
+
+## Layout
+
+nav-home = Home
+nav-about = About
+nav-contact = Contact
+nav-dashboard = Dashboard
+nav-logout = Logout
+nav-login = Login
+footer-powered-by = Proudly using MRS Funding Software.
+language-switcher = Language
+color-scheme-toggle = Colour scheme
+color-scheme-auto = Auto
+color-scheme-light = Light
+color-scheme-dark = Dark
+pagination-previous = ← Previous
+pagination-next = Next →
+pagination-newer = ← Newer
+pagination-older = Older →
+pagination-page = Page { $current } of { $total }
+
+## Shared
+
+button-preview = Preview
+button-create = Create
+button-edit = Edit
+button-delete = Delete
+button-filter = Filter
+button-restore = Restore
+button-revoke = Revoke
+button-approve = Approve
+button-reject = Reject
+button-publish = Publish
+button-unpublish = Unpublish
+button-save-revision = Save revision
+confirm-generic = Are you sure?
+field-title = Title:
+field-description = Description:
+field-name = Name:
+field-username = Username:
+field-password = Password:
+field-role = Role:
+field-admin-email = Admin Email:
+markdown-hint = Markdown, with tables, footnotes and task lists.
+new-entry-hint = Write the description in Markdown, with tables, footnotes and task lists. Use Preview to check the result before saving.
+revision-hint = Write the description in Markdown, with tables, footnotes and task lists. Saving keeps the published version until you publish the new revision.
+entry-status = Status:
+status-published = Published
+status-draft = Draft
+trash-link = Trash
+column-name = Name
+column-created = Created
+column-ip = IP
+logged-in-as = Logged in as:
+role-owner = Owner
+role-manager = Campaign Manager
+role-editor = Editor
+theme-problem = Theme { $theme } could not be loaded: { $message }
+
+## Campaigns
+
+campaign-goal = Goal:
+campaign-current = Current:
+campaign-goal-amount = Goal Amount:
+campaign-current-amount = Current Amount:
+campaign-status = Status:
+campaign-status-active = Active
+campaign-status-completed = Completed
+campaign-status-value =
+    { $status ->
+        [active] { campaign-status-active }
+        [completed] { campaign-status-completed }
+       *[other] { $status }
+    }
+campaign-monero-address = Monero Address:
+campaign-xmr-address = XMR Address:
+campaign-start-date = Start Date:
+campaign-end-date = End Date:
+campaign-view-details = View Details →
+campaign-details-link = Details
+campaign-progress = { $percent }% funded
+campaign-address-changed = The donation address for this campaign was changed on { $date } UTC. Please verify it through another channel before donating.
+campaign-goals = Goals
+campaign-updates = Campaign Updates
+campaign-update-posted = Posted: { $date } (Hash: { $hash })
+campaign-filter-any-status = Any status
+home-active-campaigns = Active Campaigns
+home-all-campaigns = All Campaigns →
+campaigns-admin-heading = Campaign Management
+campaigns-create = Create New Campaign
+campaign-post-update = Post Update
+campaign-update-amount = Update Amount
+confirm-delete-campaign = Are you sure you want to delete this campaign?
+amount-heading = Update Current Amount
+amount-new = New Amount:
+campaign-edit-heading = Edit Campaign
+campaign-edit-managers-only = Only the campaign's managers can change it. Choose a language above to translate it.
+campaign-address-approval-hint = Address changes need a second approval before they go live.
+campaign-pending-address = A change to { $address } is waiting for approval.
+campaign-review-approval = Review
+campaign-update-submit = Update Campaign
+campaign-create-submit = Create Campaign
+update-heading = Post Campaign Update
+update-hint = Write the update in Markdown, with tables, footnotes and task lists. Use Preview to check the result before posting.
+update-field = Update:
+
+## Login
+
+login-heading = Login
+login-username = Username
+login-password = Password
+login-submit = Login
+login-totp-heading = Two-Factor Authentication
+login-totp-code = Authenticator or recovery code
+login-totp-submit = Verify
+
+## Dashboard
+
+dashboard-heading = Dashboard
+dashboard-about = Open About Me Options
+dashboard-contact = Open Contact Options
+dashboard-campaigns = Open Campaign Options
+dashboard-account = Account Settings
+dashboard-address-changes = Payout Address Approvals
+dashboard-users = Manage Users
+dashboard-audit = Audit Log
+dashboard-themes = Themes
+
+## Errors
+
+error-internal-heading = 500 - Something Went Wrong
+error-internal-reference = The error has been logged. If you report it, please mention this reference:
+error-not-found-heading = 404 - Page Not Found
+error-return-home = Return to Homepage
+error-description-empty = The description must not be empty.
+error-admin-email-invalid = The admin email must be a valid address.
+error-current-password = Current password is incorrect
+error-passwords-mismatch = New passwords do not match
+error-password-unchanged = New password must differ from the current one
+error-username-taken = Username already exists
+error-token-name = Token name must be 1 to 64 characters
+error-token-not-found = Token not found
+error-request-not-found = Request not found
+error-cooldown = The cooldown for approving your own change has not passed
+error-incorrect-password = Incorrect password
+error-request-stale = The request is no longer pending or the address has changed
+error-campaign-not-found = Campaign not found
+error-built-in-theme = Built-in themes cannot be replaced; adjust them with custom CSS instead
+error-stylesheet-too-large = Stylesheets are limited to { $kib } KiB
+error-theme-not-found = Theme not found
+error-theme-name-length = Theme names must be 1 to 40 characters long
+error-theme-name-invalid = Theme names may only contain lowercase letters, digits, '-' and '_'
+error-theme-variable-name = Variable '{ $name }' must be lowercase letters, digits and '-'
+error-theme-variable-value = Variable '{ $name }' has an invalid value
+error-theme-path-outside = '{ $path }' must be a relative path inside the package
+error-theme-file-missing = '{ $path }' does not exist
+error-theme-unreadable = Cannot read '{ $path }': { $error }
+error-theme-manifest-invalid = Invalid { $path }: { $error }
+error-theme-version-missing = The manifest needs a version
+error-theme-color-scheme = color_scheme must be 'light' or 'dark', not '{ $value }'
+error-theme-not-asset = '{ $path }' is not a font or image file
+error-theme-asset-too-large = '{ $path }' is larger than { $mib } MiB
+error-theme-font-invalid = Font '{ $family }' has an invalid family, weight or style
+error-theme-not-font = '{ $path }' is not a font file
+error-theme-templates-missing = Templates directory '{ $path }' does not exist in the package
+error-theme-template-unknown = Template '{ $name }' does not replace a built-in template
+error-theme-template-invalid = Template '{ $name }': { $error }
+error-unknown-role = Unknown role
+error-user-not-found = User not found
+error-demote-self = You cannot demote yourself
+error-last-owner = At least one owner is required
+error-delete-self = You cannot delete yourself
+error-unknown-color-scheme = Unknown colour scheme '{ $scheme }'
+error-username-invalid = Username must be 1 to 32 letters, digits, '_', '-' or '.'
+error-password-too-short = Password must be at least { $min } characters long
+error-password-too-long = Password must be at most { $max } characters long
+error-password-contains-username = Password must not contain the username
+error-password-weak = Password must mix at least three of: lowercase, uppercase, digits, symbols
+
+## About and contact
+
+about-heading = About Us
+about-placeholder = Please configure about me in dashboard
+about-all-heading = All About Entries
+about-create = Create New Entry
+about-details-heading = About Entry Details
+about-new-heading = Create New About Entry
+contact-heading = Contact Us
+contact-placeholder = Contact information will be available soon
+contact-email = Email:
+contact-all-heading = All Contact Entries
+contact-create = Create New Contact
+contact-details-heading = Contact Entry Details
+contact-new-heading = Create New Contact Information
+
+## Translations
+
+translation-hint = Blank fields show the text in the default language.
+translation-original = Original text
+translation-save = Save translation
+translation-update-heading = Update posted { $date }
+
+## Revisions
+
+revisions-heading = Revisions
+revision-not-published = This entry is not published.
+revision-column = Revision
+revision-author = Author
+revision-saved = Saved
+revision-compare = Compare with previous
+revision-move-to-trash = Move to trash
+revision-diff-heading = Changes from revision #{ $from } to #{ $to }
+revision-back = Back to entry
+trash-heading = Trash
+trash-entry = Entry
+trash-deleted = Deleted
+trash-delete-permanently = Delete permanently
+trash-empty = The trash is empty.
+trash-back = Back to all entries
+
+## Account
+
+account-heading = Account Settings
+account-change-password = Change Password
+account-password-rules = At least { $min } characters, mixing three of: lowercase, uppercase, digits and symbols.
+account-password-logout = Changing your password logs out all your other sessions.
+account-current-password = Current password:
+account-new-password = New password:
+account-confirm-password = Confirm new password:
+account-change-username = Change Username
+account-new-username = New username:
+api-tokens-heading = API Tokens
+api-tokens-usage = To act as your account from scripts, send a token in this header:
+api-tokens-new = Copy your new token now, it will not be shown again:
+api-tokens-last-used = Last Used
+api-tokens-never = Never
+api-tokens-name = Token name:
+api-tokens-create = Create Token
+recovery-heading = Two-Factor Authentication Enabled
+recovery-hint = Store these recovery codes somewhere safe. Each code works once and they will not be shown again.
+recovery-back = Back to Account
+two-factor-heading = Two-Factor Authentication
+two-factor-enabled = Two-factor authentication is enabled.
+two-factor-disabled = Two-factor authentication is disabled.
+two-factor-code = Authenticator or recovery code:
+two-factor-app-code = Authenticator code:
+two-factor-disable = Disable 2FA
+two-factor-enable = Enable 2FA
+two-factor-setup = Set up 2FA
+two-factor-scan = Scan this QR code with your authenticator app, then enter the code it shows.
+two-factor-manual = Or add it manually:
+
+## Sessions
+
+sessions-heading = Active Sessions
+sessions-last-seen = Last Seen
+sessions-user-agent = User Agent
+sessions-this = This session
+sessions-revoke-all = Log Out Everywhere
+confirm-revoke-all-sessions = Log out of all sessions, including this one?
+
+## Payout address changes
+
+address-changes-heading = Pending Payout Address Changes
+address-current = Current address:
+address-new = New address:
+address-requested = Requested by { $name } at { $time } UTC
+address-confirm-password = Confirm your password:
+address-waiting = Waiting for a second admin, or for you to confirm after { $time } UTC.
+
+## Audit log
+
+audit-heading = Audit Log
+audit-chain-intact = Hash chain intact
+audit-chain-broken = Hash chain broken at entry #{ $entry }
+audit-chain-unverified = Hash chain could not be verified
+audit-chain-verify = Verify hash chain
+audit-time = Time (UTC)
+audit-actor = Actor
+audit-action = Action
+audit-target-type = Target type
+audit-target = Target
+audit-before = Before
+audit-after = After
+audit-export = Export JSON
+
+## Themes
+
+themes-heading = Themes
+themes-previewing = This page is shown with the { $theme } theme. Visitors still see the active one.
+themes-use = Use This Theme
+themes-end-preview = End Preview
+themes-built-in = built-in
+theme-version = Version { $version }
+theme-author = by { $author }
+themes-active = Active
+themes-activate = Activate
+themes-custom-css = Custom CSS
+themes-custom-css-hint = Added after whichever theme is active, for small adjustments.
+themes-save-custom-css = Save Custom CSS
+themes-add = Add a Theme
+themes-add-hint = Adding a theme under the name of an added one replaces it.
+themes-stylesheet = Stylesheet:
+themes-add-submit = Add Theme
+
+## Users
+
+users-heading = User Management
+users-change-role = Change Role
+users-create-heading = Create New User
+users-create-submit = Create User
+confirm-delete-user = Are you sure you want to delete this user?
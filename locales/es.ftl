@@ -0,0 +1,319 @@
+## Formatting
+
+number-decimal-separator = ,
+number-group-separator = .
+# chrono strftime patterns
+date-format = %d/%m/%Y
+datetime-format = %d/%m/%Y %H:%M
+
+## Layout
+
+nav-home = Inicio
+nav-about = Acerca de
+nav-contact = Contacto
+nav-dashboard = Panel
+nav-logout = Cerrar sesión
+nav-login = Iniciar sesión
+footer-powered-by = Con orgullo usamos MRS Funding Software.
+language-switcher = Idioma
+color-scheme-toggle = Esquema de color
+color-scheme-auto = Automático
+color-scheme-light = Claro
+color-scheme-dark = Oscuro
+pagination-previous = ← Anterior
+pagination-next = Siguiente →
+pagination-newer = ← Más recientes
+pagination-older = Más antiguas →
+pagination-page = Página { $current } de { $total }
+
+## Shared
+
+button-preview = Vista previa
+button-create = Crear
+button-edit = Editar
+button-delete = Eliminar
+button-filter = Filtrar
+button-restore = Restaurar
+button-revoke = Revocar
+button-approve = Aprobar
+button-reject = Rechazar
+button-publish = Publicar
+button-unpublish = Despublicar
+button-save-revision = Guardar revisión
+confirm-generic = ¿Estás seguro?
+field-title = Título:
+field-description = Descripción:
+field-name = Nombre:
+field-username = Nombre de usuario:
+field-password = Contraseña:
+field-role = Rol:
+field-admin-email = Correo del administrador:
+markdown-hint = Markdown, con tablas, notas al pie y listas de tareas.
+new-entry-hint = Escribe la descripción en Markdown, con tablas, notas al pie y listas de tareas. Usa Vista previa para comprobar el resultado antes de guardar.
+revision-hint = Escribe la descripción en Markdown, con tablas, notas al pie y listas de tareas. Al guardar se mantiene la versión publicada hasta que publiques la nueva revisión.
+entry-status = Estado:
+status-published = Publicado
+status-draft = Borrador
+trash-link = Papelera
+column-name = Nombre
+column-created = Creado
+column-ip = IP
+logged-in-as = Sesión iniciada como:
+role-owner = Propietario
+role-manager = Gestor de campañas
+role-editor = Editor
+theme-problem = No se pudo cargar el tema { $theme }: { $message }
+
+## Campaigns
+
+campaign-goal = Objetivo:
+campaign-current = Actual:
+campaign-goal-amount = Importe objetivo:
+campaign-current-amount = Importe actual:
+campaign-status = Estado:
+campaign-status-active = Activa
+campaign-status-completed = Completada
+campaign-status-value =
+    { $status ->
+        [active] { campaign-status-active }
+        [completed] { campaign-status-completed }
+       *[other] { $status }
+    }
+campaign-monero-address = Dirección Monero:
+campaign-xmr-address = Dirección XMR:
+campaign-start-date = Fecha de inicio:
+campaign-end-date = Fecha de fin:
+campaign-view-details = Ver detalles →
+campaign-details-link = Detalles
+campaign-progress = { $percent } % financiado
+campaign-address-changed = La dirección de donación de esta campaña se cambió el { $date } UTC. Verifícala por otro canal antes de donar.
+campaign-goals = Objetivos
+campaign-updates = Novedades de la campaña
+campaign-update-posted = Publicado: { $date } (hash: { $hash })
+campaign-filter-any-status = Cualquier estado
+home-active-campaigns = Campañas activas
+home-all-campaigns = Todas las campañas →
+campaigns-admin-heading = Gestión de campañas
+campaigns-create = Crear nueva campaña
+campaign-post-update = Publicar novedad
+campaign-update-amount = Actualizar importe
+confirm-delete-campaign = ¿Seguro que quieres eliminar esta campaña?
+amount-heading = Actualizar importe actual
+amount-new = Nuevo importe:
+campaign-edit-heading = Editar campaña
+campaign-edit-managers-only = Solo quienes gestionan la campaña pueden cambiarla. Elige un idioma arriba para traducirla.
+campaign-address-approval-hint = Los cambios de dirección necesitan una segunda aprobación antes de aplicarse.
+campaign-pending-address = Un cambio a { $address } está pendiente de aprobación.
+campaign-review-approval = Revisar
+campaign-update-submit = Actualizar campaña
+campaign-create-submit = Crear campaña
+update-heading = Publicar novedad de la campaña
+update-hint = Escribe la novedad en Markdown, con tablas, notas al pie y listas de tareas. Usa Vista previa para comprobar el resultado antes de publicar.
+update-field = Novedad:
+
+## Login
+
+login-heading = Iniciar sesión
+login-username = Nombre de usuario
+login-password = Contraseña
+login-submit = Entrar
+login-totp-heading = Autenticación en dos pasos
+login-totp-code = Código del autenticador o de recuperación
+login-totp-submit = Verificar
+
+## Dashboard
+
+dashboard-heading = Panel
+dashboard-about = Opciones de «Acerca de»
+dashboard-contact = Opciones de contacto
+dashboard-campaigns = Opciones de campañas
+dashboard-account = Ajustes de la cuenta
+dashboard-address-changes = Aprobación de direcciones de cobro
+dashboard-users = Gestionar usuarios
+dashboard-audit = Registro de auditoría
+dashboard-themes = Temas
+
+## Errors
+
+error-internal-heading = 500 - Algo ha salido mal
+error-internal-reference = El error se ha registrado. Si lo notificas, menciona esta referencia:
+error-not-found-heading = 404 - Página no encontrada
+error-return-home = Volver al inicio
+error-description-empty = La descripción no puede estar vacía.
+error-admin-email-invalid = El correo del administrador debe ser una dirección válida.
+error-current-password = La contraseña actual no es correcta
+error-passwords-mismatch = Las contraseñas nuevas no coinciden
+error-password-unchanged = La contraseña nueva debe ser distinta de la actual
+error-username-taken = El nombre de usuario ya existe
+error-token-name = El nombre del token debe tener entre 1 y 64 caracteres
+error-token-not-found = Token no encontrado
+error-request-not-found = Solicitud no encontrada
+error-cooldown = Aún no ha pasado el tiempo de espera para aprobar tu propio cambio
+error-incorrect-password = Contraseña incorrecta
+error-request-stale = La solicitud ya no está pendiente o la dirección ha cambiado
+error-campaign-not-found = Campaña no encontrada
+error-built-in-theme = Los temas integrados no se pueden reemplazar; ajústalos con CSS personalizado
+error-stylesheet-too-large = Las hojas de estilo están limitadas a { $kib } KiB
+error-theme-not-found = Tema no encontrado
+error-theme-name-length = Los nombres de tema deben tener entre 1 y 40 caracteres
+error-theme-name-invalid = Los nombres de tema solo pueden contener letras minúsculas, dígitos, '-' y '_'
+error-theme-variable-name = La variable '{ $name }' solo puede contener letras minúsculas, dígitos y '-'
+error-theme-variable-value = La variable '{ $name }' tiene un valor no válido
+error-theme-path-outside = '{ $path }' debe ser una ruta relativa dentro del paquete
+error-theme-file-missing = '{ $path }' no existe
+error-theme-unreadable = No se puede leer '{ $path }': { $error }
+error-theme-manifest-invalid = { $path } no es válido: { $error }
+error-theme-version-missing = El manifiesto necesita una versión
+error-theme-color-scheme = color_scheme debe ser 'light' o 'dark', no '{ $value }'
+error-theme-not-asset = '{ $path }' no es un archivo de fuente ni de imagen
+error-theme-asset-too-large = '{ $path }' ocupa más de { $mib } MiB
+error-theme-font-invalid = La fuente '{ $family }' tiene una familia, grosor o estilo no válido
+error-theme-not-font = '{ $path }' no es un archivo de fuente
+error-theme-templates-missing = El directorio de plantillas '{ $path }' no existe en el paquete
+error-theme-template-unknown = La plantilla '{ $name }' no sustituye a ninguna plantilla incorporada
+error-theme-template-invalid = Plantilla '{ $name }': { $error }
+error-unknown-role = Rol desconocido
+error-user-not-found = Usuario no encontrado
+error-demote-self = No puedes quitarte el rol a ti mismo
+error-last-owner = Se necesita al menos un propietario
+error-delete-self = No puedes eliminarte a ti mismo
+error-unknown-color-scheme = Esquema de color desconocido «{ $scheme }»
+error-username-invalid = El nombre de usuario debe tener de 1 a 32 letras, dígitos, «_», «-» o «.»
+error-password-too-short = La contraseña debe tener al menos { $min } caracteres
+error-password-too-long = La contraseña debe tener como máximo { $max } caracteres
+error-password-contains-username = La contraseña no puede contener el nombre de usuario
+error-password-weak = La contraseña debe combinar al menos tres de: minúsculas, mayúsculas, dígitos y símbolos
+
+## About and contact
+
+about-heading = Acerca de nosotros
+about-placeholder = Configura la sección «Acerca de» en el panel
+about-all-heading = Todas las entradas de «Acerca de»
+about-create = Crear nueva entrada
+about-details-heading = Detalles de la entrada
+about-new-heading = Crear nueva entrada de «Acerca de»
+contact-heading = Contacto
+contact-placeholder = La información de contacto estará disponible pronto
+contact-email = Correo:
+contact-all-heading = Todas las entradas de contacto
+contact-create = Crear nuevo contacto
+contact-details-heading = Detalles del contacto
+contact-new-heading = Crear nueva información de contacto
+
+## Translations
+
+translation-hint = Los campos vacíos muestran el texto en el idioma predeterminado.
+translation-original = Texto original
+translation-save = Guardar traducción
+translation-update-heading = Novedad publicada el { $date }
+
+## Revisions
+
+revisions-heading = Revisiones
+revision-not-published = Esta entrada no está publicada.
+revision-column = Revisión
+revision-author = Autor
+revision-saved = Guardada
+revision-compare = Comparar con la anterior
+revision-move-to-trash = Mover a la papelera
+revision-diff-heading = Cambios de la revisión #{ $from } a la #{ $to }
+revision-back = Volver a la entrada
+trash-heading = Papelera
+trash-entry = Entrada
+trash-deleted = Eliminada
+trash-delete-permanently = Eliminar definitivamente
+trash-empty = La papelera está vacía.
+trash-back = Volver a todas las entradas
+
+## Account
+
+account-heading = Ajustes de la cuenta
+account-change-password = Cambiar contraseña
+account-password-rules = Al menos { $min } caracteres, combinando tres de: minúsculas, mayúsculas, dígitos y símbolos.
+account-password-logout = Al cambiar la contraseña se cierran todas tus otras sesiones.
+account-current-password = Contraseña actual:
+account-new-password = Contraseña nueva:
+account-confirm-password = Confirmar contraseña nueva:
+account-change-username = Cambiar nombre de usuario
+account-new-username = Nuevo nombre de usuario:
+api-tokens-heading = Tokens de API
+api-tokens-usage = Para actuar con tu cuenta desde scripts, envía un token en esta cabecera:
+api-tokens-new = Copia tu nuevo token ahora, no se volverá a mostrar:
+api-tokens-last-used = Último uso
+api-tokens-never = Nunca
+api-tokens-name = Nombre del token:
+api-tokens-create = Crear token
+recovery-heading = Autenticación en dos pasos activada
+recovery-hint = Guarda estos códigos de recuperación en un lugar seguro. Cada código funciona una vez y no se volverán a mostrar.
+recovery-back = Volver a la cuenta
+two-factor-heading = Autenticación en dos pasos
+two-factor-enabled = La autenticación en dos pasos está activada.
+two-factor-disabled = La autenticación en dos pasos está desactivada.
+two-factor-code = Código del autenticador o de recuperación:
+two-factor-app-code = Código del autenticador:
+two-factor-disable = Desactivar 2FA
+two-factor-enable = Activar 2FA
+two-factor-setup = Configurar 2FA
+two-factor-scan = Escanea este código QR con tu aplicación de autenticación e introduce el código que muestre.
+two-factor-manual = O añádelo manualmente:
+
+## Sessions
+
+sessions-heading = Sesiones activas
+sessions-last-seen = Última actividad
+sessions-user-agent = Agente de usuario
+sessions-this = Esta sesión
+sessions-revoke-all = Cerrar sesión en todas partes
+confirm-revoke-all-sessions = ¿Cerrar todas las sesiones, incluida esta?
+
+## Payout address changes
+
+address-changes-heading = Cambios pendientes de dirección de cobro
+address-current = Dirección actual:
+address-new = Dirección nueva:
+address-requested = Solicitado por { $name } el { $time } UTC
+address-confirm-password = Confirma tu contraseña:
+address-waiting = Esperando a un segundo administrador, o a que confirmes después del { $time } UTC.
+
+## Audit log
+
+audit-heading = Registro de auditoría
+audit-chain-intact = Cadena de hashes intacta
+audit-chain-broken = Cadena de hashes rota en la entrada #{ $entry }
+audit-chain-unverified = No se pudo verificar la cadena de hashes
+audit-chain-verify = Verificar la cadena de hashes
+audit-time = Hora (UTC)
+audit-actor = Autor
+audit-action = Acción
+audit-target-type = Tipo de objeto
+audit-target = Objeto
+audit-before = Antes
+audit-after = Después
+audit-export = Exportar JSON
+
+## Themes
+
+themes-heading = Temas
+themes-previewing = Esta página se muestra con el tema { $theme }. Los visitantes siguen viendo el activo.
+themes-use = Usar este tema
+themes-end-preview = Terminar vista previa
+themes-built-in = integrado
+theme-version = Versión { $version }
+theme-author = de { $author }
+themes-active = Activo
+themes-activate = Activar
+themes-custom-css = CSS personalizado
+themes-custom-css-hint = Se añade después del tema activo, para pequeños ajustes.
+themes-save-custom-css = Guardar CSS personalizado
+themes-add = Añadir un tema
+themes-add-hint = Añadir un tema con el nombre de otro añadido lo reemplaza.
+themes-stylesheet = Hoja de estilo:
+themes-add-submit = Añadir tema
+
+## Users
+
+users-heading = Gestión de usuarios
+users-change-role = Cambiar rol
+users-create-heading = Crear nuevo usuario
+users-create-submit = Crear usuario
+confirm-delete-user = ¿Seguro que quieres eliminar este usuario?
@@ -0,0 +1,320 @@
+## Formatting
+
+number-decimal-separator = ,
+# A no-break space
+number-group-separator = { "\u00A0" }
+# chrono strftime patterns
+date-format = %d.%m.%Y
+datetime-format = %d.%m.%Y %H:%M
+
+## Layout
+
+nav-home = Strona główna
+nav-about = O nas
+nav-contact = Kontakt
+nav-dashboard = Panel
+nav-logout = Wyloguj
+nav-login = Zaloguj
+footer-powered-by = Z dumą korzystamy z MRS Funding Software.
+language-switcher = Język
+color-scheme-toggle = Schemat kolorów
+color-scheme-auto = Automatyczny
+color-scheme-light = Jasny
+color-scheme-dark = Ciemny
+pagination-previous = ← Poprzednia
+pagination-next = Następna →
+pagination-newer = ← Nowsze
+pagination-older = Starsze →
+pagination-page = Strona { $current } z { $total }
+
+## Shared
+
+button-preview = Podgląd
+button-create = Utwórz
+button-edit = Edytuj
+button-delete = Usuń
+button-filter = Filtruj
+button-restore = Przywróć
+button-revoke = Unieważnij
+button-approve = Zatwierdź
+button-reject = Odrzuć
+button-publish = Opublikuj
+button-unpublish = Wycofaj publikację
+button-save-revision = Zapisz wersję
+confirm-generic = Czy na pewno?
+field-title = Tytuł:
+field-description = Opis:
+field-name = Nazwa:
+field-username = Nazwa użytkownika:
+field-password = Hasło:
+field-role = Rola:
+field-admin-email = E-mail administratora:
+markdown-hint = Markdown z tabelami, przypisami i listami zadań.
+new-entry-hint = Napisz opis w Markdown z tabelami, przypisami i listami zadań. Użyj podglądu, aby sprawdzić wynik przed zapisaniem.
+revision-hint = Napisz opis w Markdown z tabelami, przypisami i listami zadań. Zapisanie zachowuje opublikowaną wersję, dopóki nie opublikujesz nowej.
+entry-status = Status:
+status-published = Opublikowany
+status-draft = Szkic
+trash-link = Kosz
+column-name = Nazwa
+column-created = Utworzono
+column-ip = IP
+logged-in-as = Zalogowano jako:
+role-owner = Właściciel
+role-manager = Menedżer kampanii
+role-editor = Redaktor
+theme-problem = Nie udało się wczytać motywu { $theme }: { $message }
+
+## Campaigns
+
+campaign-goal = Cel:
+campaign-current = Zebrano:
+campaign-goal-amount = Kwota docelowa:
+campaign-current-amount = Zebrana kwota:
+campaign-status = Status:
+campaign-status-active = Aktywna
+campaign-status-completed = Zakończona
+campaign-status-value =
+    { $status ->
+        [active] { campaign-status-active }
+        [completed] { campaign-status-completed }
+       *[other] { $status }
+    }
+campaign-monero-address = Adres Monero:
+campaign-xmr-address = Adres XMR:
+campaign-start-date = Data rozpoczęcia:
+campaign-end-date = Data zakończenia:
+campaign-view-details = Zobacz szczegóły →
+campaign-details-link = Szczegóły
+campaign-progress = Zebrano { $percent }%
+campaign-address-changed = Adres do wpłat tej kampanii zmieniono { $date } UTC. Przed wpłatą sprawdź go innym kanałem.
+campaign-goals = Cele
+campaign-updates = Aktualności kampanii
+campaign-update-posted = Opublikowano: { $date } (hash: { $hash })
+campaign-filter-any-status = Dowolny status
+home-active-campaigns = Aktywne kampanie
+home-all-campaigns = Wszystkie kampanie →
+campaigns-admin-heading = Zarządzanie kampaniami
+campaigns-create = Utwórz nową kampanię
+campaign-post-update = Dodaj aktualność
+campaign-update-amount = Zmień kwotę
+confirm-delete-campaign = Czy na pewno chcesz usunąć tę kampanię?
+amount-heading = Zmień zebraną kwotę
+amount-new = Nowa kwota:
+campaign-edit-heading = Edytuj kampanię
+campaign-edit-managers-only = Tylko menedżerowie kampanii mogą ją zmieniać. Wybierz język powyżej, aby ją przetłumaczyć.
+campaign-address-approval-hint = Zmiana adresu wymaga drugiego zatwierdzenia, zanim zacznie obowiązywać.
+campaign-pending-address = Zmiana na { $address } czeka na zatwierdzenie.
+campaign-review-approval = Przejrzyj
+campaign-update-submit = Zapisz kampanię
+campaign-create-submit = Utwórz kampanię
+update-heading = Dodaj aktualność kampanii
+update-hint = Napisz aktualność w Markdown z tabelami, przypisami i listami zadań. Użyj podglądu, aby sprawdzić wynik przed publikacją.
+update-field = Aktualność:
+
+## Login
+
+login-heading = Logowanie
+login-username = Nazwa użytkownika
+login-password = Hasło
+login-submit = Zaloguj
+login-totp-heading = Uwierzytelnianie dwuskładnikowe
+login-totp-code = Kod z aplikacji lub kod odzyskiwania
+login-totp-submit = Zweryfikuj
+
+## Dashboard
+
+dashboard-heading = Panel
+dashboard-about = Ustawienia strony „O nas”
+dashboard-contact = Ustawienia kontaktu
+dashboard-campaigns = Ustawienia kampanii
+dashboard-account = Ustawienia konta
+dashboard-address-changes = Zatwierdzanie adresów wypłat
+dashboard-users = Zarządzaj użytkownikami
+dashboard-audit = Dziennik audytu
+dashboard-themes = Motywy
+
+## Errors
+
+error-internal-heading = 500 - Coś poszło nie tak
+error-internal-reference = Błąd został zapisany. Zgłaszając go, podaj ten identyfikator:
+error-not-found-heading = 404 - Nie znaleziono strony
+error-return-home = Wróć na stronę główną
+error-description-empty = Opis nie może być pusty.
+error-admin-email-invalid = E-mail administratora musi być poprawnym adresem.
+error-current-password = Obecne hasło jest nieprawidłowe
+error-passwords-mismatch = Nowe hasła się nie zgadzają
+error-password-unchanged = Nowe hasło musi się różnić od obecnego
+error-username-taken = Nazwa użytkownika już istnieje
+error-token-name = Nazwa tokenu musi mieć od 1 do 64 znaków
+error-token-not-found = Nie znaleziono tokenu
+error-request-not-found = Nie znaleziono wniosku
+error-cooldown = Czas oczekiwania na zatwierdzenie własnej zmiany jeszcze nie minął
+error-incorrect-password = Nieprawidłowe hasło
+error-request-stale = Wniosek nie oczekuje już na decyzję albo adres się zmienił
+error-campaign-not-found = Nie znaleziono kampanii
+error-built-in-theme = Wbudowanych motywów nie można zastąpić; dostosuj je własnym CSS
+error-stylesheet-too-large = Arkusze stylów mogą mieć najwyżej { $kib } KiB
+error-theme-not-found = Nie znaleziono motywu
+error-theme-name-length = Nazwa motywu musi mieć od 1 do 40 znaków
+error-theme-name-invalid = Nazwa motywu może zawierać tylko małe litery, cyfry, '-' i '_'
+error-theme-variable-name = Zmienna '{ $name }' może zawierać tylko małe litery, cyfry i '-'
+error-theme-variable-value = Zmienna '{ $name }' ma nieprawidłową wartość
+error-theme-path-outside = '{ $path }' musi być ścieżką względną wewnątrz pakietu
+error-theme-file-missing = '{ $path }' nie istnieje
+error-theme-unreadable = Nie można odczytać '{ $path }': { $error }
+error-theme-manifest-invalid = Nieprawidłowy plik { $path }: { $error }
+error-theme-version-missing = Manifest musi zawierać wersję
+error-theme-color-scheme = color_scheme musi mieć wartość 'light' lub 'dark', a nie '{ $value }'
+error-theme-not-asset = '{ $path }' nie jest plikiem czcionki ani obrazu
+error-theme-asset-too-large = '{ $path }' jest większy niż { $mib } MiB
+error-theme-font-invalid = Czcionka '{ $family }' ma nieprawidłową rodzinę, grubość lub styl
+error-theme-not-font = '{ $path }' nie jest plikiem czcionki
+error-theme-templates-missing = Katalog szablonów '{ $path }' nie istnieje w pakiecie
+error-theme-template-unknown = Szablon '{ $name }' nie zastępuje żadnego wbudowanego szablonu
+error-theme-template-invalid = Szablon '{ $name }': { $error }
+error-unknown-role = Nieznana rola
+error-user-not-found = Nie znaleziono użytkownika
+error-demote-self = Nie możesz odebrać roli samemu sobie
+error-last-owner = Wymagany jest co najmniej jeden właściciel
+error-delete-self = Nie możesz usunąć samego siebie
+error-unknown-color-scheme = Nieznany schemat kolorów „{ $scheme }”
+error-username-invalid = Nazwa użytkownika musi mieć od 1 do 32 liter, cyfr, „_”, „-” lub „.”
+error-password-too-short = Hasło musi mieć co najmniej { $min } znaków
+error-password-too-long = Hasło może mieć najwyżej { $max } znaków
+error-password-contains-username = Hasło nie może zawierać nazwy użytkownika
+error-password-weak = Hasło musi łączyć co najmniej trzy z: małe litery, wielkie litery, cyfry, symbole
+
+## About and contact
+
+about-heading = O nas
+about-placeholder = Skonfiguruj stronę „O nas” w panelu
+about-all-heading = Wszystkie wpisy „O nas”
+about-create = Utwórz nowy wpis
+about-details-heading = Szczegóły wpisu
+about-new-heading = Utwórz nowy wpis „O nas”
+contact-heading = Kontakt
+contact-placeholder = Dane kontaktowe będą dostępne wkrótce
+contact-email = E-mail:
+contact-all-heading = Wszystkie wpisy kontaktowe
+contact-create = Utwórz nowy kontakt
+contact-details-heading = Szczegóły kontaktu
+contact-new-heading = Utwórz nowe dane kontaktowe
+
+## Translations
+
+translation-hint = Puste pola pokazują tekst w języku domyślnym.
+translation-original = Tekst oryginalny
+translation-save = Zapisz tłumaczenie
+translation-update-heading = Aktualność opublikowana { $date }
+
+## Revisions
+
+revisions-heading = Wersje
+revision-not-published = Ten wpis nie jest opublikowany.
+revision-column = Wersja
+revision-author = Autor
+revision-saved = Zapisano
+revision-compare = Porównaj z poprzednią
+revision-move-to-trash = Przenieś do kosza
+revision-diff-heading = Zmiany od wersji #{ $from } do #{ $to }
+revision-back = Wróć do wpisu
+trash-heading = Kosz
+trash-entry = Wpis
+trash-deleted = Usunięto
+trash-delete-permanently = Usuń na zawsze
+trash-empty = Kosz jest pusty.
+trash-back = Wróć do wszystkich wpisów
+
+## Account
+
+account-heading = Ustawienia konta
+account-change-password = Zmień hasło
+account-password-rules = Co najmniej { $min } znaków, w tym trzy z: małe litery, wielkie litery, cyfry i symbole.
+account-password-logout = Zmiana hasła wylogowuje wszystkie pozostałe sesje.
+account-current-password = Obecne hasło:
+account-new-password = Nowe hasło:
+account-confirm-password = Powtórz nowe hasło:
+account-change-username = Zmień nazwę użytkownika
+account-new-username = Nowa nazwa użytkownika:
+api-tokens-heading = Tokeny API
+api-tokens-usage = Aby działać jako Twoje konto ze skryptów, wysyłaj token w tym nagłówku:
+api-tokens-new = Skopiuj nowy token teraz, nie zostanie pokazany ponownie:
+api-tokens-last-used = Ostatnio użyty
+api-tokens-never = Nigdy
+api-tokens-name = Nazwa tokenu:
+api-tokens-create = Utwórz token
+recovery-heading = Uwierzytelnianie dwuskładnikowe włączone
+recovery-hint = Przechowuj te kody odzyskiwania w bezpiecznym miejscu. Każdy kod działa raz i nie zostaną pokazane ponownie.
+recovery-back = Wróć do konta
+two-factor-heading = Uwierzytelnianie dwuskładnikowe
+two-factor-enabled = Uwierzytelnianie dwuskładnikowe jest włączone.
+two-factor-disabled = Uwierzytelnianie dwuskładnikowe jest wyłączone.
+two-factor-code = Kod z aplikacji lub kod odzyskiwania:
+two-factor-app-code = Kod z aplikacji:
+two-factor-disable = Wyłącz 2FA
+two-factor-enable = Włącz 2FA
+two-factor-setup = Skonfiguruj 2FA
+two-factor-scan = Zeskanuj ten kod QR aplikacją uwierzytelniającą, a następnie wpisz pokazany kod.
+two-factor-manual = Albo dodaj go ręcznie:
+
+## Sessions
+
+sessions-heading = Aktywne sesje
+sessions-last-seen = Ostatnia aktywność
+sessions-user-agent = Przeglądarka
+sessions-this = Ta sesja
+sessions-revoke-all = Wyloguj wszędzie
+confirm-revoke-all-sessions = Wylogować ze wszystkich sesji, łącznie z tą?
+
+## Payout address changes
+
+address-changes-heading = Oczekujące zmiany adresów wypłat
+address-current = Obecny adres:
+address-new = Nowy adres:
+address-requested = Zgłoszone przez { $name } { $time } UTC
+address-confirm-password = Potwierdź hasło:
+address-waiting = Czeka na drugiego administratora lub na Twoje potwierdzenie po { $time } UTC.
+
+## Audit log
+
+audit-heading = Dziennik audytu
+audit-chain-intact = Łańcuch skrótów nienaruszony
+audit-chain-broken = Łańcuch skrótów przerwany przy wpisie #{ $entry }
+audit-chain-unverified = Nie udało się zweryfikować łańcucha skrótów
+audit-chain-verify = Zweryfikuj łańcuch skrótów
+audit-time = Czas (UTC)
+audit-actor = Wykonawca
+audit-action = Akcja
+audit-target-type = Typ obiektu
+audit-target = Obiekt
+audit-before = Przed
+audit-after = Po
+audit-export = Eksportuj JSON
+
+## Themes
+
+themes-heading = Motywy
+themes-previewing = Ta strona jest wyświetlana z motywem { $theme }. Odwiedzający nadal widzą aktywny.
+themes-use = Użyj tego motywu
+themes-end-preview = Zakończ podgląd
+themes-built-in = wbudowany
+theme-version = Wersja { $version }
+theme-author = autor: { $author }
+themes-active = Aktywny
+themes-activate = Aktywuj
+themes-custom-css = Własny CSS
+themes-custom-css-hint = Dodawany po aktywnym motywie, do drobnych poprawek.
+themes-save-custom-css = Zapisz własny CSS
+themes-add = Dodaj motyw
+themes-add-hint = Dodanie motywu pod nazwą już dodanego zastępuje go.
+themes-stylesheet = Arkusz stylów:
+themes-add-submit = Dodaj motyw
+
+## Users
+
+users-heading = Zarządzanie użytkownikami
+users-change-role = Zmień rolę
+users-create-heading = Utwórz nowego użytkownika
+users-create-submit = Utwórz użytkownika
+confirm-delete-user = Czy na pewno chcesz usunąć tego użytkownika?
@@ -0,0 +1,9 @@
+-- Campaign listings filter by status and date range and page by id; detail
+-- pages look up goals, updates and address changes by campaign.
+
+CREATE INDEX idx_campaigns_status ON campaigns(status, id);
+CREATE INDEX idx_campaigns_dates ON campaigns(start_date, end_date);
+CREATE INDEX idx_campaigns_creator ON campaigns(creator_id);
+CREATE INDEX idx_campaign_updates_campaign ON campaign_updates(campaign_id, created_at);
+CREATE INDEX idx_goals_campaign ON goals(campaign_id);
+CREATE INDEX idx_address_change_requests_campaign ON address_change_requests(campaign_id, status);
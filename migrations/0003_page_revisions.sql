@@ -0,0 +1,48 @@
+-- About and contact edits are kept as revisions; the entry holds the published
+-- text. Deleted entries stay in the trash until purged.
+
+ALTER TABLE aboutme ADD COLUMN published_revision_id INTEGER;
+ALTER TABLE aboutme ADD COLUMN deleted_at DATETIME;
+
+CREATE TABLE about_revisions (
+    id INTEGER PRIMARY KEY,
+    about_id INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    author_id INTEGER,
+    author_name TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(about_id) REFERENCES aboutme(id)
+);
+
+CREATE INDEX idx_about_revisions_about ON about_revisions(about_id, id);
+
+ALTER TABLE contact ADD COLUMN published_revision_id INTEGER;
+ALTER TABLE contact ADD COLUMN deleted_at DATETIME;
+
+CREATE TABLE contact_revisions (
+    id INTEGER PRIMARY KEY,
+    contact_id INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    admin_mail TEXT NOT NULL,
+    author_id INTEGER,
+    author_name TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(contact_id) REFERENCES contact(id)
+);
+
+CREATE INDEX idx_contact_revisions_contact ON contact_revisions(contact_id, id);
+
+-- Existing entries start with their current text as the first revision.
+INSERT INTO about_revisions (about_id, description, author_name)
+SELECT id, description, 'before revisions' FROM aboutme;
+
+UPDATE aboutme
+SET published_revision_id = (SELECT MAX(id) FROM about_revisions WHERE about_id = aboutme.id)
+WHERE active = 1;
+
+INSERT INTO contact_revisions (contact_id, description, admin_mail, author_name)
+SELECT id, description, admin_mail, 'before revisions' FROM contact;
+
+UPDATE contact
+SET published_revision_id = (SELECT MAX(id) FROM contact_revisions WHERE contact_id = contact.id)
+WHERE active = 1;
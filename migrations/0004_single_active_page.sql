@@ -0,0 +1,16 @@
+-- At most one about and one contact entry may be published. Databases that
+-- ended up with several keep the newest live one.
+
+UPDATE aboutme SET active = 0
+WHERE active = 1
+  AND (deleted_at IS NOT NULL
+       OR id != (SELECT MAX(id) FROM aboutme WHERE active = 1 AND deleted_at IS NULL));
+
+CREATE UNIQUE INDEX idx_aboutme_single_active ON aboutme(active) WHERE active = 1;
+
+UPDATE contact SET active = 0
+WHERE active = 1
+  AND (deleted_at IS NOT NULL
+       OR id != (SELECT MAX(id) FROM contact WHERE active = 1 AND deleted_at IS NULL));
+
+CREATE UNIQUE INDEX idx_contact_single_active ON contact(active) WHERE active = 1;
@@ -0,0 +1,13 @@
+-- Themes added from the dashboard, and site-wide settings such as the active
+-- theme and the custom CSS appended to it.
+
+CREATE TABLE IF NOT EXISTS themes (
+    name TEXT PRIMARY KEY,
+    css TEXT NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
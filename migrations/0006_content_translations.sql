@@ -0,0 +1,13 @@
+-- Per-language versions of campaign, update, about and contact text. The
+-- content's own columns hold the default language; languages without a row,
+-- and blank fields, fall back to them.
+
+CREATE TABLE content_translations (
+    content_type TEXT NOT NULL,
+    content_id INTEGER NOT NULL,
+    locale TEXT NOT NULL,
+    title TEXT NOT NULL DEFAULT '',
+    body TEXT NOT NULL DEFAULT '',
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (content_type, content_id, locale)
+);
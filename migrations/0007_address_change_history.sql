@@ -0,0 +1,29 @@
+-- Deleting a user keeps the payout address changes they requested or decided:
+-- the public address change notice is built from them.
+
+CREATE TABLE address_change_requests_new (
+    id INTEGER PRIMARY KEY,
+    campaign_id INTEGER NOT NULL,
+    old_address TEXT NOT NULL,
+    new_address TEXT NOT NULL,
+    requested_by INTEGER,
+    requested_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    status TEXT NOT NULL DEFAULT 'pending',
+    decided_by INTEGER,
+    decided_at DATETIME,
+    FOREIGN KEY(campaign_id) REFERENCES campaigns(id),
+    FOREIGN KEY(requested_by) REFERENCES users(id) ON DELETE SET NULL,
+    FOREIGN KEY(decided_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+INSERT INTO address_change_requests_new
+    (id, campaign_id, old_address, new_address, requested_by, requested_at, status, decided_by, decided_at)
+SELECT id, campaign_id, old_address, new_address,
+       (SELECT id FROM users WHERE id = requested_by), requested_at, status,
+       (SELECT id FROM users WHERE id = decided_by), decided_at
+FROM address_change_requests;
+
+DROP TABLE address_change_requests;
+ALTER TABLE address_change_requests_new RENAME TO address_change_requests;
+
+CREATE INDEX idx_address_change_requests_campaign ON address_change_requests(campaign_id, status);
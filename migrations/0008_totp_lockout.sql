@@ -0,0 +1,5 @@
+-- Failed second factor codes are counted per user rather than per login session,
+-- so starting a new login does not reset them.
+
+ALTER TABLE user_totp ADD COLUMN failed_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE user_totp ADD COLUMN locked_until DATETIME;
@@ -0,0 +1,93 @@
+use crate::auth::CurrentUser;
+use crate::db;
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+use serde_json::Value;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+/// Client address: the peer address, or nginx's `X-Real-IP` header when the peer is a trusted proxy.
+#[derive(Clone, Debug)]
+pub struct ClientIp(pub String);
+
+/// Whether `peer` is listed in `trusted_proxies`, a comma-separated list of addresses.
+fn is_trusted_proxy(peer: IpAddr, trusted_proxies: &str) -> bool {
+    trusted_proxies
+        .split(',')
+        .filter_map(|proxy| proxy.trim().parse::<IpAddr>().ok())
+        .any(|proxy| proxy == peer)
+}
+
+/// The client address of a request from `peer` carrying the `X-Real-IP` value `real_ip`.
+pub fn client_ip(peer: Option<IpAddr>, real_ip: Option<&str>, trusted_proxies: &str) -> String {
+    match (peer, real_ip) {
+        (Some(peer), Some(real_ip)) if is_trusted_proxy(peer, trusted_proxies) => real_ip.trim().to_string(),
+        (Some(peer), _) => peer.to_string(),
+        (None, _) => "unknown".to_string(),
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip());
+        let real_ip = parts.headers.get("x-real-ip").and_then(|value| value.to_str().ok());
+        let trusted_proxies = std::env::var("TRUSTED_PROXY").unwrap_or_default();
+        Ok(ClientIp(client_ip(peer, real_ip, &trusted_proxies)))
+    }
+}
+
+/// Appends an entry to the audit log. Failures are reported but never undo the action.
+#[allow(clippy::too_many_arguments)]
+pub async fn record(
+    pool: &db::Pool,
+    user: &CurrentUser,
+    ip: &ClientIp,
+    action: &str,
+    target_type: &str,
+    target_id: Option<i64>,
+    before: Option<Value>,
+    after: Option<Value>,
+) {
+    let entry = db::NewAuditEntry {
+        actor_id: Some(user.id),
+        actor_name: user.username.clone(),
+        action: action.to_string(),
+        target_type: target_type.to_string(),
+        target_id,
+        before,
+        after,
+        ip: ip.0.clone(),
+    };
+    write(pool, entry).await;
+}
+
+/// Records a login attempt, before a `CurrentUser` exists.
+pub async fn record_login(pool: &db::Pool, user_id: Option<i64>, username: &str, ip: &ClientIp, action: &str) {
+    let entry = db::NewAuditEntry {
+        actor_id: user_id,
+        actor_name: username.to_string(),
+        action: action.to_string(),
+        target_type: "user".to_string(),
+        target_id: user_id,
+        before: None,
+        after: None,
+        ip: ip.0.clone(),
+    };
+    write(pool, entry).await;
+}
+
+async fn write(pool: &db::Pool, entry: db::NewAuditEntry) {
+    let action = entry.action.clone();
+    if let Err(e) = db::run(pool, move |conn| db::insert_audit_entry(conn, &entry)).await {
+        eprintln!("Error: failed to write audit log entry for '{}': {}", action, e);
+    }
+}
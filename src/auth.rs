@@ -0,0 +1,167 @@
+use crate::i18n;
+use crate::models::Author;
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+pub const MIN_PASSWORD_LENGTH: usize = 12;
+const MAX_PASSWORD_LENGTH: usize = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// Full access, including managing other users.
+    Owner,
+    /// Creates campaigns and fully manages the ones they created.
+    Manager,
+    /// Edits pages and posts campaign updates, but never touches payout addresses.
+    Editor,
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::Owner, Role::Manager, Role::Editor];
+
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "owner" => Some(Role::Owner),
+            "manager" => Some(Role::Manager),
+            "editor" => Some(Role::Editor),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Owner => "owner",
+            Role::Manager => "manager",
+            Role::Editor => "editor",
+        }
+    }
+
+    /// The message naming the role.
+    pub fn message_key(&self) -> &'static str {
+        match self {
+            Role::Owner => "role-owner",
+            Role::Manager => "role-manager",
+            Role::Editor => "role-editor",
+        }
+    }
+
+    /// The role's name in the request's locale.
+    pub fn label(&self) -> String {
+        i18n::t(self.message_key())
+    }
+}
+
+/// The logged-in user, inserted into request extensions by `require_auth`.
+#[derive(Clone, Debug)]
+pub struct CurrentUser {
+    pub id: i64,
+    pub username: String,
+    pub role: Role,
+}
+
+impl CurrentUser {
+    pub fn author(&self) -> Author {
+        Author {
+            id: Some(self.id),
+            name: self.username.clone(),
+        }
+    }
+
+    pub fn can_manage_users(&self) -> bool {
+        self.role == Role::Owner
+    }
+
+    pub fn can_edit_pages(&self) -> bool {
+        matches!(self.role, Role::Owner | Role::Editor)
+    }
+
+    pub fn can_create_campaigns(&self) -> bool {
+        matches!(self.role, Role::Owner | Role::Manager)
+    }
+
+    /// Delete, amount and payout address changes.
+    pub fn can_manage_campaign(&self, creator_id: i64) -> bool {
+        match self.role {
+            Role::Owner => true,
+            Role::Manager => creator_id == self.id,
+            Role::Editor => false,
+        }
+    }
+
+    /// Campaign updates and translations; the campaign's own terms need `can_manage_campaign`.
+    pub fn can_edit_campaign_content(&self, creator_id: i64) -> bool {
+        self.role == Role::Editor || self.can_manage_campaign(creator_id)
+    }
+}
+
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default().hash_password(password.as_bytes(), &salt)?.to_string())
+}
+
+/// Checks a password against its stored value. Accounts created before passwords
+/// were hashed still hold plaintext, which `needs_rehash` reports so it can be upgraded.
+pub fn verify_password(stored: &str, password: &str) -> bool {
+    match PasswordHash::new(stored) {
+        Ok(hash) => Argon2::default().verify_password(password.as_bytes(), &hash).is_ok(),
+        Err(_) => stored == password,
+    }
+}
+
+pub fn needs_rehash(stored: &str) -> bool {
+    PasswordHash::new(stored).is_err()
+}
+
+pub fn check_username(username: &str) -> Result<(), String> {
+    let valid_chars = username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if username.is_empty() || username.len() > 32 || !valid_chars {
+        return Err(i18n::t("error-username-invalid"));
+    }
+    Ok(())
+}
+
+/// Returns a description of the first rule the password breaks.
+pub fn check_password_strength(password: &str, username: &str) -> Result<(), String> {
+    let length = password.chars().count();
+    if length < MIN_PASSWORD_LENGTH {
+        return Err(i18n::t_args("error-password-too-short", &[("min", MIN_PASSWORD_LENGTH.to_string())]));
+    }
+    if length > MAX_PASSWORD_LENGTH {
+        return Err(i18n::t_args("error-password-too-long", &[("max", MAX_PASSWORD_LENGTH.to_string())]));
+    }
+    if !username.is_empty() && password.to_lowercase().contains(&username.to_lowercase()) {
+        return Err(i18n::t("error-password-contains-username"));
+    }
+
+    let classes = [
+        password.chars().any(|c| c.is_lowercase()),
+        password.chars().any(|c| c.is_uppercase()),
+        password.chars().any(|c| c.is_numeric()),
+        password.chars().any(|c| !c.is_alphanumeric()),
+    ];
+    if classes.iter().filter(|present| **present).count() < 3 {
+        return Err(i18n::t("error-password-weak"));
+    }
+    Ok(())
+}
+
+/// A random 256-bit token, hex encoded.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Session and API tokens are stored as SHA-256 hashes; they are random, so no salt is needed.
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,163 @@
+use crate::db::{self, DatabaseError, Pool};
+use crate::migrations::{self, MIGRATIONS};
+use chrono::Utc;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use rusqlite::{Connection, DatabaseName};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Where snapshots go and how many are kept, read from the environment.
+#[derive(Clone, Debug)]
+pub struct BackupConfig {
+    pub dir: PathBuf,
+    pub compress: bool,
+    pub keep: usize,
+    /// `None` disables the scheduled job.
+    pub interval: Option<Duration>,
+}
+
+impl BackupConfig {
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let hours: u64 = var("BACKUP_INTERVAL_HOURS").and_then(|hours| hours.parse().ok()).unwrap_or(24);
+
+        BackupConfig {
+            dir: var("BACKUP_DIR").unwrap_or_else(|| "data/backups".to_string()).into(),
+            compress: var("BACKUP_COMPRESS").is_none_or(|value| value != "false" && value != "0"),
+            keep: var("BACKUP_KEEP").and_then(|keep| keep.parse().ok()).unwrap_or(14).max(1),
+            interval: (hours > 0).then(|| Duration::from_secs(hours * 60 * 60)),
+        }
+    }
+}
+
+const SNAPSHOT_PREFIX: &str = "snapshot-";
+
+fn is_snapshot(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with(SNAPSHOT_PREFIX) && (name.ends_with(".db") || name.ends_with(".db.gz")))
+}
+
+/// Copies the live database with the online backup API, so the server can keep
+/// serving while it runs, then prunes snapshots beyond `config.keep`.
+pub fn create_snapshot(conn: &Connection, config: &BackupConfig) -> Result<PathBuf, DatabaseError> {
+    fs::create_dir_all(&config.dir)?;
+    let name = format!("{}{}.db", SNAPSHOT_PREFIX, Utc::now().format("%Y%m%d-%H%M%S"));
+    let partial = config.dir.join(format!("{}.partial", name));
+    conn.backup(DatabaseName::Main, &partial, None)?;
+
+    let snapshot = if config.compress {
+        let snapshot = config.dir.join(format!("{}.gz", name));
+        let compressing = config.dir.join(format!("{}.gz.partial", name));
+        let mut encoder = GzEncoder::new(File::create(&compressing)?, Compression::default());
+        io::copy(&mut File::open(&partial)?, &mut encoder)?;
+        encoder.finish()?;
+        fs::remove_file(&partial)?;
+        fs::rename(&compressing, &snapshot)?;
+        snapshot
+    } else {
+        let snapshot = config.dir.join(name);
+        fs::rename(&partial, &snapshot)?;
+        snapshot
+    };
+
+    prune(config)?;
+    Ok(snapshot)
+}
+
+/// Removes the oldest snapshots so at most `config.keep` remain, returning what was removed.
+pub fn prune(config: &BackupConfig) -> Result<Vec<PathBuf>, DatabaseError> {
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(&config.dir)? {
+        let path = entry?.path();
+        if is_snapshot(&path) {
+            snapshots.push(path);
+        }
+    }
+    // Names embed a sortable timestamp, newest last.
+    snapshots.sort();
+
+    let excess = snapshots.len().saturating_sub(config.keep);
+    let removed: Vec<PathBuf> = snapshots.into_iter().take(excess).collect();
+    for path in &removed {
+        fs::remove_file(path)?;
+    }
+    Ok(removed)
+}
+
+/// Checks that `path` holds an intact database this build can run, returning its schema version.
+fn check_snapshot(path: &Path) -> Result<i64, DatabaseError> {
+    let conn = Connection::open(path)?;
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| DatabaseError::InvalidSnapshot(e.to_string()))?;
+    if integrity != "ok" {
+        return Err(DatabaseError::InvalidSnapshot(integrity));
+    }
+
+    let version = migrations::applied_versions(&conn)?
+        .last()
+        .map(|(version, _)| *version)
+        .ok_or_else(|| DatabaseError::InvalidSnapshot("no schema version recorded".to_string()))?;
+    let latest_known = MIGRATIONS.last().map(|migration| migration.version).unwrap_or(0);
+    if version > latest_known {
+        return Err(DatabaseError::SchemaTooNew(version));
+    }
+    Ok(version)
+}
+
+/// Replaces the database at `database_path` with `snapshot`. The server must be
+/// stopped; the current file and its WAL are kept next to it as `<name>.pre-restore-<timestamp>`.
+/// Older snapshots are migrated on the next start.
+pub fn restore(snapshot: &Path, database_path: &Path) -> Result<i64, DatabaseError> {
+    let staged = database_path.with_extension("restore");
+    if snapshot.extension().is_some_and(|extension| extension == "gz") {
+        io::copy(&mut GzDecoder::new(File::open(snapshot)?), &mut File::create(&staged)?)?;
+    } else {
+        fs::copy(snapshot, &staged)?;
+    }
+
+    let version = match check_snapshot(&staged) {
+        Ok(version) => version,
+        Err(e) => {
+            fs::remove_file(&staged)?;
+            return Err(e);
+        }
+    };
+
+    let set_aside = format!(".pre-restore-{}", Utc::now().format("%Y%m%d-%H%M%S"));
+    for suffix in ["", "-wal", "-shm"] {
+        let mut current = database_path.as_os_str().to_owned();
+        current.push(suffix);
+        if Path::new(&current).exists() {
+            let mut previous = database_path.as_os_str().to_owned();
+            previous.push(format!("{}{}", set_aside, suffix));
+            fs::rename(current, previous)?;
+        }
+    }
+    fs::rename(&staged, database_path)?;
+    Ok(version)
+}
+
+/// Takes a snapshot every `config.interval` in the background.
+pub fn spawn_scheduled(pool: Pool, config: BackupConfig) {
+    let Some(interval) = config.interval else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // The first tick completes immediately; wait a full interval before the first snapshot.
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let config = config.clone();
+            match db::run(&pool, move |conn| create_snapshot(conn, &config)).await {
+                Ok(path) => println!("Wrote database snapshot {}", path.display()),
+                Err(e) => eprintln!("Error: scheduled database backup failed: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,164 @@
+use crate::backup::{self, BackupConfig};
+use crate::db;
+use crate::migrations::{self, MIGRATIONS};
+use crate::repositories::SqliteRepository;
+use crate::transfer::{self, Bundle, ImportOptions, OnConflict};
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const USAGE: &str = "Usage: mrs-funding-software [migrate status | migrate up | backup | restore <snapshot> | \
+export [file] | import <file> [--dry-run] [--on-conflict skip|overwrite|duplicate]]";
+
+pub enum Command {
+    Serve,
+    MigrateStatus,
+    MigrateUp,
+    Backup,
+    Restore(PathBuf),
+    Export(Option<PathBuf>),
+    Import {
+        bundle: PathBuf,
+        dry_run: bool,
+        on_conflict: OnConflict,
+    },
+}
+
+impl Command {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        match args.as_slice() {
+            [] => Ok(Command::Serve),
+            ["migrate", "status"] => Ok(Command::MigrateStatus),
+            ["migrate", "up"] => Ok(Command::MigrateUp),
+            ["backup"] => Ok(Command::Backup),
+            ["restore", snapshot] => Ok(Command::Restore(PathBuf::from(snapshot))),
+            ["export"] => Ok(Command::Export(None)),
+            ["export", file] => Ok(Command::Export(Some(PathBuf::from(file)))),
+            ["import", bundle, flags @ ..] => {
+                let mut dry_run = false;
+                let mut on_conflict = OnConflict::Skip;
+                let mut flags = flags.iter();
+                while let Some(flag) = flags.next() {
+                    match *flag {
+                        "--dry-run" => dry_run = true,
+                        "--on-conflict" => match flags.next().map(|mode| mode.parse()) {
+                            Some(Ok(mode)) => on_conflict = mode,
+                            Some(Err(e)) => bail!(e),
+                            None => bail!(USAGE),
+                        },
+                        _ => bail!(USAGE),
+                    }
+                }
+                Ok(Command::Import {
+                    bundle: PathBuf::from(bundle),
+                    dry_run,
+                    on_conflict,
+                })
+            }
+            _ => bail!(USAGE),
+        }
+    }
+}
+
+pub fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Serve => Ok(()),
+        Command::MigrateStatus => migrate_status(),
+        Command::MigrateUp => migrate_up(),
+        Command::Backup => backup(),
+        Command::Restore(snapshot) => restore(&snapshot),
+        Command::Export(file) => export(file.as_deref()),
+        Command::Import {
+            bundle,
+            dry_run,
+            on_conflict,
+        } => import(&bundle, dry_run, on_conflict),
+    }
+}
+
+fn migrate_status() -> Result<()> {
+    let conn = db::create_pool(&db::database_url())?.get()?;
+    let applied = migrations::applied_versions(&conn)?;
+
+    for migration in MIGRATIONS {
+        match applied.iter().find(|(version, _)| *version == migration.version) {
+            Some((_, applied_at)) => println!("{:04} {:<30} applied {}", migration.version, migration.name, applied_at),
+            None => println!("{:04} {:<30} pending", migration.version, migration.name),
+        }
+    }
+    for (version, applied_at) in applied.iter().filter(|(version, _)| !MIGRATIONS.iter().any(|m| m.version == *version)) {
+        println!("{:04} {:<30} applied {} (unknown to this build)", version, "?", applied_at);
+    }
+    Ok(())
+}
+
+fn migrate_up() -> Result<()> {
+    let applied = db::setup_database(&db::create_pool(&db::database_url())?)?;
+    if applied.is_empty() {
+        println!("Database schema is already up to date");
+    }
+    for version in applied {
+        println!("Applied migration {:04}", version);
+    }
+    Ok(())
+}
+
+fn backup() -> Result<()> {
+    let conn = db::create_pool(&db::database_url())?.get()?;
+    let snapshot = backup::create_snapshot(&conn, &BackupConfig::from_env())?;
+    println!("Wrote database snapshot {}", snapshot.display());
+    Ok(())
+}
+
+fn restore(snapshot: &Path) -> Result<()> {
+    let database_url = db::database_url();
+    if database_url == ":memory:" {
+        bail!("Cannot restore into an in-memory database");
+    }
+    let version = backup::restore(snapshot, Path::new(&database_url))?;
+    println!("Restored {} (schema version {:04}) to {}", snapshot.display(), version, database_url);
+    Ok(())
+}
+
+fn export(file: Option<&Path>) -> Result<()> {
+    let conn = db::create_pool(&db::database_url())?.get()?;
+    let json = serde_json::to_string_pretty(&transfer::export(&SqliteRepository::new(&conn))?)?;
+    match file {
+        Some(file) => {
+            fs::write(file, json)?;
+            println!("Exported site content to {}", file.display());
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+fn import(file: &Path, dry_run: bool, on_conflict: OnConflict) -> Result<()> {
+    let bundle: Bundle = serde_json::from_str(&fs::read_to_string(file)?)
+        .with_context(|| format!("{} is not an export bundle", file.display()))?;
+
+    let pool = db::create_pool(&db::database_url())?;
+    db::setup_database(&pool)?;
+    let mut conn = pool.get()?;
+    let Some(creator_id) = db::first_owner_id(&conn)? else {
+        bail!("Create an owner account before importing campaigns");
+    };
+
+    if !dry_run {
+        let snapshot = backup::create_snapshot(&conn, &BackupConfig::from_env())?;
+        println!("Wrote database snapshot {} before importing", snapshot.display());
+    }
+    let options = ImportOptions {
+        on_conflict,
+        dry_run,
+        creator_id,
+    };
+    for line in transfer::import_database(&mut conn, &bundle, &options)? {
+        println!("{}", line);
+    }
+    if dry_run {
+        println!("Dry run, nothing was written");
+    }
+    Ok(())
+}
@@ -1,212 +1,466 @@
-use crate::{db::get_connection, render_page_or_error};
+use crate::audit::{self, ClientIp};
+use crate::auth::CurrentUser;
+use crate::db::{self, Pool};
+use crate::error::AppError;
+use crate::i18n;
+use crate::markdown;
+use crate::render_page_or_error;
+use crate::repositories::{localize_description, AboutRepository, SqliteRepository, TranslationRepository};
 use crate::views::layout::render_layout;
+use crate::views::templates::{self, filters, view};
 use axum::{
-    extract::{Form, Path},
-    response::{Html, Redirect},
+    extract::{Extension, Form, Path, Query, State},
+    response::{Html, IntoResponse, Redirect, Response},
 };
 use axum_csrf::CsrfToken;
-use hyper::StatusCode;
-use rusqlite::params;
 use serde::Deserialize;
+use serde_json::json;
 use crate::controllers::page::Page;
+use crate::controllers::translations::{stored_translation, tabs, translation_form, TranslationQuery, TranslationSource, TranslationTab};
+use crate::controllers::revisions::{diff_content, revisions_content, trash_content, DiffQuery, RevisionSummary};
+use crate::models::{AboutEntry, AboutRevision, TranslatedContent};
+use tower_sessions::Session;
+
+const BASE: &str = "/protected/about";
 
 #[derive(Deserialize)]
 pub struct AboutForm {
     description: String,
+    /// Set by the form's Preview button: render the text instead of saving it.
+    #[serde(default)]
+    preview: Option<String>,
+}
+
+fn validate_description(description: &str) -> Option<String> {
+    description.trim().is_empty().then_some(i18n::t("error-description-empty"))
 }
 
-pub async fn about(session: &tower_sessions::Session) -> Html<String> {
+view! {
+    "about/new.html",
+    struct NewAboutView<'a> {
+        csrf_token: &'a str,
+        description: &'a str,
+        error: &'a str,
+        preview: &'a str,
+    }
+}
+
+view! {
+    "about/details.html",
+    struct AboutDetailsView<'a> {
+        csrf_token: &'a str,
+        id: i64,
+        tabs: Vec<TranslationTab>,
+        /// The translation form; empty on the default language's tab.
+        translation: String,
+        description: &'a str,
+        error: &'a str,
+        preview: &'a str,
+        history: &'a str,
+    }
+}
+
+view! {
+    "about/public.html",
+    struct AboutView {
+        description: String,
+    }
+}
+
+view! {
+    "about/all.html",
+    struct AboutListView {
+        entries: Vec<AboutEntry>,
+    }
+}
+
+fn new_about_content(token_str: &str, description: &str, error: Option<&str>, preview: &str) -> String {
+    templates::render(&NewAboutView { csrf_token: token_str, description, error: error.unwrap_or(""), preview })
+}
+
+fn about_details_content(
+    token_str: &str,
+    id: i64,
+    description: &str,
+    error: Option<&str>,
+    preview: &str,
+    history: &str,
+) -> String {
+    templates::render(&AboutDetailsView {
+        csrf_token: token_str,
+        id,
+        tabs: tabs(&format!("{}/{}", BASE, id), i18n::DEFAULT_LOCALE),
+        translation: String::new(),
+        description,
+        error: error.unwrap_or(""),
+        preview,
+        history,
+    })
+}
+
+pub fn about_content(repository: &(impl AboutRepository + TranslationRepository)) -> String {
+    let description = repository
+        .active()
+        .ok()
+        .flatten()
+        .map(|mut entry| {
+            localize_description(repository, TranslatedContent::About, entry.id, &mut entry.description, i18n::current());
+            entry.description
+        })
+        .unwrap_or_else(|| i18n::t("about-placeholder"));
+
+    templates::render(&AboutView { description: markdown::render(&description) })
+}
+
+pub async fn about(pool: &Pool, session: &Session) -> Html<String> {
     let mut page = Page::new("About Us 👥", session)
         .with_meta_description("Learn more about our crowdfunding platform");
 
-    let conn = get_connection().unwrap();
-    let description = conn
-        .query_row(
-            "SELECT description FROM aboutme WHERE active = 1",
-            [],
-            |row| row.get(0),
-        )
-        .unwrap_or_else(|_| "Please configure about me in dashboard".to_string());
-
-    let content = format!(
-        r#"
-        <section>
-            <h2>About Us</h2>
-            <div class="about-content">{}</div>
-        </section>
-        "#,
-        description
-    );
-
-    page.set_content(content);
+    match db::run(pool, |conn| Ok(about_content(&SqliteRepository::new(conn)))).await {
+        Ok(content) => page.set_content(content),
+        Err(_) => return Html(String::from("Error loading about page")),
+    }
     page.render().await.unwrap_or_else(|_| Html(String::from("Error loading about page")))
 }
 
 pub async fn about_create(
     csrf_token: CsrfToken,
-    session: &tower_sessions::Session,
+    session: &Session,
 ) -> Html<String> {
     let mut page = Page::new("Create New About Entry", session)
         .with_csrf_token(csrf_token)
         .with_meta_description("Create a new about page entry");
 
-    let content = format!(
-        r#"
-        <section>
-            <h2>Create New About Entry</h2>
-            <h3>You can use HTML in the description.</h3>
-            <form method="POST" action="/protected/about/new">
-                <input type="hidden" name="csrf_token" value="{}">
-                <textarea name="description" required></textarea>
-                <button type="submit">Create</button>
-            </form>
-        </section>
-        "#,        
-        page.get_csrf_token().unwrap_or(&String::new())
-    );
-
+    let content = new_about_content(page.get_csrf_token().map(String::as_str).unwrap_or(""), "", None, "");
     page.set_content(content);
     render_page_or_error!(page, "about page")
 }
-pub async fn about_insert_created(Form(form): Form<AboutForm>) -> Result<Redirect, StatusCode> {
-    let conn = get_connection().unwrap();
+pub async fn about_insert_created(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    csrf_token: CsrfToken,
+    session: Session,
+    Form(form): Form<AboutForm>,
+) -> Result<Response, AppError> {
+    if let Some(error) = validate_description(&form.description) {
+        let token_str = csrf_token.authenticity_token().unwrap_or_default();
+        let content = new_about_content(&token_str, &form.description, Some(&error), "");
+        return Err(AppError::invalid_form(&content, &session).await);
+    }
+    if form.preview.is_some() {
+        let token_str = csrf_token.authenticity_token().unwrap_or_default();
+        let content = new_about_content(&token_str, &form.description, None, &markdown::render(&form.description));
+        return Ok(render_layout(&content, &session).await.into_response());
+    }
+
+    let (description, author) = (form.description.clone(), user.author());
+    let id = db::run(&pool, move |conn| SqliteRepository::new(conn).insert(&description, &author)).await?;
 
-    conn.execute(
-        "INSERT INTO aboutme (description, active) VALUES (?, 0)",
-        [&form.description],
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "about.create",
+        "about",
+        Some(id),
+        None,
+        Some(json!({ "description": form.description, "active": false })),
     )
-    .unwrap();
+    .await;
 
-    Ok(Redirect::to("/protected/about/all"))
+    Ok(Redirect::to("/protected/about/all").into_response())
 }
 
-fn get_all_about_records() -> Vec<(i64, String, bool)> {
-    let conn = get_connection().unwrap();
-    let mut stmt = conn
-        .prepare("SELECT id, description, active FROM aboutme ORDER BY id DESC")
-        .unwrap();
-
-    stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
-        .unwrap()
-        .map(|r| r.unwrap())
-        .collect()
-}
-
-pub async fn about_all(session: &tower_sessions::Session) -> Result<Html<String>, StatusCode> {
-    let about_list = get_all_about_records();
-
-    let content = format!(
-        r#"
-        <section>
-            <h2>All About Entries</h2>
-            <div class="about-entries">
-                {}
-            </div>
-            <a href="/protected/about/new" class="button">Create New Entry</a>
-        </section>
-        "#,
-        about_list.iter()
-            .map(|(id, desc, active)| format!(
-                "<div class='entry'><p><a href='/protected/about/{}'>{}</a></p><p>{}</p><p>Status: {}</p></div>",
-                id, id, desc, if *active { "Active" } else { "Inactive" }
-            ))
-            .collect::<String>()
-    );
+pub async fn about_all(pool: &Pool, session: &Session) -> Result<Html<String>, AppError> {
+    let mut about_list = db::run(pool, |conn| AboutRepository::list(&SqliteRepository::new(conn))).await?;
+    for entry in &mut about_list {
+        entry.description = markdown::render(&entry.description);
+    }
+
+    let content = templates::render(&AboutListView { entries: about_list });
 
     let rendered = render_layout(&content, session).await;
     Ok(rendered)
 }
-fn select_from_id(id: i64) -> (i64, String, bool) {
-    let conn = get_connection().unwrap();
-    conn.query_row(
-        "SELECT id, description, active FROM aboutme WHERE id = ?",
-        [id],
-        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-    )
-    .unwrap()
+/// Loads an entry that hasn't been moved to the trash.
+async fn select_from_id(pool: &Pool, id: i64) -> Result<AboutEntry, AppError> {
+    db::run(pool, move |conn| AboutRepository::get(&SqliteRepository::new(conn), id))
+        .await?
+        .filter(|entry| entry.deleted_at.is_none())
+        .ok_or(AppError::NotFound)
+}
+
+/// Loads a revision of entry `id`.
+async fn select_revision(pool: &Pool, id: i64, revision_id: i64) -> Result<AboutRevision, AppError> {
+    db::run(pool, move |conn| SqliteRepository::new(conn).revision(revision_id))
+        .await?
+        .filter(|revision| revision.about_id == id)
+        .ok_or(AppError::NotFound)
+}
+
+async fn history_content(pool: &Pool, about: &AboutEntry, token_str: &str) -> Result<(String, String), AppError> {
+    let id = about.id;
+    let revisions = db::run(pool, move |conn| SqliteRepository::new(conn).revisions(id)).await?;
+    let summaries: Vec<RevisionSummary> = revisions
+        .iter()
+        .map(|revision| RevisionSummary { id: revision.id, author_name: &revision.author_name, created_at: &revision.created_at })
+        .collect();
+    let latest = revisions.first().map_or_else(|| about.description.clone(), |revision| revision.description.clone());
+    let history = revisions_content(BASE, id, token_str, about.published_revision_id, about.active, &summaries);
+    Ok((latest, history))
+}
+
+/// The `locale` tab of the details screen, translating the published text.
+async fn translation_content(pool: &Pool, about: AboutEntry, locale: &'static str, token_str: &str) -> Result<String, AppError> {
+    let id = about.id;
+    let translation =
+        db::run(pool, move |conn| stored_translation(&SqliteRepository::new(conn), TranslatedContent::About, id, locale)).await?;
+    let action = format!("{}/{}/translations", BASE, id);
+    let source = TranslationSource {
+        action: &action,
+        update_id: None,
+        heading: String::new(),
+        title: None,
+        body_label: "field-description",
+        body: &about.description,
+    };
+    Ok(templates::render(&AboutDetailsView {
+        csrf_token: token_str,
+        id,
+        tabs: tabs(&format!("{}/{}", BASE, id), locale),
+        translation: translation_form(token_str, &source, translation),
+        description: "",
+        error: "",
+        preview: "",
+        history: "",
+    }))
 }
 
 pub async fn about_details(
+    pool: &Pool,
     Path(id): Path<i64>,
+    Query(query): Query<TranslationQuery>,
     csrf_token: CsrfToken,
-    session: &tower_sessions::Session,
-) -> Result<Html<String>, StatusCode> {
-    let about = select_from_id(id);
-    let token_str = csrf_token.authenticity_token().unwrap();
-
-    let content = format!(
-        r#"
-        <section>
-            <h2>About Entry Details</h2>
-            <h3>You can use HTML in the description.</h3>
-            <div class="about-entry">
-                <form method="POST" action="/protected/about/{}/update">
-                    <input type="hidden" name="csrf_token" value="{}">
-                    <textarea name="description" required>{}</textarea>
-                    <label>
-                        <input type="checkbox" name="active" value="1" {}>
-                        Active
-                    </label>
-                    <button type="submit">Update</button>
-                </form>
-                <form method="POST" action="/protected/about/{}/delete" 
-                    onsubmit="return confirm('Are you sure?')">
-                    <input type="hidden" name="csrf_token" value="{}">
-                    <button type="submit" class="delete">Delete</button>
-                </form>
-            </div>
-        </section>
-        "#,
-        about.0,
-        token_str,
-        about.1,
-        if about.2 { "checked" } else { "" },
-        about.0,
-        token_str
-    );
+    session: &Session,
+) -> Result<Html<String>, AppError> {
+    let about = select_from_id(pool, id).await?;
+    let token_str = csrf_token.authenticity_token().unwrap_or_default();
+    let content = if query.is_default() {
+        let (latest, history) = history_content(pool, &about, &token_str).await?;
+        about_details_content(&token_str, about.id, &latest, None, "", &history)
+    } else {
+        translation_content(pool, about, query.locale(), &token_str).await?
+    };
     let rendered = render_layout(&content, session).await;
     Ok(rendered)
 }
+
+/// Saves the form as a new revision; what visitors see only changes on publish.
 pub async fn about_update(
+    State(pool): State<Pool>,
     Path(id): Path<i64>,
-    Form(form): Form<AboutUpdateForm>,
-) -> Result<Redirect, StatusCode> {
-    let conn = get_connection().unwrap();
-
-    if form.active {
-        conn.execute("UPDATE aboutme SET active = 0 WHERE active = 1", [])
-            .unwrap();
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    csrf_token: CsrfToken,
+    session: Session,
+    Form(form): Form<AboutForm>,
+) -> Result<Response, AppError> {
+    let about = select_from_id(&pool, id).await?;
+    if let Some(error) = validate_description(&form.description) {
+        let token_str = csrf_token.authenticity_token().unwrap_or_default();
+        let (_, history) = history_content(&pool, &about, &token_str).await?;
+        let content = about_details_content(&token_str, id, &form.description, Some(&error), "", &history);
+        return Err(AppError::invalid_form(&content, &session).await);
     }
+    if form.preview.is_some() {
+        let token_str = csrf_token.authenticity_token().unwrap_or_default();
+        let (_, history) = history_content(&pool, &about, &token_str).await?;
+        let preview = markdown::render(&form.description);
+        let content = about_details_content(&token_str, id, &form.description, None, &preview, &history);
+        return Ok(render_layout(&content, &session).await.into_response());
+    }
+
+    let (description, author) = (form.description.clone(), user.author());
+    let revision_id = db::run(&pool, move |conn| SqliteRepository::new(conn).save(id, &description, &author)).await?;
 
-    conn.execute(
-        "UPDATE aboutme SET description = ?, active = ? WHERE id = ?",
-        params![form.description, form.active, id],
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "about.save",
+        "about",
+        Some(id),
+        None,
+        Some(json!({ "revision_id": revision_id, "description": form.description })),
     )
-    .unwrap();
+    .await;
 
-    Ok(Redirect::to("/protected/about/all"))
+    Ok(Redirect::to(&format!("{}/{}", BASE, id)).into_response())
+}
+
+/// Makes `revision_id` the published about page, replacing whichever entry was live.
+pub async fn about_publish(
+    State(pool): State<Pool>,
+    Path((id, revision_id)): Path<(i64, i64)>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let before = select_from_id(&pool, id).await?;
+    select_revision(&pool, id, revision_id).await?;
+    db::run(&pool, move |conn| SqliteRepository::new(conn).publish(id, revision_id)).await?;
+
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "about.publish",
+        "about",
+        Some(id),
+        Some(json!({ "active": before.active, "published_revision_id": before.published_revision_id })),
+        Some(json!({ "active": true, "published_revision_id": revision_id })),
+    )
+    .await;
+    Ok(Redirect::to(&format!("{}/{}", BASE, id)))
+}
+
+/// Copies an old revision into a new one, publishing it if the entry is live.
+pub async fn about_restore(
+    State(pool): State<Pool>,
+    Path((id, revision_id)): Path<(i64, i64)>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let about = select_from_id(&pool, id).await?;
+    let revision = select_revision(&pool, id, revision_id).await?;
+    let (active, author) = (about.active, user.author());
+    let restored = db::run(&pool, move |conn| {
+        let repository = SqliteRepository::new(conn);
+        let restored = repository.save(id, &revision.description, &author)?;
+        if active {
+            repository.publish(id, restored)?;
+        }
+        Ok(restored)
+    })
+    .await?;
+
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "about.restore",
+        "about",
+        Some(id),
+        Some(json!({ "revision_id": revision_id })),
+        Some(json!({ "revision_id": restored, "published": active })),
+    )
+    .await;
+    Ok(Redirect::to(&format!("{}/{}", BASE, id)))
+}
+
+pub async fn about_unpublish(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let before = select_from_id(&pool, id).await?;
+    db::run(&pool, move |conn| SqliteRepository::new(conn).unpublish(id)).await?;
+
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "about.unpublish",
+        "about",
+        Some(id),
+        Some(json!({ "active": before.active })),
+        Some(json!({ "active": false })),
+    )
+    .await;
+    Ok(Redirect::to(&format!("{}/{}", BASE, id)))
+}
+
+pub async fn about_diff(
+    pool: &Pool,
+    Path(id): Path<i64>,
+    Query(query): Query<DiffQuery>,
+    session: &Session,
+) -> Result<Html<String>, AppError> {
+    select_from_id(pool, id).await?;
+    let old = select_revision(pool, id, query.from).await?;
+    let new = select_revision(pool, id, query.to).await?;
+
+    let content = diff_content(BASE, id, old.id, new.id, &old.description, &new.description);
+    Ok(render_layout(&content, session).await)
 }
 
-pub async fn about_delete(Path(id): Path<i64>) -> Result<Redirect, StatusCode> {
-    let conn = get_connection().unwrap();
-    conn.execute("DELETE FROM aboutme WHERE id = ?", [id])
-        .unwrap();
+/// Moves the entry to the trash; it stops being published and can be restored from there.
+pub async fn about_delete(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let before = select_from_id(&pool, id).await?;
+    db::run(&pool, move |conn| AboutRepository::delete(&SqliteRepository::new(conn), id)).await?;
+
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "about.delete",
+        "about",
+        Some(id),
+        Some(json!(before)),
+        None,
+    )
+    .await;
     Ok(Redirect::to("/protected/about/all"))
 }
-#[derive(Deserialize)]
-pub struct AboutUpdateForm {
-    description: String,
-    #[serde(default)]
-    #[serde(deserialize_with = "deserialize_checkbox")]
-    active: bool,
+
+pub async fn about_trash(pool: &Pool, csrf_token: CsrfToken, session: &Session) -> Result<Html<String>, AppError> {
+    let entries = db::run(pool, |conn| SqliteRepository::new(conn).trash()).await?;
+    let token_str = csrf_token.authenticity_token().unwrap_or_default();
+    let entries: Vec<(i64, String)> =
+        entries.into_iter().map(|entry| (entry.id, entry.deleted_at.unwrap_or_default())).collect();
+
+    let content = trash_content(BASE, &token_str, &entries);
+    Ok(render_layout(&content, session).await)
 }
 
-fn deserialize_checkbox<'de, D>(deserializer: D) -> Result<bool, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    match String::deserialize(deserializer) {
-        Ok(string) => Ok(string == "1" || string == "on" || string == "true"),
-        Err(_) => Ok(false),
-    }
+/// Loads an entry from the trash.
+async fn select_deleted(pool: &Pool, id: i64) -> Result<AboutEntry, AppError> {
+    db::run(pool, move |conn| AboutRepository::get(&SqliteRepository::new(conn), id))
+        .await?
+        .filter(|entry| entry.deleted_at.is_some())
+        .ok_or(AppError::NotFound)
+}
+
+/// Takes an entry out of the trash as an unpublished draft.
+pub async fn about_undelete(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    select_deleted(&pool, id).await?;
+    db::run(&pool, move |conn| SqliteRepository::new(conn).undelete(id)).await?;
+
+    audit::record(&pool, &user, &ip, "about.undelete", "about", Some(id), None, None).await;
+    Ok(Redirect::to(&format!("{}/{}", BASE, id)))
+}
+
+/// Permanently removes a trashed entry and its revisions.
+pub async fn about_purge(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let before = select_deleted(&pool, id).await?;
+    db::run(&pool, move |conn| SqliteRepository::new(conn).purge(id)).await?;
+
+    audit::record(&pool, &user, &ip, "about.purge", "about", Some(id), Some(json!(before)), None).await;
+    Ok(Redirect::to("/protected/about/trash"))
 }
@@ -0,0 +1,240 @@
+use crate::audit::{self, ClientIp};
+use crate::auth::{self, CurrentUser};
+use crate::controllers::totp::two_factor_section;
+use crate::db::Pool;
+use crate::error::AppError;
+use crate::i18n;
+use crate::views::templates::{self, filters, view};
+use crate::{controllers::page::Page, db, render_page_or_error, sessions};
+use axum::{
+    extract::{Extension, Form, Path, State},
+    http::StatusCode,
+    response::{Html, Redirect},
+};
+use axum_csrf::CsrfToken;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tower_sessions::Session;
+
+const NEW_API_TOKEN_KEY: &str = "new_api_token";
+
+#[derive(Serialize)]
+struct TokenRow {
+    id: i64,
+    name: String,
+    created_at: String,
+    /// Empty if the token was never used.
+    last_used_at: String,
+}
+
+view! {
+    "account/api_tokens.html",
+    struct ApiTokensView<'a> {
+        csrf_token: &'a str,
+        /// Shown once right after creation, empty otherwise.
+        new_token: String,
+        tokens: Vec<TokenRow>,
+    }
+}
+
+view! {
+    "account/page.html",
+    struct AccountView<'a> {
+        username: &'a str,
+        role: String,
+        min_password_length: usize,
+        csrf_token: &'a str,
+        two_factor: &'a str,
+        api_tokens: &'a str,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PasswordForm {
+    current_password: String,
+    new_password: String,
+    confirm_password: String,
+}
+
+#[derive(Deserialize)]
+pub struct UsernameForm {
+    username: String,
+    current_password: String,
+}
+
+#[derive(Deserialize)]
+pub struct ApiTokenForm {
+    name: String,
+}
+
+async fn check_current_password(pool: &Pool, user: &CurrentUser, password: &str) -> Result<(), AppError> {
+    let (user_id, password) = (user.id, password.to_string());
+    let password_ok = db::run(pool, move |conn| {
+        Ok(db::get_user_password(conn, user_id)?.is_some_and(|stored| auth::verify_password(&stored, &password)))
+    })
+    .await?;
+    if password_ok {
+        Ok(())
+    } else {
+        Err(AppError::Rejected(StatusCode::FORBIDDEN, i18n::t("error-current-password")))
+    }
+}
+
+async fn api_tokens_section(pool: &Pool, user: &CurrentUser, token_str: &str, new_token: Option<String>) -> String {
+    let user_id = user.id;
+    let tokens = db::run(pool, move |conn| db::get_api_tokens(conn, user_id))
+        .await
+        .unwrap_or_else(|_| vec![]);
+    let tokens = tokens
+        .into_iter()
+        .map(|token| TokenRow {
+            id: token.id,
+            name: token.name,
+            created_at: token.created_at.to_string(),
+            last_used_at: token.last_used_at.map(|used| used.to_string()).unwrap_or_default(),
+        })
+        .collect();
+
+    templates::render(&ApiTokensView { csrf_token: token_str, new_token: new_token.unwrap_or_default(), tokens })
+}
+
+pub async fn account_page(pool: &Pool, user: &CurrentUser, csrf_token: CsrfToken, session: &Session) -> Html<String> {
+    let mut page = Page::new("Account Settings", session)
+        .with_csrf_token(csrf_token)
+        .with_meta_description("Manage your password, username, 2FA and API tokens");
+    let token_str = page.get_csrf_token().cloned().unwrap_or_default();
+
+    let new_token = session.remove::<String>(NEW_API_TOKEN_KEY).await.unwrap_or(None);
+    let two_factor = two_factor_section(pool, user, &token_str).await;
+    let api_tokens = api_tokens_section(pool, user, &token_str, new_token).await;
+
+    let content = templates::render(&AccountView {
+        username: &user.username,
+        role: user.role.label(),
+        min_password_length: auth::MIN_PASSWORD_LENGTH,
+        csrf_token: &token_str,
+        two_factor: &two_factor,
+        api_tokens: &api_tokens,
+    });
+
+    page.set_content(content);
+    render_page_or_error!(page, "Account Settings")
+}
+
+pub async fn change_password(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    session: Session,
+    Form(form): Form<PasswordForm>,
+) -> Result<Redirect, AppError> {
+    check_current_password(&pool, &user, &form.current_password).await?;
+    if form.new_password != form.confirm_password {
+        return Err(AppError::Rejected(StatusCode::BAD_REQUEST, i18n::t("error-passwords-mismatch")));
+    }
+    if form.new_password == form.current_password {
+        return Err(AppError::Rejected(StatusCode::BAD_REQUEST, i18n::t("error-password-unchanged")));
+    }
+    auth::check_password_strength(&form.new_password, &user.username).map_err(|e| AppError::Rejected(StatusCode::BAD_REQUEST, e))?;
+
+    let current = sessions::current_token_hash(&session).await;
+    let (user_id, new_password) = (user.id, form.new_password);
+    let revoked = db::run(&pool, move |conn| {
+        let password_hash = auth::hash_password(&new_password)
+            .map_err(|e| db::DatabaseError::PasswordHash(e.to_string()))?;
+        db::update_user_password(conn, user_id, &password_hash)?;
+        db::delete_other_user_sessions(conn, user_id, current.as_deref())
+    })
+    .await?;
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "user.password_change",
+        "user",
+        Some(user.id),
+        None,
+        Some(json!({ "revoked_sessions": revoked })),
+    )
+    .await;
+    Ok(Redirect::to("/protected/account"))
+}
+
+pub async fn change_username(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    Form(form): Form<UsernameForm>,
+) -> Result<Redirect, AppError> {
+    check_current_password(&pool, &user, &form.current_password).await?;
+    let username = form.username.trim().to_string();
+    auth::check_username(&username).map_err(|e| AppError::Rejected(StatusCode::BAD_REQUEST, e))?;
+    if username == user.username {
+        return Ok(Redirect::to("/protected/account"));
+    }
+
+    let (user_id, new_username) = (user.id, username.clone());
+    let changed = db::run(&pool, move |conn| {
+        if db::username_exists(conn, &new_username)? {
+            return Ok(false);
+        }
+        db::update_username(conn, user_id, &new_username)?;
+        Ok(true)
+    })
+    .await?;
+    if !changed {
+        return Err(AppError::Rejected(StatusCode::CONFLICT, i18n::t("error-username-taken")));
+    }
+
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "user.username_change",
+        "user",
+        Some(user.id),
+        Some(json!({ "username": user.username })),
+        Some(json!({ "username": username })),
+    )
+    .await;
+    Ok(Redirect::to("/protected/account"))
+}
+
+pub async fn create_api_token(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    session: Session,
+    Form(form): Form<ApiTokenForm>,
+) -> Result<Redirect, AppError> {
+    let name = form.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > 64 {
+        return Err(AppError::Rejected(StatusCode::BAD_REQUEST, i18n::t("error-token-name")));
+    }
+
+    let token = auth::generate_token();
+    let (user_id, token_name, token_hash) = (user.id, name.clone(), auth::hash_token(&token));
+    let id = db::run(&pool, move |conn| db::insert_api_token(conn, user_id, &token_name, &token_hash))
+        .await?;
+    session
+        .insert(NEW_API_TOKEN_KEY, token)
+        .await?;
+    audit::record(&pool, &user, &ip, "api_token.create", "api_token", Some(id), None, Some(json!({ "name": name }))).await;
+    Ok(Redirect::to("/protected/account"))
+}
+
+pub async fn revoke_api_token(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let user_id = user.id;
+    let deleted = db::run(&pool, move |conn| db::delete_api_token(conn, id, user_id))
+        .await?;
+    if !deleted {
+        return Err(AppError::Rejected(StatusCode::NOT_FOUND, i18n::t("error-token-not-found")));
+    }
+    audit::record(&pool, &user, &ip, "api_token.revoke", "api_token", Some(id), None, None).await;
+    Ok(Redirect::to("/protected/account"))
+}
@@ -0,0 +1,197 @@
+use crate::audit::{self, ClientIp};
+use crate::auth::{self, CurrentUser};
+use crate::db::Pool;
+use crate::error::AppError;
+use crate::i18n;
+use crate::repositories::{CampaignRepository, SqliteRepository};
+use crate::views::templates::{self, filters, view};
+use crate::{controllers::page::Page, db, render_page_or_error};
+use axum::{
+    extract::{Extension, Form, Path, State},
+    http::StatusCode,
+    response::{Html, Redirect},
+};
+use axum_csrf::CsrfToken;
+use chrono::{Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use tower_sessions::Session;
+
+#[derive(Serialize)]
+struct AddressChangeRow {
+    id: i64,
+    title: String,
+    old_address: String,
+    new_address: String,
+    requested_by_name: String,
+    requested_at: String,
+    /// The requester may only approve with their password, and only after the cooldown.
+    can_approve: bool,
+    needs_password: bool,
+    available_at: String,
+}
+
+view! {
+    "address_changes.html",
+    struct AddressChangesView<'a> {
+        csrf_token: &'a str,
+        changes: Vec<AddressChangeRow>,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ApprovalForm {
+    #[serde(default)]
+    password: String,
+}
+
+fn self_approval_cooldown() -> Duration {
+    let hours = std::env::var("ADDRESS_CHANGE_COOLDOWN_HOURS")
+        .ok()
+        .and_then(|hours| hours.parse().ok())
+        .unwrap_or(24);
+    Duration::hours(hours)
+}
+
+pub fn address_change_notice_period() -> Duration {
+    let days = std::env::var("ADDRESS_CHANGE_NOTICE_DAYS")
+        .ok()
+        .and_then(|days| days.parse().ok())
+        .unwrap_or(14);
+    Duration::days(days)
+}
+
+/// Self-approval becomes possible once the cooldown has passed since the request.
+fn self_approval_available_at(requested_at: NaiveDateTime) -> NaiveDateTime {
+    requested_at + self_approval_cooldown()
+}
+
+pub async fn address_changes_page(
+    pool: &Pool,
+    user: &CurrentUser,
+    csrf_token: CsrfToken,
+    session: &Session,
+) -> Html<String> {
+    let mut page = Page::new("Payout Address Approvals", session)
+        .with_csrf_token(csrf_token)
+        .with_meta_description("Approve pending payout address changes");
+    let token_str = page.get_csrf_token().cloned().unwrap_or_default();
+
+    let (requests, creators): (_, HashMap<i64, i64>) = db::run(pool, |conn| {
+        let requests = db::get_pending_address_changes(conn)?;
+        let repository = SqliteRepository::new(conn);
+        let mut creators = HashMap::new();
+        for request in &requests {
+            if let Some(campaign) = CampaignRepository::get(&repository, request.campaign_id)? {
+                creators.insert(request.campaign_id, campaign.creator_id);
+            }
+        }
+        Ok((requests, creators))
+    })
+    .await
+    .unwrap_or_default();
+    let now = Utc::now().naive_utc();
+
+    let mut changes = Vec::new();
+    for request in requests {
+        let creator_id = creators.get(&request.campaign_id).copied().unwrap_or_default();
+        if !user.can_manage_campaign(creator_id) {
+            continue;
+        }
+
+        let available_at = self_approval_available_at(request.requested_at);
+        let own_request = request.requested_by == user.id;
+        changes.push(AddressChangeRow {
+            id: request.id,
+            title: request.campaign_title,
+            old_address: request.old_address,
+            new_address: request.new_address,
+            requested_by_name: request.requested_by_name,
+            requested_at: request.requested_at.to_string(),
+            can_approve: !own_request || now >= available_at,
+            needs_password: own_request,
+            available_at: available_at.to_string(),
+        });
+    }
+    let content = templates::render(&AddressChangesView { csrf_token: &token_str, changes });
+
+    page.set_content(content);
+    render_page_or_error!(page, "Payout Address Approvals")
+}
+
+pub async fn approve_address_change(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    Form(form): Form<ApprovalForm>,
+) -> Result<Redirect, AppError> {
+    let request = db::run(&pool, move |conn| db::get_address_change_request(conn, id))
+        .await?
+        .ok_or_else(|| AppError::Rejected(StatusCode::NOT_FOUND, i18n::t("error-request-not-found")))?;
+    if request.status != "pending" {
+        return Err(AppError::Rejected(StatusCode::CONFLICT, i18n::t("error-request-stale")));
+    }
+
+    let own_request = request.requested_by == Some(user.id);
+    if own_request {
+        if Utc::now().naive_utc() < self_approval_available_at(request.requested_at) {
+            return Err(AppError::Rejected(StatusCode::FORBIDDEN, i18n::t("error-cooldown")));
+        }
+        let (user_id, password) = (user.id, form.password);
+        let password_ok = db::run(&pool, move |conn| {
+            Ok(db::get_user_password(conn, user_id)?.is_some_and(|stored| auth::verify_password(&stored, &password)))
+        })
+        .await?;
+        if !password_ok {
+            return Err(AppError::Rejected(StatusCode::FORBIDDEN, i18n::t("error-incorrect-password")));
+        }
+    }
+
+    let approved_by = user.id;
+    let applied = db::run(&pool, move |conn| db::approve_address_change(conn, id, approved_by))
+        .await?;
+    if !applied {
+        return Err(AppError::Rejected(StatusCode::CONFLICT, i18n::t("error-request-stale")));
+    }
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "campaign.address_change_approved",
+        "campaign",
+        Some(request.campaign_id),
+        None,
+        Some(json!({ "request_id": id, "self_approved": own_request })),
+    )
+    .await;
+    Ok(Redirect::to("/protected/address-changes"))
+}
+
+pub async fn reject_address_change(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let rejected_by = user.id;
+    let campaign_id = db::run(&pool, move |conn| {
+        let request = db::get_address_change_request(conn, id)?;
+        db::reject_address_change(conn, id, rejected_by)?;
+        Ok(request.map(|request| request.campaign_id))
+    })
+    .await?;
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "campaign.address_change_rejected",
+        "campaign",
+        campaign_id,
+        None,
+        Some(json!({ "request_id": id })),
+    )
+    .await;
+    Ok(Redirect::to("/protected/address-changes"))
+}
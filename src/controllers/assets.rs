@@ -0,0 +1,30 @@
+use crate::error::AppError;
+use crate::views::themes::theme_manager;
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use hyper::header;
+
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// Theme stylesheets, fonts and images. Their names carry a hash of the content,
+/// so browsers may keep them forever; after a change the old name is gone.
+pub async fn theme_asset(Path(file): Path<String>) -> Result<Response, AppError> {
+    let themes = theme_manager::current();
+    if let Some(css) = themes.stylesheet(&file) {
+        return Ok((
+            [(header::CONTENT_TYPE, "text/css; charset=utf-8"), (header::CACHE_CONTROL, IMMUTABLE)],
+            css.to_string(),
+        )
+            .into_response());
+    }
+    let asset = themes.asset(&file).ok_or(AppError::NotFound)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, asset.content_type),
+            (header::CACHE_CONTROL, IMMUTABLE),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        ],
+        asset.bytes.clone(),
+    )
+        .into_response())
+}
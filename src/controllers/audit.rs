@@ -0,0 +1,163 @@
+use crate::db::{self, AuditFilter, Pool};
+use crate::error::AppError;
+use crate::views::templates::{self, filters, view, Pagination};
+use crate::{controllers::page::Page, render_page_or_error};
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{Html, IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+
+const PAGE_SIZE: i64 = 50;
+
+#[derive(Deserialize)]
+pub struct AuditPageQuery {
+    page: Option<i64>,
+    /// Recompute the hash chain, which reads the whole log.
+    verify: Option<bool>,
+}
+
+#[derive(Serialize)]
+struct AuditRow<'a> {
+    id: i64,
+    created_at: &'a str,
+    actor_name: &'a str,
+    action: &'a str,
+    target: String,
+    before_state: &'a str,
+    after_state: &'a str,
+    ip: &'a str,
+}
+
+view! {
+    "audit.html",
+    struct AuditView<'a> {
+        /// `intact`, `broken` (at `broken_at`), `unverified`, or `unchecked` unless asked to verify.
+        chain_status: &'static str,
+        broken_at: i64,
+        actor: String,
+        action: String,
+        target_type: String,
+        from: String,
+        to: String,
+        /// The encoded filter, for the export link.
+        query: String,
+        entries: Vec<AuditRow<'a>>,
+        pagination: Pagination,
+    }
+}
+
+pub fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn filter_query_string(filter: &AuditFilter) -> String {
+    [
+        ("actor", &filter.actor),
+        ("action", &filter.action),
+        ("target_type", &filter.target_type),
+        ("from", &filter.from),
+        ("to", &filter.to),
+    ]
+    .iter()
+    .filter_map(|(name, value)| {
+        value
+            .as_deref()
+            .filter(|value| !value.is_empty())
+            .map(|value| format!("{}={}", name, encode_query_value(value)))
+    })
+    .collect::<Vec<_>>()
+    .join("&")
+}
+
+pub async fn audit_page(
+    pool: &Pool,
+    Query(filter): Query<AuditFilter>,
+    Query(page_query): Query<AuditPageQuery>,
+    session: &Session,
+) -> Html<String> {
+    let mut page = Page::new("Audit Log", session)
+        .with_meta_description("Audit log of admin actions");
+
+    let current_page = page_query.page.unwrap_or(1).max(1);
+    let page_filter = filter.clone();
+    let verify = page_query.verify.unwrap_or(false);
+    let (total, entries, chain) = db::run(pool, move |conn| {
+        let total = db::count_audit_entries(conn, &page_filter).unwrap_or(0);
+        let entries = db::get_audit_entries(conn, &page_filter, PAGE_SIZE, (current_page - 1) * PAGE_SIZE)
+            .unwrap_or_else(|_| vec![]);
+        Ok((total, entries, verify.then(|| db::verify_audit_chain(conn))))
+    })
+    .await
+    .unwrap_or_else(|e| (0, vec![], Some(Err(e))));
+    let total_pages = ((total + PAGE_SIZE - 1) / PAGE_SIZE).max(1);
+
+    let (chain_status, broken_at) = match chain {
+        Some(Ok(None)) => ("intact", 0),
+        Some(Ok(Some(id))) => ("broken", id),
+        Some(Err(_)) => ("unverified", 0),
+        None => ("unchecked", 0),
+    };
+
+    let rows = entries
+        .iter()
+        .map(|entry| AuditRow {
+            id: entry.id,
+            created_at: &entry.created_at,
+            actor_name: &entry.actor_name,
+            action: &entry.action,
+            target: format!(
+                "{} {}",
+                entry.target_type,
+                entry.target_id.map(|id| format!("#{}", id)).unwrap_or_default()
+            ),
+            before_state: entry.before_state.as_deref().unwrap_or(""),
+            after_state: entry.after_state.as_deref().unwrap_or(""),
+            ip: &entry.ip,
+        })
+        .collect();
+
+    let query = filter_query_string(&filter);
+    let value = |field: &Option<String>| field.clone().unwrap_or_default();
+    let content = templates::render(&AuditView {
+        chain_status,
+        broken_at,
+        actor: value(&filter.actor),
+        action: value(&filter.action),
+        target_type: value(&filter.target_type),
+        from: value(&filter.from),
+        to: value(&filter.to),
+        pagination: Pagination::new("/protected/audit", &query, current_page, total_pages)
+            .with_labels("pagination-newer", "pagination-older"),
+        query,
+        entries: rows,
+    });
+
+    page.set_content(content);
+    render_page_or_error!(page, "Audit Log")
+}
+
+pub async fn audit_export(State(pool): State<Pool>, Query(filter): Query<AuditFilter>) -> Result<Response, AppError> {
+    let entries = db::run(&pool, move |conn| {
+        let total = db::count_audit_entries(conn, &filter)?;
+        db::get_audit_entries(conn, &filter, total, 0)
+    })
+    .await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"audit-log.json\""),
+        ],
+        serde_json::to_string_pretty(&entries)?,
+    )
+        .into_response())
+}
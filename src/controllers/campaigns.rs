@@ -1,15 +1,39 @@
+use crate::audit::{self, ClientIp};
+use crate::markdown;
 use crate::render_page_or_error;
-use crate::{controllers::page::Page, db};
+use crate::db::Pool;
+use crate::error::AppError;
+use crate::i18n;
+use crate::{auth::CurrentUser, controllers::page::Page, db};
+use crate::controllers::address_changes::address_change_notice_period;
+use crate::controllers::audit::encode_query_value;
+use crate::controllers::translations::{
+    stored_translation, tabs, translation_form, TranslationQuery, TranslationSource, TranslationTab,
+};
+use crate::models::{Campaign, CampaignDetails, CampaignFilter, CampaignUpdate, NewCampaign, TranslatedContent};
+use crate::repositories::{
+    localize_campaign, localize_update, CampaignRepository, SqliteRepository, TranslationRepository,
+};
 use crate::views::layout::render_layout;
+use crate::views::templates::{self, filters, view, Pagination, SelectOption};
 use axum::{
-    extract::{Form, Path},
+    extract::{Extension, Form, Path, Query, State},
     http::StatusCode,
-    response::{Html, Redirect},
+    response::{Html, IntoResponse, Redirect, Response},
 };
 use axum_csrf::CsrfToken;
-use serde::Deserialize;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use tower_sessions::Session;
 
+const PAGE_SIZE: i64 = 20;
+
+#[derive(Deserialize)]
+pub struct CampaignPageQuery {
+    page: Option<i64>,
+}
+
 #[derive(Deserialize)]
 pub struct CampaignForm {
     title: String,
@@ -18,282 +42,533 @@ pub struct CampaignForm {
     end_date: String,
     goal_amount: f64,
     xmr_address: String,
+    /// Set by the form's Preview button: render the description instead of saving.
+    #[serde(default)]
+    preview: Option<String>,
+}
+/// A campaign as shown in listings, with amounts formatted for display.
+#[derive(Default, Serialize)]
+pub struct CampaignCard {
+    pub id: i64,
+    pub title: String,
+    /// The Markdown source, for edit forms.
+    pub description: String,
+    pub description_html: String,
+    pub goal_amount: String,
+    pub current_amount: String,
+    pub status: String,
+    pub xmr_address: String,
+    pub start_date: String,
+    pub end_date: String,
+    /// Share of the goal raised, 0 to 100, for the progress badge.
+    pub progress_percent: String,
+}
+
+fn progress_percent(current_amount: f64, goal_amount: f64) -> String {
+    if goal_amount <= 0.0 {
+        return "0".to_string();
+    }
+    format!("{:.0}", (current_amount / goal_amount * 100.0).clamp(0.0, 100.0))
+}
+
+impl From<&Campaign> for CampaignCard {
+    fn from(campaign: &Campaign) -> Self {
+        CampaignCard {
+            id: campaign.id,
+            title: campaign.title.clone(),
+            description: campaign.description.clone(),
+            description_html: markdown::render(&campaign.description),
+            goal_amount: campaign.goal_amount.to_string(),
+            current_amount: campaign.current_amount.to_string(),
+            status: campaign.status.clone(),
+            xmr_address: campaign.xmr_address.clone(),
+            start_date: campaign.start_date.clone(),
+            end_date: campaign.end_date.clone(),
+            progress_percent: progress_percent(campaign.current_amount, campaign.goal_amount),
+        }
+    }
+}
+
+impl CampaignCard {
+    /// The card with the submitted form values, to show the form again with a preview.
+    fn with_form(self, form: &CampaignForm) -> Self {
+        CampaignCard {
+            title: form.title.clone(),
+            description: form.description.clone(),
+            description_html: markdown::render(&form.description),
+            goal_amount: form.goal_amount.to_string(),
+            xmr_address: form.xmr_address.clone(),
+            start_date: form.start_date.clone(),
+            end_date: form.end_date.clone(),
+            ..self
+        }
+    }
+}
+
+view! {
+    "campaigns/new.html",
+    struct NewCampaignView<'a> {
+        csrf_token: &'a str,
+        campaign: CampaignCard,
+        preview: String,
+    }
 }
+
 pub async fn new_campaign_page(csrf_token: CsrfToken, session: &Session) -> Html<String> {
-    let token_str = csrf_token.authenticity_token().unwrap();
-
-    let content = format!(
-        r#"
-        <section class="campaign-form">
-            <form method="POST" action="/protected/campaigns/new">
-                <input type="hidden" name="csrf_token" value="{}">
-                <div class="form-group">
-                    <label for="title">Title:</label>
-                    <input type="text" name="title" required>
-                </div>
-                <div class="form-group">
-                    <label for="description">Description:</label>
-                    <textarea name="description" required></textarea>
-                </div>
-                <div class="form-group">
-                    <label for="start_date">Start Date:</label>
-                    <input type="date" name="start_date" required>
-                </div>
-                <div class="form-group">
-                    <label for="end_date">End Date:</label>
-                    <input type="date" name="end_date" required>
-                </div>
-                <div class="form-group">
-                    <label for="goal_amount">Goal Amount:</label>
-                    <input type="number" step="0.01" name="goal_amount" required>
-                </div>
-                    <div class="form-group">
-                    <label for="xmr_address">Monero Address:</label>
-                    <input type="text" name="xmr_address" required>
-                </div>
-                <button type="submit">Create Campaign</button>
-            </form>
-        </section>
-        "#,
-        token_str
-    );
+    let token_str = csrf_token.authenticity_token().unwrap_or_default();
+
+    let content = templates::render(&NewCampaignView {
+        csrf_token: &token_str,
+        campaign: CampaignCard::default(),
+        preview: String::new(),
+    });
     render_layout(&content, session).await
 }
 
+async fn campaign_snapshot(pool: &Pool, id: i64) -> Option<Value> {
+    db::run(pool, move |conn| CampaignRepository::get(&SqliteRepository::new(conn), id))
+        .await
+        .ok()
+        .flatten()
+        .and_then(|campaign| serde_json::to_value(campaign).ok())
+}
+
+async fn find_campaign(pool: &Pool, id: i64) -> Result<Campaign, AppError> {
+    db::run(pool, move |conn| CampaignRepository::get(&SqliteRepository::new(conn), id))
+        .await?
+        .ok_or_else(|| AppError::Rejected(StatusCode::NOT_FOUND, i18n::t("error-campaign-not-found")))
+}
+
 pub async fn create_campaign(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    csrf_token: CsrfToken,
     session: Session,
     Form(form): Form<CampaignForm>,
-) -> Result<Redirect, (StatusCode, String)> {
-    let creator_id: i64 = match session.get::<String>("user_id").await {
-        Ok(Some(user_id)) => user_id.parse().unwrap_or(1),
-        _ => 0,
+) -> Result<Response, AppError> {
+    if form.preview.is_some() {
+        let token_str = csrf_token.authenticity_token().unwrap_or_default();
+        let campaign = CampaignCard::default().with_form(&form);
+        let preview = campaign.description_html.clone();
+        let content = templates::render(&NewCampaignView { csrf_token: &token_str, campaign, preview });
+        return Ok(render_layout(&content, &session).await.into_response());
+    }
+
+    let campaign = NewCampaign {
+        title: form.title,
+        description: form.description,
+        creator_id: user.id,
+        start_date: form.start_date,
+        end_date: form.end_date,
+        goal_amount: form.goal_amount,
+        status: "active".to_string(),
+        xmr_address: form.xmr_address,
     };
+    let id = db::run(&pool, move |conn| SqliteRepository::new(conn).insert(&campaign))
+        .await?;
 
-    db::insert_campaign(
-        &form.title,
-        &form.description,
-        creator_id,
-        &form.start_date,
-        &form.end_date,
-        form.goal_amount,
-        "active",
-        &form.xmr_address,
-    )
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-
-    Ok(Redirect::to("/"))
-}
-
-pub async fn list_campaigns(session: &Session) -> Html<String> {
-    let campaigns = db::get_campaigns().unwrap_or_else(|_| vec![]);
-
-    let mut content = String::from("<section class=\"campaign-list\">");
-    for (id, title, description, goal_amount, current_amount, status, _xmr_address, start_date, end_date) in campaigns {
-        content.push_str(&format!(
-            r#"
-            <div class="campaign">
-                <h2>{}</h2>
-                <p>{}</p>
-                <p>Goal: {} XMR</p>
-                <p>Current: {} XMR</p>
-                <p>Status: {}</p>
-                <p>Start Date: {}</p>
-                <p>End Date: {}</p>
-                <a href="/campaigns/{}">Details</a>
-            </div>
-            "#,
-            title, description, goal_amount, current_amount, status, start_date, end_date, id
-        ));
+    let after = campaign_snapshot(&pool, id).await;
+    audit::record(&pool, &user, &ip, "campaign.create", "campaign", Some(id), None, after).await;
+
+    Ok(Redirect::to("/").into_response())
+}
+
+/// One page of campaigns matching `filter` in the request's language, with the total number of pages.
+async fn campaign_page(pool: &Pool, filter: &CampaignFilter, current_page: i64) -> (Vec<Campaign>, i64) {
+    let filter = filter.clone();
+    let (total, campaigns) = db::run(pool, move |conn| {
+        let repository = SqliteRepository::new(conn);
+        let total = CampaignRepository::count(&repository, &filter)?;
+        let mut campaigns = CampaignRepository::list(&repository, &filter, PAGE_SIZE, (current_page - 1) * PAGE_SIZE)?;
+        for campaign in &mut campaigns {
+            localize_campaign(&repository, campaign, i18n::current());
+        }
+        Ok((total, campaigns))
+    })
+    .await
+    .unwrap_or_else(|_| (0, vec![]));
+    (campaigns, ((total + PAGE_SIZE - 1) / PAGE_SIZE).max(1))
+}
+
+fn filter_query_string(filter: &CampaignFilter) -> String {
+    [("status", &filter.status), ("from", &filter.from), ("to", &filter.to)]
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .as_deref()
+                .filter(|value| !value.trim().is_empty())
+                .map(|value| format!("{}={}", name, encode_query_value(value)))
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// The status and date range filter of a campaign list at `path`.
+#[derive(Serialize)]
+struct FilterForm {
+    path: &'static str,
+    statuses: Vec<SelectOption>,
+    from: String,
+    to: String,
+}
+
+impl FilterForm {
+    fn new(path: &'static str, filter: &CampaignFilter) -> Self {
+        FilterForm {
+            path,
+            statuses: SelectOption::list(
+                &[
+                    ("", "campaign-filter-any-status"),
+                    ("active", "campaign-status-active"),
+                    ("completed", "campaign-status-completed"),
+                ],
+                filter.status.as_deref().unwrap_or(""),
+            ),
+            from: filter.from.clone().unwrap_or_default(),
+            to: filter.to.clone().unwrap_or_default(),
+        }
     }
-    content.push_str("</section>");
+}
+
+view! {
+    "campaigns/list.html",
+    struct CampaignListView {
+        filter: FilterForm,
+        campaigns: Vec<CampaignCard>,
+        pagination: Pagination,
+    }
+}
+
+pub async fn list_campaigns(
+    pool: &Pool,
+    Query(filter): Query<CampaignFilter>,
+    Query(page_query): Query<CampaignPageQuery>,
+    session: &Session,
+) -> Html<String> {
+    let current_page = page_query.page.unwrap_or(1).max(1);
+    let (campaigns, total_pages) = campaign_page(pool, &filter, current_page).await;
+
+    let content = templates::render(&CampaignListView {
+        filter: FilterForm::new("/campaigns", &filter),
+        campaigns: campaigns.iter().map(CampaignCard::from).collect(),
+        pagination: Pagination::new("/campaigns", &filter_query_string(&filter), current_page, total_pages),
+    });
     render_layout(&content, session).await
 }
 
-pub async fn campaign_details(Path(id): Path<i64>, session: &Session) -> Html<String> {
-    let campaigns = db::get_campaigns().unwrap_or_else(|_| vec![]);
-    let updates = db::get_campaign_updates(id).unwrap_or_else(|_| vec![]);
-
-    let campaign = campaigns
-        .into_iter()
-        .find(|(cid, _, _, _, _, _, _, _, _)| *cid == id);
-
-    let mut content = if let Some((_, title, description, goal_amount, current_amount, status, xmr_address, start_date, end_date)) = campaign {
-        format!(
-            r#"
-            <section class="campaign-details">
-                <h1>{}</h1>
-                <p>{}</p>
-                <p>Goal Amount: {} XMR</p>
-                <p>Current Amount: {} XMR</p>
-                <p>Status: {}</p>
-                <p>XMR Address: {}</p>
-                <p>Start Date: {}</p>
-                <p>End Date: {}</p>
-                
-                <div class="campaign-updates">
-                    <h2>Campaign Updates</h2>
-                    "#,
-            title, description, goal_amount, current_amount, status, xmr_address, start_date, end_date
-        )
-    } else {
-        return render_layout("Campaign not found", session).await;
-    };
+#[derive(Serialize)]
+struct GoalRow {
+    amount: String,
+    description: String,
+}
+
+view! {
+    "campaigns/details.html",
+    struct CampaignDetailsView<'a> {
+        campaign: CampaignCard,
+        /// When the payout address changed recently, for the warning; empty otherwise.
+        address_changed_at: &'a str,
+        has_goals: bool,
+        goals: Vec<GoalRow>,
+        updates: Vec<CampaignUpdate>,
+    }
+}
 
-    for (_, update_text, update_hash, created_at) in updates {
-        content.push_str(&format!(
-            r#"
-            <div class="update-entry">
-                <p>{}</p>
-                <small>Posted: {} (Hash: {})</small>
-            </div>
-            "#,
-            update_text, created_at, update_hash
-        ));
+/// The public campaign page body in the request's language, or `None` if there is no such campaign.
+pub fn campaign_details_content(
+    repository: &(impl CampaignRepository + TranslationRepository),
+    id: i64,
+    address_changed_at: &str,
+) -> Option<String> {
+    let locale = i18n::current();
+    let mut campaign = repository.get(id).ok().flatten()?;
+    localize_campaign(repository, &mut campaign, locale);
+    let goals = repository.goals(id).unwrap_or_else(|_| vec![]);
+    let mut updates = repository.updates(id).unwrap_or_else(|_| vec![]);
+    for update in &mut updates {
+        localize_update(repository, update, locale);
+        update.update_text = markdown::render(&update.update_text);
     }
 
-    content.push_str("</div></section>");
-    render_layout(&content, session).await
-}pub async fn update_campaign(
-    Path(id): Path<i64>,
-    Form(form): Form<CampaignForm>,
-) -> Result<Redirect, (StatusCode, String)> {
-    db::update_campaign_current_amount(id, form.goal_amount)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    Ok(Redirect::to(&format!("/campaigns/{}", id)))
+    Some(templates::render(&CampaignDetailsView {
+        campaign: CampaignCard::from(&campaign),
+        address_changed_at,
+        has_goals: !goals.is_empty(),
+        goals: goals
+            .into_iter()
+            .map(|goal| GoalRow { amount: goal.amount.to_string(), description: goal.description })
+            .collect(),
+        updates,
+    }))
+}
+
+pub async fn campaign_details(pool: &Pool, Path(id): Path<i64>, session: &Session) -> Result<Html<String>, AppError> {
+    let last_address_change = db::run(pool, move |conn| db::get_last_address_change(conn, id)).await;
+    let address_changed_at = match last_address_change {
+        Ok(Some(changed_at)) if Utc::now().naive_utc() - changed_at < address_change_notice_period() => {
+            changed_at.to_string()
+        }
+        _ => String::new(),
+    };
+
+    let content = db::run(pool, move |conn| {
+        Ok(campaign_details_content(&SqliteRepository::new(conn), id, &address_changed_at))
+    })
+    .await?
+    .ok_or(AppError::NotFound)?;
+    Ok(render_layout(&content, session).await)
 }
 
-pub async fn delete_campaign(Path(id): Path<i64>) -> Result<Redirect, (StatusCode, String)> {
-    db::delete_campaign(id).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+pub async fn delete_campaign(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let before = campaign_snapshot(&pool, id).await;
+    db::run(&pool, move |conn| CampaignRepository::delete(&SqliteRepository::new(conn), id))
+        .await?;
+    audit::record(&pool, &user, &ip, "campaign.delete", "campaign", Some(id), before, None).await;
     Ok(Redirect::to("/protected/dashboard"))
 }
-pub async fn list_all_campaigns(session: &Session) -> Html<String> {
+#[derive(Serialize)]
+struct AdminCampaignRow {
+    campaign: CampaignCard,
+    can_edit: bool,
+    can_manage: bool,
+}
+
+view! {
+    "campaigns/admin_list.html",
+    struct AdminCampaignListView {
+        can_create: bool,
+        filter: FilterForm,
+        campaigns: Vec<AdminCampaignRow>,
+        pagination: Pagination,
+    }
+}
+
+pub async fn list_all_campaigns(
+    pool: &Pool,
+    user: &CurrentUser,
+    Query(filter): Query<CampaignFilter>,
+    Query(page_query): Query<CampaignPageQuery>,
+    session: &Session,
+) -> Html<String> {
     let mut page = Page::new("Campaign Management", session)
         .with_meta_description("Manage all crowdfunding campaigns");
 
-    let campaigns = db::get_campaigns().unwrap_or_else(|_| vec![]);
-    let mut content = String::from(r#"<section class="campaigns-admin">
-        <h2>Campaign Management</h2>
-        <a href="/protected/campaigns/new" class="button">Create New Campaign</a>
-        <div class="campaigns-list">"#);
-
-    for (id, title, description, goal_amount, current_amount, status, xmr_address, start_date, end_date) in campaigns {
-        content.push_str(&format!(
-            r#"<div class="campaign-entry">
-                <h3>{}</h3>
-                <p>{}</p>
-                <div class="campaign-stats">
-                    <span>Goal: {} XMR</span>
-                    <span>Current: {} XMR</span>
-                    <span>Status: {}</span>
-                    <span>Start Date: {}</span>
-                    <span>End Date: {}</span>
-                </div>
-                <div class="campaign-actions">
-                    <a href="/protected/campaigns/{}/edit" class="button">Edit</a>
-                    <a href="/protected/campaigns/{}/amount" class="button">Update Amount</a>
-                    <form method="POST" action="/protected/campaigns/{}/delete" 
-                        onsubmit="return confirm('Are you sure you want to delete this campaign?')">
-                        <button type="submit" class="button delete">Delete</button>
-                    </form>
-                </div>
-            </div>"#,
-            title, description, goal_amount, current_amount, status, start_date, end_date, id, id, id
-        ));
-    }
-    content.push_str("</div></section>");
-    
+    let current_page = page_query.page.unwrap_or(1).max(1);
+    let (campaigns, total_pages) = campaign_page(pool, &filter, current_page).await;
+    let content = templates::render(&AdminCampaignListView {
+        can_create: user.can_create_campaigns(),
+        filter: FilterForm::new("/protected/campaigns", &filter),
+        campaigns: campaigns
+            .iter()
+            .map(|campaign| AdminCampaignRow {
+                campaign: CampaignCard::from(campaign),
+                can_edit: user.can_edit_campaign_content(campaign.creator_id),
+                can_manage: user.can_manage_campaign(campaign.creator_id),
+            })
+            .collect(),
+        pagination: Pagination::new("/protected/campaigns", &filter_query_string(&filter), current_page, total_pages),
+    });
+
     page.set_content(content);
     render_page_or_error!(page, "Campaign Management")
-}pub async fn edit_campaign_page(
+}
+
+view! {
+    "campaigns/edit.html",
+    struct EditCampaignView<'a> {
+        csrf_token: &'a str,
+        tabs: Vec<TranslationTab>,
+        /// The forms of a translation tab; empty on the default language's tab.
+        translations: String,
+        /// Editors may only translate; the campaign itself is changed by its managers.
+        can_manage: bool,
+        campaign: CampaignCard,
+        /// The address awaiting approval, if any.
+        pending_address: String,
+        preview: String,
+    }
+}
+
+fn edit_tabs(id: i64, locale: &str) -> Vec<TranslationTab> {
+    tabs(&format!("/protected/campaigns/{}/edit", id), locale)
+}
+
+/// The forms translating a campaign and its updates into `locale`.
+fn campaign_translation_forms(
+    repository: &(impl CampaignRepository + TranslationRepository),
+    campaign: &Campaign,
+    locale: &str,
+    csrf_token: &str,
+) -> Result<String, db::DatabaseError> {
+    let action = format!("/protected/campaigns/{}/translations", campaign.id);
+    let source = TranslationSource {
+        action: &action,
+        update_id: None,
+        heading: String::new(),
+        title: Some(&campaign.title),
+        body_label: "field-description",
+        body: &campaign.description,
+    };
+    let mut forms = translation_form(
+        csrf_token,
+        &source,
+        stored_translation(repository, TranslatedContent::Campaign, campaign.id, locale)?,
+    );
+    for update in repository.updates(campaign.id)? {
+        let source = TranslationSource {
+            action: &action,
+            update_id: Some(update.id),
+            heading: i18n::t_args("translation-update-heading", &[("date", i18n::format_datetime(&update.created_at))]),
+            title: None,
+            body_label: "update-field",
+            body: &update.update_text,
+        };
+        let translation = stored_translation(repository, TranslatedContent::CampaignUpdate, update.id, locale)?;
+        forms.push_str(&translation_form(csrf_token, &source, translation));
+    }
+    Ok(forms)
+}
+
+pub async fn edit_campaign_page(
+    pool: &Pool,
+    user: &CurrentUser,
     Path(id): Path<i64>,
+    Query(query): Query<TranslationQuery>,
     csrf_token: CsrfToken,
     session: &Session
-) -> Html<String> {
+) -> Result<Html<String>, AppError> {
     let mut page = Page::new("Edit Campaign", session)
         .with_csrf_token(csrf_token)
         .with_meta_description("Edit crowdfunding campaign details");
 
-    let campaigns = db::get_campaigns().unwrap_or_else(|_| vec![]);
-    let campaign = campaigns.into_iter().find(|(cid, _, _, _, _, _, _, _, _)| *cid == id);
-
-    if let Some((_, title, description, goal_amount, current_amount, status, xmr_address, start_date, end_date)) = campaign {
-        let content = format!(
-            r#"<section class="campaign-form">
-                <h2>Edit Campaign</h2>
-                <form method="POST" action="/protected/campaigns/{}/edit">
-                    <input type="hidden" name="csrf_token" value="{}">
-                    <div class="form-group">
-                        <label for="title">Title:</label>
-                        <input type="text" name="title" value="{}" required>
-                    </div>
-                    <div class="form-group">
-                        <label for="description">Description:</label>
-                        <textarea name="description" required>{}</textarea>
-                    </div>
-                    <div class="form-group">
-                        <label for="start_date">Start Date:</label>
-                        <input type="date" name="start_date" value="{}" required>
-                    </div>
-                    <div class="form-group">
-                        <label for="end_date">End Date:</label>
-                        <input type="date" name="end_date" value="{}" required>
-                    </div>
-                    <div class="form-group">
-                        <label for="goal_amount">Goal Amount:</label>
-                        <input type="number" step="0.01" name="goal_amount" value="{}" required>
-                    </div>
-                    <div class="form-group">
-                        <label for="xmr_address">Monero Address:</label>
-                        <input type="text" name="xmr_address" value="{}" required>
-                    </div>
-                    <button type="submit">Update Campaign</button>
-                </form>
-            </section>"#,
-            id,
-            page.get_csrf_token().unwrap_or(&String::new()),
-            title, description, start_date, end_date, goal_amount, xmr_address
-        );
-        page.set_content(content);
-    }
-    
-
-    render_page_or_error!(page, "Edit Campaign")
-}
+    let locale = query.locale();
+    let token_str = page.get_csrf_token().cloned().unwrap_or_default();
+    let (campaign, translations, pending_address) = db::run(pool, move |conn| {
+        let repository = SqliteRepository::new(conn);
+        let Some(campaign) = CampaignRepository::get(&repository, id)? else {
+            return Ok(None);
+        };
+        let translations = if query.is_default() {
+            String::new()
+        } else {
+            campaign_translation_forms(&repository, &campaign, locale, &token_str)?
+        };
+        Ok(Some((campaign, translations, db::get_pending_address_for_campaign(conn, id))))
+    })
+    .await?
+    .ok_or(AppError::NotFound)?;
 
+    let content = templates::render(&EditCampaignView {
+        csrf_token: page.get_csrf_token().map(String::as_str).unwrap_or(""),
+        tabs: edit_tabs(id, locale),
+        translations,
+        can_manage: user.can_manage_campaign(campaign.creator_id),
+        campaign: CampaignCard::from(&campaign),
+        pending_address: pending_address.ok().flatten().unwrap_or_default(),
+        preview: String::new(),
+    });
+    page.set_content(content);
 
-#[derive(Deserialize)]
-pub struct EditCampaignForm {
-    title: String,
-    description: String,
-    start_date: String,
-    end_date: String,
-    goal_amount: f64,
-    xmr_address: String,
+    Ok(render_page_or_error!(page, "Edit Campaign"))
 }
 
 pub async fn edit_campaign(
+    State(pool): State<Pool>,
     Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    csrf_token: CsrfToken,
+    session: Session,
     Form(form): Form<CampaignForm>,
-) -> Result<Redirect, (StatusCode, String)> {
-    db::update_campaign(
-        id,
-        form.title,
-        form.description,
-        form.start_date,
-        form.end_date, 
-        form.goal_amount,
-        form.xmr_address
+) -> Result<Response, AppError> {
+    let campaign = find_campaign(&pool, id).await?;
+    if form.preview.is_some() {
+        let pending_address = db::run(&pool, move |conn| db::get_pending_address_for_campaign(conn, id))
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let token_str = csrf_token.authenticity_token().unwrap_or_default();
+        let card = CampaignCard::from(&campaign).with_form(&form);
+        let preview = card.description_html.clone();
+        let content = templates::render(&EditCampaignView {
+            csrf_token: &token_str,
+            tabs: edit_tabs(id, i18n::DEFAULT_LOCALE),
+            translations: String::new(),
+            can_manage: true,
+            campaign: card,
+            pending_address,
+            preview,
+        });
+        return Ok(render_layout(&content, &session).await.into_response());
+    }
+    let current_address = campaign.xmr_address.clone();
+
+    if form.xmr_address != current_address {
+        let (old_address, new_address, requested_by) = (current_address.clone(), form.xmr_address.clone(), user.id);
+        db::run(&pool, move |conn| {
+            db::create_address_change_request(conn, id, &old_address, &new_address, requested_by)
+        })
+        .await?;
+        audit::record(
+            &pool,
+            &user,
+            &ip,
+            "campaign.address_change_requested",
+            "campaign",
+            Some(id),
+            Some(json!({ "xmr_address": current_address })),
+            Some(json!({ "xmr_address": form.xmr_address })),
+        )
+        .await;
+    }
+
+    let details = CampaignDetails {
+        title: form.title,
+        description: form.description,
+        start_date: form.start_date,
+        end_date: form.end_date,
+        goal_amount: form.goal_amount,
+    };
+    db::run(&pool, move |conn| SqliteRepository::new(conn).update_details(id, &details))
+        .await?;
+    let after = campaign_snapshot(&pool, id).await;
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "campaign.update",
+        "campaign",
+        Some(id),
+        serde_json::to_value(&campaign).ok(),
+        after,
     )
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    .await;
 
-    Ok(Redirect::to("/protected/campaigns"))
+    Ok(Redirect::to("/protected/campaigns").into_response())
 }
 #[derive(Deserialize)]
 pub struct AmountUpdateForm {
     current_amount: f64,
 }
 
+view! {
+    "campaigns/amount.html",
+    struct AmountView<'a> {
+        id: i64,
+        csrf_token: &'a str,
+        current_amount: String,
+    }
+}
+
 pub async fn update_amount_page(
+    pool: &Pool,
     Path(id): Path<i64>,
     csrf_token: CsrfToken,
     session: &Session
@@ -301,37 +576,115 @@ pub async fn update_amount_page(
     let mut page = Page::new("Update Amount", session)
         .with_csrf_token(csrf_token);
 
-    let current_amount = db::get_campaign_amount(id)
-        .unwrap_or(0.0);
-
-    let content = format!(
-        r#"<section class="amount-form">
-            <h2>Update Current Amount</h2>
-            <p class="current-amount-display">Current Amount: {} XMR</p>
-            <form method="POST" action="/protected/campaigns/{}/amount">
-                <input type="hidden" name="csrf_token" value="{}">
-                <div class="form-group">
-                    <label for="current_amount">New Amount:</label>
-                    <input type="number" step="0.01" name="current_amount" value="{}" required>
-                </div>
-                <button type="submit">Update Amount</button>
-            </form>
-        </section>"#,
-        current_amount,
+    let current_amount = db::run(pool, move |conn| CampaignRepository::get(&SqliteRepository::new(conn), id))
+        .await
+        .ok()
+        .flatten()
+        .map_or(0.0, |campaign| campaign.current_amount);
+
+    let content = templates::render(&AmountView {
         id,
-        page.get_csrf_token().unwrap_or(&String::new()),
-        current_amount
-    );
+        csrf_token: page.get_csrf_token().map(String::as_str).unwrap_or(""),
+        current_amount: current_amount.to_string(),
+    });
     page.set_content(content);
     render_page_or_error!(page, "Update Amount")
 }
 
 
 pub async fn update_campaign_amount(
+    State(pool): State<Pool>,
     Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
     Form(form): Form<AmountUpdateForm>,
-) -> Result<Redirect, (StatusCode, String)> {
-    db::update_campaign_amount(id, form.current_amount)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+) -> Result<Redirect, AppError> {
+    let before = find_campaign(&pool, id).await?.current_amount;
+    let current_amount = form.current_amount;
+    db::run(&pool, move |conn| SqliteRepository::new(conn).set_current_amount(id, current_amount))
+        .await?;
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "campaign.amount_update",
+        "campaign",
+        Some(id),
+        Some(json!({ "current_amount": before })),
+        Some(json!({ "current_amount": form.current_amount })),
+    )
+    .await;
     Ok(Redirect::to("/protected/campaigns"))
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize)]
+pub struct CampaignUpdateForm {
+    update_text: String,
+    #[serde(default)]
+    preview: Option<String>,
+}
+
+view! {
+    "campaigns/new_update.html",
+    struct NewUpdateView<'a> {
+        id: i64,
+        csrf_token: &'a str,
+        update_text: &'a str,
+        preview: String,
+    }
+}
+
+pub async fn new_update_page(
+    Path(id): Path<i64>,
+    csrf_token: CsrfToken,
+    session: &Session
+) -> Html<String> {
+    let mut page = Page::new("Post Campaign Update", session)
+        .with_csrf_token(csrf_token);
+
+    let content = templates::render(&NewUpdateView {
+        id,
+        csrf_token: page.get_csrf_token().map(String::as_str).unwrap_or(""),
+        update_text: "",
+        preview: String::new(),
+    });
+    page.set_content(content);
+    render_page_or_error!(page, "Post Campaign Update")
+}
+
+pub async fn create_campaign_update(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    csrf_token: CsrfToken,
+    session: Session,
+    Form(form): Form<CampaignUpdateForm>,
+) -> Result<Response, AppError> {
+    if form.preview.is_some() {
+        let token_str = csrf_token.authenticity_token().unwrap_or_default();
+        let content = templates::render(&NewUpdateView {
+            id,
+            csrf_token: &token_str,
+            update_text: &form.update_text,
+            preview: markdown::render(&form.update_text),
+        });
+        return Ok(render_layout(&content, &session).await.into_response());
+    }
+
+    let update_text = form.update_text.clone();
+    db::run(&pool, move |conn| SqliteRepository::new(conn).add_update(id, &update_text))
+        .await?;
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "campaign.post_update",
+        "campaign",
+        Some(id),
+        None,
+        Some(json!({ "update_text": form.update_text })),
+    )
+    .await;
+    Ok(Redirect::to(&format!("/campaigns/{}", id)).into_response())
+}
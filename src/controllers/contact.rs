@@ -1,240 +1,517 @@
-use crate::{db::get_connection, render_page_or_error};
+use crate::audit::{self, ClientIp};
+use crate::auth::CurrentUser;
+use crate::models::{ContactEntry, ContactRevision, TranslatedContent};
+use crate::db::{self, Pool};
+use crate::error::AppError;
+use crate::i18n;
+use crate::markdown;
+use crate::render_page_or_error;
+use crate::repositories::{localize_description, ContactRepository, SqliteRepository, TranslationRepository};
 use crate::views::layout::render_layout;
+use crate::views::templates::{self, filters, view};
 use axum::{
-    extract::{Form, Path},
-    response::{Html, Redirect},
+    extract::{Extension, Form, Path, Query, State},
+    response::{Html, IntoResponse, Redirect, Response},
 };
 use axum_csrf::CsrfToken;
-use hyper::StatusCode;
-use rusqlite::params;
 use serde::Deserialize;
+use serde_json::json;
 use crate::controllers::page::Page;
+use crate::controllers::translations::{stored_translation, tabs, translation_form, TranslationQuery, TranslationSource, TranslationTab};
+use crate::controllers::revisions::{diff_content, revisions_content, trash_content, DiffQuery, RevisionSummary};
+use tower_sessions::Session;
+
+const BASE: &str = "/protected/contact";
 
 #[derive(Deserialize)]
 pub struct ContactForm {
     description: String,
     admin_mail: String,
+    /// Set by the form's Preview button: render the text instead of saving it.
+    #[serde(default)]
+    preview: Option<String>,
+}
+
+fn validate_contact(description: &str, admin_mail: &str) -> Option<String> {
+    let valid_mail = admin_mail
+        .trim()
+        .split_once('@')
+        .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.') && !domain.contains('@'));
+
+    if description.trim().is_empty() {
+        Some(i18n::t("error-description-empty"))
+    } else if !valid_mail {
+        Some(i18n::t("error-admin-email-invalid"))
+    } else {
+        None
+    }
+}
+
+view! {
+    "contact/new.html",
+    struct NewContactView<'a> {
+        csrf_token: &'a str,
+        description: &'a str,
+        admin_mail: &'a str,
+        error: &'a str,
+        preview: &'a str,
+    }
+}
+
+view! {
+    "contact/details.html",
+    struct ContactDetailsView<'a> {
+        csrf_token: &'a str,
+        id: i64,
+        tabs: Vec<TranslationTab>,
+        /// The translation form; empty on the default language's tab.
+        translation: String,
+        description: &'a str,
+        admin_mail: &'a str,
+        error: &'a str,
+        preview: &'a str,
+        history: &'a str,
+    }
+}
+
+view! {
+    "contact/public.html",
+    struct ContactView {
+        description: String,
+        admin_mail: String,
+    }
+}
+
+view! {
+    "contact/all.html",
+    struct ContactListView {
+        entries: Vec<ContactEntry>,
+    }
+}
+
+fn new_contact_content(token_str: &str, description: &str, admin_mail: &str, error: Option<&str>, preview: &str) -> String {
+    templates::render(&NewContactView {
+        csrf_token: token_str,
+        description,
+        admin_mail,
+        error: error.unwrap_or(""),
+        preview,
+    })
+}
+
+fn contact_details_content(
+    token_str: &str,
+    id: i64,
+    description: &str,
+    admin_mail: &str,
+    error: Option<&str>,
+    preview: &str,
+    history: &str,
+) -> String {
+    templates::render(&ContactDetailsView {
+        csrf_token: token_str,
+        id,
+        tabs: tabs(&format!("{}/{}", BASE, id), i18n::DEFAULT_LOCALE),
+        translation: String::new(),
+        description,
+        admin_mail,
+        error: error.unwrap_or(""),
+        preview,
+        history,
+    })
 }
 
-pub async fn contact(session: &tower_sessions::Session) -> Html<String> {
+pub async fn contact(pool: &Pool, session: &Session) -> Html<String> {
     let mut page = Page::new("Contact Us", session)
         .with_meta_description("Get in touch with our team");
 
-    let conn = get_connection().unwrap();
-    let result = conn
-        .query_row(
-            "SELECT description, admin_mail FROM contact WHERE active = 1",
-            [],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        )
-        .unwrap_or_else(|_| {
+    match db::run(pool, |conn| Ok(contact_content(&SqliteRepository::new(conn)))).await {
+        Ok(content) => page.set_content(content),
+        Err(_) => return Html(String::from("Error loading contact page")),
+    }
+    render_page_or_error!(page, "contact page")
+}
+
+pub fn contact_content(repository: &(impl ContactRepository + TranslationRepository)) -> String {
+    let (description, admin_mail) = repository
+        .active()
+        .ok()
+        .flatten()
+        .map(|mut entry| {
+            localize_description(repository, TranslatedContent::Contact, entry.id, &mut entry.description, i18n::current());
+            (entry.description, entry.admin_mail)
+        })
+        .unwrap_or_else(|| {
             (
-                "Contact information will be available soon".to_string(),
+                i18n::t("contact-placeholder"),
                 "admin@example.com".to_string(),
             )
         });
 
-    let content = format!(
-        r#"
-        <section>
-            <h2>Contact Us 📬</h2>
-            <div class="contact-info">{}</div>
-            <div class="email-info">Email: {}</div>
-        </section>
-        "#,
-        result.0, result.1
-    );
-
-    page.set_content(content);
-    render_page_or_error!(page, "contact page")
+    templates::render(&ContactView { description: markdown::render(&description), admin_mail })
 }
 
 pub async fn contact_create(
     csrf_token: CsrfToken,
-    session: &tower_sessions::Session,
+    session: &Session,
 ) -> Html<String> {
     let mut page = Page::new("Create Contact Information", session)
         .with_csrf_token(csrf_token)
         .with_meta_description("Create new contact information");
 
-    let content = format!(
-        r#"
-        <section>
-            <h2>Create New Contact Information</h2>
-            <h3>You can use HTML in the description.</h3>
-            <form method="POST" action="/protected/contact/new">
-                <input type="hidden" name="csrf_token" value="{}">
-                <div>
-                    <label>Description:</label>
-                    <textarea name="description" required></textarea>
-                </div>
-                <div>
-                    <label>Admin Email:</label>
-                    <input type="email" name="admin_mail" required>
-                </div>
-                <button type="submit">Create</button>
-            </form>
-        </section>
-        "#,
-        page.get_csrf_token().unwrap_or(&String::new())
-    );
-
+    let content = new_contact_content(page.get_csrf_token().map(String::as_str).unwrap_or(""), "", "", None, "");
     page.set_content(content);
     page.render().await.unwrap_or_else(|_| Html(String::from("Error creating contact page")))
 }
 
 
-pub async fn contact_insert_created(Form(form): Form<ContactForm>) -> Result<Redirect, StatusCode> {
-    let conn = get_connection().unwrap();
-    conn.execute(
-        "INSERT INTO contact (description, admin_mail, active) VALUES (?, ?, 0)",
-        params![form.description, form.admin_mail],
-    )
-    .unwrap();
+pub async fn contact_insert_created(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    csrf_token: CsrfToken,
+    session: Session,
+    Form(form): Form<ContactForm>,
+) -> Result<Response, AppError> {
+    if let Some(error) = validate_contact(&form.description, &form.admin_mail) {
+        let token_str = csrf_token.authenticity_token().unwrap_or_default();
+        let content = new_contact_content(&token_str, &form.description, &form.admin_mail, Some(&error), "");
+        return Err(AppError::invalid_form(&content, &session).await);
+    }
+    if form.preview.is_some() {
+        let token_str = csrf_token.authenticity_token().unwrap_or_default();
+        let preview = markdown::render(&form.description);
+        let content = new_contact_content(&token_str, &form.description, &form.admin_mail, None, &preview);
+        return Ok(render_layout(&content, &session).await.into_response());
+    }
 
-    Ok(Redirect::to("/protected/contact/all"))
-}
+    let (description, admin_mail, author) = (form.description.clone(), form.admin_mail.clone(), user.author());
+    let id = db::run(&pool, move |conn| SqliteRepository::new(conn).insert(&description, &admin_mail, &author)).await?;
 
-#[derive(Deserialize)]
-pub struct ContactUpdateForm {
-    description: String,
-    admin_mail: String,
-    #[serde(default)]
-    #[serde(deserialize_with = "deserialize_checkbox")]
-    active: bool,
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "contact.create",
+        "contact",
+        Some(id),
+        None,
+        Some(json!({ "description": form.description, "admin_mail": form.admin_mail, "active": false })),
+    )
+    .await;
+
+    Ok(Redirect::to("/protected/contact/all").into_response())
 }
 
-fn get_all_contact_records() -> Vec<(i64, String, String, bool)> {
-    let conn = get_connection().unwrap();
-    let mut stmt = conn
-        .prepare("SELECT id, description, admin_mail, active FROM contact ORDER BY id DESC")
-        .unwrap();
+pub async fn contact_all(pool: &Pool, session: &Session) -> Result<Html<String>, AppError> {
+    let mut contact_list = db::run(pool, |conn| ContactRepository::list(&SqliteRepository::new(conn))).await?;
+    for entry in &mut contact_list {
+        entry.description = markdown::render(&entry.description);
+    }
 
-    stmt.query_map([], |row| {
-        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
-    })
-    .unwrap()
-    .map(|r| r.unwrap())
-    .collect()
-}
-
-pub async fn contact_all(session: &tower_sessions::Session) -> Result<Html<String>, StatusCode> {
-    let contact_list = get_all_contact_records();
-
-    let content = format!(
-        r#"
-        <section>
-            <h2>All Contact Entries</h2>
-            <div class="contact-entries">
-                {}
-            </div>
-            <a href="/protected/contact/new" class="button">Create New Contact</a>
-        </section>
-        "#,
-        contact_list.iter()
-            .map(|(id, desc, email, active)| format!(
-                "<div class='entry'><p><a href='/protected/contact/{}'>{}</a></p><p>{}</p><p>Email: {}</p><p>Status: {}</p></div>",
-                id, id, desc, email, if *active { "Active" } else { "Inactive" }
-            ))
-            .collect::<String>()
-    );
+    let content = templates::render(&ContactListView { entries: contact_list });
 
     let rendered = render_layout(&content, session).await;
     Ok(rendered)
 }
-fn select_contact_by_id(id: i64) -> (i64, String, String, bool) {
-    let conn = get_connection().unwrap();
-    conn.query_row(
-        "SELECT id, description, admin_mail, active FROM contact WHERE id = ?",
-        [id],
-        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
-    )
-    .unwrap()
+/// Loads an entry that hasn't been moved to the trash.
+async fn select_contact_by_id(pool: &Pool, id: i64) -> Result<ContactEntry, AppError> {
+    db::run(pool, move |conn| ContactRepository::get(&SqliteRepository::new(conn), id))
+        .await?
+        .filter(|entry| entry.deleted_at.is_none())
+        .ok_or(AppError::NotFound)
+}
+
+/// Loads a revision of entry `id`.
+async fn select_revision(pool: &Pool, id: i64, revision_id: i64) -> Result<ContactRevision, AppError> {
+    db::run(pool, move |conn| SqliteRepository::new(conn).revision(revision_id))
+        .await?
+        .filter(|revision| revision.contact_id == id)
+        .ok_or(AppError::NotFound)
+}
+
+async fn history_content(
+    pool: &Pool,
+    contact: &ContactEntry,
+    token_str: &str,
+) -> Result<(Option<ContactRevision>, String), AppError> {
+    let id = contact.id;
+    let revisions = db::run(pool, move |conn| SqliteRepository::new(conn).revisions(id)).await?;
+    let summaries: Vec<RevisionSummary> = revisions
+        .iter()
+        .map(|revision| RevisionSummary { id: revision.id, author_name: &revision.author_name, created_at: &revision.created_at })
+        .collect();
+    let history = revisions_content(BASE, id, token_str, contact.published_revision_id, contact.active, &summaries);
+    Ok((revisions.into_iter().next(), history))
+}
+
+/// The `locale` tab of the details screen, translating the published text.
+async fn translation_content(
+    pool: &Pool,
+    contact: ContactEntry,
+    locale: &'static str,
+    token_str: &str,
+) -> Result<String, AppError> {
+    let id = contact.id;
+    let translation =
+        db::run(pool, move |conn| stored_translation(&SqliteRepository::new(conn), TranslatedContent::Contact, id, locale)).await?;
+    let action = format!("{}/{}/translations", BASE, id);
+    let source = TranslationSource {
+        action: &action,
+        update_id: None,
+        heading: String::new(),
+        title: None,
+        body_label: "field-description",
+        body: &contact.description,
+    };
+    Ok(templates::render(&ContactDetailsView {
+        csrf_token: token_str,
+        id,
+        tabs: tabs(&format!("{}/{}", BASE, id), locale),
+        translation: translation_form(token_str, &source, translation),
+        description: "",
+        admin_mail: "",
+        error: "",
+        preview: "",
+        history: "",
+    }))
 }
 
 pub async fn contact_details(
+    pool: &Pool,
     Path(id): Path<i64>,
+    Query(query): Query<TranslationQuery>,
     csrf_token: CsrfToken,
-    session: &tower_sessions::Session,
-) -> Result<Html<String>, StatusCode> {
-    let contact = select_contact_by_id(id);
-    let token_str = csrf_token.authenticity_token().unwrap();
-
-    let content = format!(
-        r#"
-        <section>
-            <h2>Contact Entry Details</h2>
-            <h3>You can use HTML in the description.</h3>
-            <div class="contact-entry">
-                <form method="POST" action="/protected/contact/{}/update">
-                    <input type="hidden" name="csrf_token" value="{}">
-                    <div>
-                        <label>Description:</label>
-                        <textarea name="description" required>{}</textarea>
-                    </div>
-                    <div>
-                        <label>Admin Email:</label>
-                        <input type="email" name="admin_mail" value="{}" required>
-                    </div>
-                    <label>
-                        <input type="checkbox" name="active" value="1" {}>
-                        Active
-                    </label>
-                    <button type="submit">Update</button>
-                </form>
-                <form method="POST" action="/protected/contact/{}/delete" 
-                    onsubmit="return confirm('Are you sure?')">
-                    <input type="hidden" name="csrf_token" value="{}">
-                    <button type="submit" class="delete">Delete</button>
-                </form>
-            </div>
-        </section>
-        "#,
-        contact.0,
-        token_str,
-        contact.1,
-        contact.2,
-        if contact.3 { "checked" } else { "" },
-        contact.0,
-        token_str
-    );
+    session: &Session,
+) -> Result<Html<String>, AppError> {
+    let contact = select_contact_by_id(pool, id).await?;
+    let token_str = csrf_token.authenticity_token().unwrap_or_default();
+    if !query.is_default() {
+        let content = translation_content(pool, contact, query.locale(), &token_str).await?;
+        return Ok(render_layout(&content, session).await);
+    }
+    let (latest, history) = history_content(pool, &contact, &token_str).await?;
+    let (description, admin_mail) = latest
+        .map(|revision| (revision.description, revision.admin_mail))
+        .unwrap_or((contact.description, contact.admin_mail));
+
+    let content = contact_details_content(&token_str, contact.id, &description, &admin_mail, None, "", &history);
     let rendered = render_layout(&content, session).await;
     Ok(rendered)
 }
 
+/// Saves the form as a new revision; what visitors see only changes on publish.
 pub async fn contact_update(
+    State(pool): State<Pool>,
     Path(id): Path<i64>,
-    Form(form): Form<ContactUpdateForm>,
-) -> Result<Redirect, StatusCode> {
-    let conn = get_connection().unwrap();
-
-    if form.active {
-        conn.execute("UPDATE contact SET active = 0 WHERE active = 1", [])
-            .unwrap();
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    csrf_token: CsrfToken,
+    session: Session,
+    Form(form): Form<ContactForm>,
+) -> Result<Response, AppError> {
+    let contact = select_contact_by_id(&pool, id).await?;
+    if let Some(error) = validate_contact(&form.description, &form.admin_mail) {
+        let token_str = csrf_token.authenticity_token().unwrap_or_default();
+        let (_, history) = history_content(&pool, &contact, &token_str).await?;
+        let content =
+            contact_details_content(&token_str, id, &form.description, &form.admin_mail, Some(&error), "", &history);
+        return Err(AppError::invalid_form(&content, &session).await);
     }
+    if form.preview.is_some() {
+        let token_str = csrf_token.authenticity_token().unwrap_or_default();
+        let (_, history) = history_content(&pool, &contact, &token_str).await?;
+        let preview = markdown::render(&form.description);
+        let content =
+            contact_details_content(&token_str, id, &form.description, &form.admin_mail, None, &preview, &history);
+        return Ok(render_layout(&content, &session).await.into_response());
+    }
+
+    let (description, admin_mail, author) = (form.description.clone(), form.admin_mail.clone(), user.author());
+    let revision_id =
+        db::run(&pool, move |conn| SqliteRepository::new(conn).save(id, &description, &admin_mail, &author)).await?;
 
-    conn.execute(
-        "UPDATE contact SET description = ?, admin_mail = ?, active = ? WHERE id = ?",
-        params![form.description, form.admin_mail, form.active, id],
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "contact.save",
+        "contact",
+        Some(id),
+        None,
+        Some(json!({ "revision_id": revision_id, "description": form.description, "admin_mail": form.admin_mail })),
     )
-    .unwrap();
+    .await;
 
-    Ok(Redirect::to("/protected/contact/all"))
+    Ok(Redirect::to(&format!("{}/{}", BASE, id)).into_response())
 }
 
-pub async fn contact_delete(Path(id): Path<i64>) -> Result<Redirect, StatusCode> {
-    let conn = get_connection().unwrap();
-    conn.execute("DELETE FROM contact WHERE id = ?", [id])
-        .unwrap();
+/// Makes `revision_id` the published contact page, replacing whichever entry was live.
+pub async fn contact_publish(
+    State(pool): State<Pool>,
+    Path((id, revision_id)): Path<(i64, i64)>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let before = select_contact_by_id(&pool, id).await?;
+    select_revision(&pool, id, revision_id).await?;
+    db::run(&pool, move |conn| SqliteRepository::new(conn).publish(id, revision_id)).await?;
+
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "contact.publish",
+        "contact",
+        Some(id),
+        Some(json!({ "active": before.active, "published_revision_id": before.published_revision_id })),
+        Some(json!({ "active": true, "published_revision_id": revision_id })),
+    )
+    .await;
+    Ok(Redirect::to(&format!("{}/{}", BASE, id)))
+}
+
+/// Copies an old revision into a new one, publishing it if the entry is live.
+pub async fn contact_restore(
+    State(pool): State<Pool>,
+    Path((id, revision_id)): Path<(i64, i64)>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let contact = select_contact_by_id(&pool, id).await?;
+    let revision = select_revision(&pool, id, revision_id).await?;
+    let (active, author) = (contact.active, user.author());
+    let restored = db::run(&pool, move |conn| {
+        let repository = SqliteRepository::new(conn);
+        let restored = repository.save(id, &revision.description, &revision.admin_mail, &author)?;
+        if active {
+            repository.publish(id, restored)?;
+        }
+        Ok(restored)
+    })
+    .await?;
+
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "contact.restore",
+        "contact",
+        Some(id),
+        Some(json!({ "revision_id": revision_id })),
+        Some(json!({ "revision_id": restored, "published": active })),
+    )
+    .await;
+    Ok(Redirect::to(&format!("{}/{}", BASE, id)))
+}
+
+pub async fn contact_unpublish(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let before = select_contact_by_id(&pool, id).await?;
+    db::run(&pool, move |conn| SqliteRepository::new(conn).unpublish(id)).await?;
+
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "contact.unpublish",
+        "contact",
+        Some(id),
+        Some(json!({ "active": before.active })),
+        Some(json!({ "active": false })),
+    )
+    .await;
+    Ok(Redirect::to(&format!("{}/{}", BASE, id)))
+}
+
+fn revision_text(revision: &ContactRevision) -> String {
+    format!("Email: {}\n{}", revision.admin_mail, revision.description)
+}
+
+pub async fn contact_diff(
+    pool: &Pool,
+    Path(id): Path<i64>,
+    Query(query): Query<DiffQuery>,
+    session: &Session,
+) -> Result<Html<String>, AppError> {
+    select_contact_by_id(pool, id).await?;
+    let old = select_revision(pool, id, query.from).await?;
+    let new = select_revision(pool, id, query.to).await?;
+
+    let content = diff_content(BASE, id, old.id, new.id, &revision_text(&old), &revision_text(&new));
+    Ok(render_layout(&content, session).await)
+}
+
+/// Moves the entry to the trash; it stops being published and can be restored from there.
+pub async fn contact_delete(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let before = select_contact_by_id(&pool, id).await?;
+    db::run(&pool, move |conn| ContactRepository::delete(&SqliteRepository::new(conn), id)).await?;
+
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "contact.delete",
+        "contact",
+        Some(id),
+        Some(json!(before)),
+        None,
+    )
+    .await;
     Ok(Redirect::to("/protected/contact/all"))
 }
 
-fn deserialize_checkbox<'de, D>(deserializer: D) -> Result<bool, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    match String::deserialize(deserializer) {
-        Ok(string) => Ok(string == "1" || string == "on" || string == "true"),
-        Err(_) => Ok(false),
-    }
+pub async fn contact_trash(pool: &Pool, csrf_token: CsrfToken, session: &Session) -> Result<Html<String>, AppError> {
+    let entries = db::run(pool, |conn| SqliteRepository::new(conn).trash()).await?;
+    let token_str = csrf_token.authenticity_token().unwrap_or_default();
+    let entries: Vec<(i64, String)> =
+        entries.into_iter().map(|entry| (entry.id, entry.deleted_at.unwrap_or_default())).collect();
+
+    let content = trash_content(BASE, &token_str, &entries);
+    Ok(render_layout(&content, session).await)
+}
+
+/// Loads an entry from the trash.
+async fn select_deleted(pool: &Pool, id: i64) -> Result<ContactEntry, AppError> {
+    db::run(pool, move |conn| ContactRepository::get(&SqliteRepository::new(conn), id))
+        .await?
+        .filter(|entry| entry.deleted_at.is_some())
+        .ok_or(AppError::NotFound)
+}
+
+/// Takes an entry out of the trash as an unpublished draft.
+pub async fn contact_undelete(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    select_deleted(&pool, id).await?;
+    db::run(&pool, move |conn| SqliteRepository::new(conn).undelete(id)).await?;
+
+    audit::record(&pool, &user, &ip, "contact.undelete", "contact", Some(id), None, None).await;
+    Ok(Redirect::to(&format!("{}/{}", BASE, id)))
+}
+
+/// Permanently removes a trashed entry and its revisions.
+pub async fn contact_purge(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let before = select_deleted(&pool, id).await?;
+    db::run(&pool, move |conn| SqliteRepository::new(conn).purge(id)).await?;
+
+    audit::record(&pool, &user, &ip, "contact.purge", "contact", Some(id), Some(json!(before)), None).await;
+    Ok(Redirect::to("/protected/contact/trash"))
 }
@@ -1,32 +1,35 @@
-use crate::{controllers::page::Page, render_page_or_error};
+use crate::{auth::CurrentUser, controllers::page::Page, render_page_or_error};
 use axum::response::Html;
+use crate::views::templates::{self, filters, view};
+use crate::views::themes::theme_manager::{self, ThemeProblem};
 use tower_sessions::Session;
 
+view! {
+    "dashboard.html",
+    struct DashboardView<'a> {
+        username: &'a str,
+        role: String,
+        can_edit_pages: bool,
+        can_create_campaigns: bool,
+        can_manage_users: bool,
+        /// Broken theme packages, shown to those who can fix them.
+        theme_problems: &'a [ThemeProblem],
+    }
+}
 
-pub async fn dashboard(session: Session) -> Html<String> {
-    let username = session
-        .get::<String>("user_id")
-        .await
-        .unwrap_or(None)
-        .unwrap_or_default();
-
+pub async fn dashboard(user: &CurrentUser, session: Session) -> Html<String> {
     let mut page = Page::new("Dashboard", &session)
         .with_meta_description("Admin Dashboard - Manage your crowdfunding platform");
 
-    let content = format!(
-        r#"
-        <section class="dashboard">
-            <h1>Dashboard</h1>
-            <p class="user-info">Logged in as: <strong>{}</strong></p>
-            <div class="dashboard-links">
-                <a href="/protected/about/all" class="button">Open About Me Options</a>
-                <a href="/protected/contact/all" class="button">Open Contact Options</a>
-                <a href="/protected/campaigns" class="button">Open Campaign Options</a>
-            </div>
-        </section>
-        "#,
-        username,
-    );
+    let themes = theme_manager::current();
+    let content = templates::render(&DashboardView {
+        username: &user.username,
+        role: user.role.label(),
+        can_edit_pages: user.can_edit_pages(),
+        can_create_campaigns: user.can_create_campaigns(),
+        can_manage_users: user.can_manage_users(),
+        theme_problems: if user.can_manage_users() { themes.problems() } else { &[] },
+    });
 
     page.set_content(content);
     render_page_or_error!(page, "dashboard")
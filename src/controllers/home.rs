@@ -1,58 +1,48 @@
+use crate::controllers::campaigns::CampaignCard;
 use crate::controllers::page::Page;
-use crate::{db, render_page_or_error};
+use crate::db::{self, Pool};
+use crate::i18n;
+use crate::render_page_or_error;
+use crate::models::CampaignFilter;
+use crate::repositories::{localize_campaign, CampaignRepository, SqliteRepository, TranslationRepository};
+use crate::views::templates::{self, filters, view};
 use axum::response::Html;
 
-pub async fn home(session: &tower_sessions::Session) -> Html<String> {
-    let mut page = Page::new("Home", session)
-        .with_meta_description("Decentralized Crowdfunding with Monero - Support innovative projects");
+/// Active campaigns shown on the home page; the rest are linked to.
+const HOME_CAMPAIGNS: i64 = 10;
+
+view! {
+    "home.html",
+    struct HomeView {
+        /// A lone active campaign is shown in full.
+        single: bool,
+        campaigns: Vec<CampaignCard>,
+        /// More active campaigns exist than are shown.
+        more: bool,
+    }
+}
 
-    let campaigns = db::get_campaigns().unwrap_or_else(|_| vec![]);
+pub fn home_content(repository: &(impl CampaignRepository + TranslationRepository)) -> String {
+    let filter = CampaignFilter::active();
+    let mut campaigns = repository.list(&filter, HOME_CAMPAIGNS, 0).unwrap_or_else(|_| vec![]);
+    for campaign in &mut campaigns {
+        localize_campaign(repository, campaign, i18n::current());
+    }
 
-    let content = if campaigns.len() == 1 {
-        let (id, title, description, goal_amount, current_amount, status, xmr_address, created_at, updated_at) = &campaigns[0];
-        format!(
-            r#"
-            <section class="campaign-details">
-                <h1>{} 🚀</h1>
-                <p>{}</p>
-                <div class="campaign-stats">
-                    <p>Goal Amount: {} XMR 🎯</p>
-                    <p>Current Amount: {} XMR 💰</p>
-                    <p>Status: {} ✨</p>
-                    <p>Monero Address: {} 🔒</p>
-                    <p>Created: {}</p>
-                    <p>Updated: {}</p>
-                </div>
-            </section>
-            "#,
-            title, description, goal_amount, current_amount, status, xmr_address, created_at, updated_at
-        )
-    } else {
-        let mut content = String::from(r#"<section class="campaign-list"><h2>Active Campaigns 🎯</h2>"#);
-        for (id, title, description, goal_amount, current_amount, status, xmr_address, created_at, updated_at) in campaigns {
-            content.push_str(&format!(
-                r#"
-                <div class="campaign">
-                    <h2>{} 🚀</h2>
-                    <p>{}</p>
-                    <div class="campaign-stats">
-                        <p>Goal: {} XMR 🎯</p>
-                        <p>Current: {} XMR 💰</p>
-                        <p>Status: {} ✨</p>
-                        <p>Monero Address: {} 🔒</p>
-                        <p>Created: {}</p>
-                        <p>Updated: {}</p>
-                    </div>
-                    <a href="/campaigns/{}" class="button">View Details →</a>
-                </div>
-                "#,
-                title, description, goal_amount, current_amount, status, xmr_address, created_at, updated_at, id
-            ));
-        }
-        content.push_str("</section>");
-        content
-    };
+    templates::render(&HomeView {
+        single: campaigns.len() == 1,
+        more: campaigns.len() > 1 && repository.count(&filter).unwrap_or(0) > HOME_CAMPAIGNS,
+        campaigns: campaigns.iter().map(CampaignCard::from).collect(),
+    })
+}
+
+pub async fn home(pool: &Pool, session: &tower_sessions::Session) -> Html<String> {
+    let mut page = Page::new("Home", session)
+        .with_meta_description("Decentralized Crowdfunding with Monero - Support innovative projects");
 
-    page.set_content(content);
+    match db::run(pool, |conn| Ok(home_content(&SqliteRepository::new(conn)))).await {
+        Ok(content) => page.set_content(content),
+        Err(_) => return Html(String::from("Error loading home page")),
+    }
     render_page_or_error!(page, "home page")
 }
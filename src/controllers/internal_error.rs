@@ -0,0 +1,22 @@
+use crate::controllers::page::Page;
+use crate::render_page_or_error;
+use crate::views::templates::{self, filters, view};
+use axum::http::StatusCode;
+use axum::response::Html;
+
+view! {
+    "errors/internal.html",
+    struct InternalErrorView<'a> {
+        correlation_id: &'a str,
+    }
+}
+
+pub async fn internal_error(session: &tower_sessions::Session, correlation_id: &str) -> Html<String> {
+    let mut page = Page::new("500 - Something Went Wrong", session)
+        .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        .with_meta_description("Something went wrong");
+
+    let content = templates::render(&InternalErrorView { correlation_id });
+    page.set_content(content);
+    render_page_or_error!(page, "error page")
+}
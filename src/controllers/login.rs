@@ -1,6 +1,11 @@
-use crate::{db, render_page_or_error};
+use crate::audit::{self, ClientIp};
+use crate::auth;
+use crate::sessions::{self, UserAgent};
+use crate::db::{self, Pool};
+use crate::error::AppError;
+use crate::render_page_or_error;
 use axum::{
-    http::StatusCode,
+    extract::State,
     response::{Html, Redirect},
     Form,
 };
@@ -8,6 +13,14 @@ use axum_csrf::CsrfToken;
 use serde::Deserialize;
 use tower_sessions::Session;
 use crate::controllers::page::Page;
+use crate::views::templates::{self, filters, view};
+
+view! {
+    "login.html",
+    struct LoginView<'a> {
+        csrf_token: &'a str,
+    }
+}
 
 #[derive(Deserialize)]
 pub struct LoginForm {
@@ -20,52 +33,52 @@ pub async fn login_page(csrf_token: CsrfToken, session: &Session) -> Html<String
         .with_csrf_token(csrf_token)
         .with_meta_description("Login to access the admin dashboard");
 
-    let content = format!(
-        r#"
-        <section class="login-form">
-            <h2>Login</h2>
-            <form method="POST" action="/login">
-                <input type="hidden" name="csrf_token" value="{}">
-                <div class="form-group">
-                    <input type="text" name="username" placeholder="Username" required>
-                </div>
-                <div class="form-group">
-                    <input type="password" name="password" placeholder="Password" required>
-                </div>
-                <button type="submit">Login</button>
-            </form>
-        </section>
-        "#,
-        page.get_csrf_token().unwrap_or(&String::new())
-    );
+    let content = templates::render(&LoginView {
+        csrf_token: page.get_csrf_token().map(String::as_str).unwrap_or(""),
+    });
 
     page.set_content(content);
     render_page_or_error!(page, "login page")
 }
 
 pub async fn login_post(
+    State(pool): State<Pool>,
     session: Session,
+    ip: ClientIp,
+    user_agent: UserAgent,
     Form(form): Form<LoginForm>,
-) -> Result<Redirect, (StatusCode, String)> {
-    match db::get_connection() {
-        Ok(conn) => {
-            let result = conn.query_row(
-                "SELECT id FROM users WHERE username = ? AND password = ?",
-                [&form.username, &form.password],
-                |row| row.get::<_, i64>(0),
-            );
+) -> Result<Redirect, AppError> {
+    let (username, password) = (form.username.clone(), form.password);
+    let login = db::run(&pool, move |conn| {
+        let login = db::get_login(conn, &username)
+            .unwrap_or(None)
+            .filter(|(_, stored)| auth::verify_password(stored, &password));
+        let Some((user_id, stored)) = login else {
+            return Ok(None);
+        };
 
-            match result {
-                Ok(_) => {
-                    session
-                        .insert("user_id", form.username)
-                        .await
-                        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-                    Ok(Redirect::to("/protected/dashboard"))
-                }
-                Err(_) => Ok(Redirect::to("/login"))
-            }
+        if auth::needs_rehash(&stored) {
+            let password_hash = auth::hash_password(&password)
+                .map_err(|e| db::DatabaseError::PasswordHash(e.to_string()))?;
+            db::update_user_password(conn, user_id, &password_hash)?;
         }
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Ok(Some((user_id, db::get_user_totp(conn, user_id)?)))
+    })
+    .await?;
+
+    let Some((user_id, totp)) = login else {
+        audit::record_login(&pool, None, &form.username, &ip, "login.failure").await;
+        return Ok(Redirect::to("/login"));
+    };
+
+    if let Some((_, true, _)) = totp {
+        session
+            .insert("pending_2fa_user", user_id)
+            .await?;
+        return Ok(Redirect::to("/login/totp"));
     }
-}
\ No newline at end of file
+
+    sessions::start(&pool, &session, user_id, &ip, &user_agent).await?;
+    audit::record_login(&pool, Some(user_id), &form.username, &ip, "login.success").await;
+    Ok(Redirect::to("/protected/dashboard"))
+}
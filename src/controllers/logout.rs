@@ -1,7 +1,9 @@
-use axum::response::Redirect;
+use crate::db::Pool;
+use crate::sessions;
+use axum::{extract::State, response::Redirect};
 use tower_sessions::Session;
 
-pub async fn logout(session: Session) -> Redirect {
-    session.clear().await;
+pub async fn logout(State(pool): State<Pool>, session: Session) -> Redirect {
+    sessions::end(&pool, &session).await;
     Redirect::to("/login")
 }
@@ -1,9 +1,21 @@
+pub mod account;
+pub mod assets;
 pub mod about;
+pub mod address_changes;
+pub mod audit;
 pub mod campaigns;
 pub mod contact;
 pub mod dashboard;
 pub mod fourofour;
 pub mod home;
+pub mod internal_error;
 pub mod login;
 pub mod logout;
-pub mod page;
\ No newline at end of file
+pub mod page;
+pub mod preferences;
+pub mod revisions;
+pub mod sessions;
+pub mod themes;
+pub mod totp;
+pub mod translations;
+pub mod users;
\ No newline at end of file
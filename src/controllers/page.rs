@@ -5,7 +5,7 @@ use axum::{
 use axum_csrf::CsrfToken;
 use tower_sessions::Session;
 
-use crate::views::layout::render_layout;
+use crate::views::layout::render_layout_with_theme;
 
 pub struct Page<'a> {
     title: &'a str,
@@ -14,6 +14,7 @@ pub struct Page<'a> {
     csrf_token: Option<String>,
     status: StatusCode,
     meta_description: Option<String>,
+    theme: Option<String>,
 }
 
 impl<'a> Page<'a> {
@@ -25,6 +26,7 @@ impl<'a> Page<'a> {
             csrf_token: None,
             status: StatusCode::OK,
             meta_description: None,
+            theme: None,
         }
     }
 
@@ -43,6 +45,12 @@ impl<'a> Page<'a> {
         self
     }
 
+    /// Styles the page with the theme `theme` instead of the active one, to preview it.
+    pub fn with_theme(mut self, theme: String) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
     pub fn add_content(&mut self, content: &str) {
         self.content.push_str(content);
     }
@@ -52,7 +60,7 @@ impl<'a> Page<'a> {
     }
 
     pub async fn render(self) -> Result<Html<String>, StatusCode> {
-        Ok(render_layout(&self.content, self.session).await)
+        Ok(render_layout_with_theme(&self.content, self.session, self.theme.as_deref()).await)
     }
     pub fn get_csrf_token(&self) -> Option<&String> {
         self.csrf_token.as_ref()
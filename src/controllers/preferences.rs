@@ -0,0 +1,31 @@
+use crate::i18n;
+use crate::views::themes::color_scheme::ColorScheme;
+use axum::{
+    extract::Form,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct ColorSchemeForm {
+    pub scheme: String,
+}
+
+/// The path of the `Referer` when it points at this site, so the toggle returns to the same page.
+fn back_path(headers: &HeaderMap) -> Option<String> {
+    let referer = headers.get(header::REFERER)?.to_str().ok()?;
+    let host = headers.get(header::HOST)?.to_str().ok()?;
+    let path = ["http://", "https://"]
+        .iter()
+        .find_map(|scheme| referer.strip_prefix(scheme)?.strip_prefix(host))?;
+    (path.starts_with('/') && !path.starts_with("//")).then(|| path.to_string())
+}
+
+/// Stores the visitor's colour scheme in a cookie; works without JavaScript or a session.
+pub async fn set_color_scheme(headers: HeaderMap, Form(form): Form<ColorSchemeForm>) -> Result<Response, (StatusCode, String)> {
+    let scheme = ColorScheme::parse(&form.scheme)
+        .ok_or_else(|| (StatusCode::BAD_REQUEST, i18n::t_args("error-unknown-color-scheme", &[("scheme", form.scheme.clone())])))?;
+    let back = back_path(&headers).unwrap_or_else(|| "/".to_string());
+    Ok(([(header::SET_COOKIE, scheme.set_cookie())], Redirect::to(&back)).into_response())
+}
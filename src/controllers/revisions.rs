@@ -0,0 +1,121 @@
+use crate::diff::{diff_lines, DiffLine};
+use crate::views::templates::{self, filters, view};
+use serde::{Deserialize, Serialize};
+
+// Shared by the about and contact editors. `base` is the entry type's admin
+// path, e.g. `/protected/about`.
+
+pub struct RevisionSummary<'a> {
+    pub id: i64,
+    pub author_name: &'a str,
+    pub created_at: &'a str,
+}
+
+#[derive(Deserialize)]
+pub struct DiffQuery {
+    pub from: i64,
+    pub to: i64,
+}
+
+#[derive(Serialize)]
+struct RevisionRow<'a> {
+    id: i64,
+    author_name: &'a str,
+    created_at: &'a str,
+    /// The revision before this one, 0 for the first.
+    previous_id: i64,
+    published: bool,
+}
+
+view! {
+    "revisions/history.html",
+    struct HistoryView<'a> {
+        base: &'a str,
+        id: i64,
+        csrf_token: &'a str,
+        active: bool,
+        revisions: Vec<RevisionRow<'a>>,
+    }
+}
+
+#[derive(Serialize)]
+struct DiffRow<'a> {
+    kind: &'static str,
+    marker: char,
+    text: &'a str,
+}
+
+view! {
+    "revisions/diff.html",
+    struct DiffView<'a> {
+        base: &'a str,
+        id: i64,
+        from: i64,
+        to: i64,
+        lines: Vec<DiffRow<'a>>,
+    }
+}
+
+#[derive(Serialize)]
+struct TrashRow<'a> {
+    id: i64,
+    deleted_at: &'a str,
+}
+
+view! {
+    "revisions/trash.html",
+    struct TrashView<'a> {
+        base: &'a str,
+        csrf_token: &'a str,
+        has_entries: bool,
+        entries: Vec<TrashRow<'a>>,
+    }
+}
+
+/// Revision history, newest first, with compare, restore and publish actions per revision.
+pub fn revisions_content(
+    base: &str,
+    id: i64,
+    token_str: &str,
+    published_revision_id: Option<i64>,
+    active: bool,
+    revisions: &[RevisionSummary],
+) -> String {
+    let rows = revisions
+        .iter()
+        .enumerate()
+        .map(|(index, revision)| RevisionRow {
+            id: revision.id,
+            author_name: revision.author_name,
+            created_at: revision.created_at,
+            previous_id: revisions.get(index + 1).map_or(0, |previous| previous.id),
+            published: active && published_revision_id == Some(revision.id),
+        })
+        .collect();
+
+    templates::render(&HistoryView { base, id, csrf_token: token_str, active, revisions: rows })
+}
+
+/// The line diff between two revisions, escaped so markup in page content shows as source.
+pub fn diff_content(base: &str, id: i64, from: i64, to: i64, old: &str, new: &str) -> String {
+    let lines = diff_lines(old, new)
+        .into_iter()
+        .map(|line| match line {
+            DiffLine::Same(text) => DiffRow { kind: "same", marker: ' ', text },
+            DiffLine::Added(text) => DiffRow { kind: "added", marker: '+', text },
+            DiffLine::Removed(text) => DiffRow { kind: "removed", marker: '-', text },
+        })
+        .collect();
+
+    templates::render(&DiffView { base, id, from, to, lines })
+}
+
+/// Deleted entries with undelete and permanent delete actions.
+pub fn trash_content(base: &str, token_str: &str, entries: &[(i64, String)]) -> String {
+    templates::render(&TrashView {
+        base,
+        csrf_token: token_str,
+        has_entries: !entries.is_empty(),
+        entries: entries.iter().map(|(id, deleted_at)| TrashRow { id: *id, deleted_at }).collect(),
+    })
+}
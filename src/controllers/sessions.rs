@@ -0,0 +1,106 @@
+use crate::audit::{self, ClientIp};
+use crate::auth::CurrentUser;
+use crate::db::Pool;
+use crate::error::AppError;
+use crate::views::templates::{self, filters, view};
+use crate::{controllers::page::Page, db, render_page_or_error, sessions};
+use axum::{
+    extract::{Extension, Path, State},
+    response::{Html, Redirect},
+};
+use axum_csrf::CsrfToken;
+use serde::Serialize;
+use serde_json::json;
+use tower_sessions::Session;
+
+#[derive(Serialize)]
+struct SessionRow {
+    id: i64,
+    is_current: bool,
+    created_at: String,
+    last_seen: String,
+    ip: String,
+    user_agent: String,
+}
+
+view! {
+    "sessions.html",
+    struct SessionsView<'a> {
+        csrf_token: &'a str,
+        sessions: Vec<SessionRow>,
+    }
+}
+
+pub async fn sessions_page(pool: &Pool, user: &CurrentUser, csrf_token: CsrfToken, session: &Session) -> Html<String> {
+    let mut page = Page::new("Active Sessions", session)
+        .with_csrf_token(csrf_token)
+        .with_meta_description("Review and revoke your active sessions");
+    let token_str = page.get_csrf_token().cloned().unwrap_or_default();
+
+    let current = sessions::current_token_hash(session).await;
+    let user_id = user.id;
+    let user_sessions = db::run(pool, move |conn| db::get_user_sessions(conn, user_id))
+        .await
+        .unwrap_or_else(|_| vec![]);
+
+    let rows = user_sessions
+        .into_iter()
+        .map(|user_session| SessionRow {
+            id: user_session.id,
+            is_current: current.as_deref() == Some(user_session.token_hash.as_str()),
+            created_at: user_session.created_at.to_string(),
+            last_seen: user_session.last_seen.to_string(),
+            ip: user_session.ip,
+            user_agent: user_session.user_agent,
+        })
+        .collect();
+
+    let content = templates::render(&SessionsView { csrf_token: &token_str, sessions: rows });
+
+    page.set_content(content);
+    render_page_or_error!(page, "Active Sessions")
+}
+
+pub async fn revoke_session(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    session: Session,
+) -> Result<Redirect, AppError> {
+    let user_id = user.id;
+    db::run(&pool, move |conn| db::delete_user_session(conn, id, user_id))
+        .await?;
+    audit::record(&pool, &user, &ip, "session.revoke", "session", Some(id), None, None).await;
+
+    if sessions::is_active(&pool, &session, user.id).await {
+        Ok(Redirect::to("/protected/sessions"))
+    } else {
+        session.clear().await;
+        Ok(Redirect::to("/login"))
+    }
+}
+
+pub async fn revoke_all_sessions(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    session: Session,
+) -> Result<Redirect, AppError> {
+    let user_id = user.id;
+    let revoked = db::run(&pool, move |conn| db::delete_other_user_sessions(conn, user_id, None))
+        .await?;
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "session.revoke_all",
+        "user",
+        Some(user.id),
+        None,
+        Some(json!({ "revoked": revoked })),
+    )
+    .await;
+    session.clear().await;
+    Ok(Redirect::to("/login"))
+}
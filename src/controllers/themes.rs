@@ -0,0 +1,229 @@
+use crate::audit::{self, ClientIp};
+use crate::auth::CurrentUser;
+use crate::db::{self, Pool};
+use crate::error::AppError;
+use crate::i18n;
+use crate::views::templates::{self, filters, view};
+use crate::views::themes::package;
+use crate::views::themes::theme_manager::{self, ThemeProblem, ACTIVE_THEME_SETTING, BUILT_IN, CUSTOM_CSS_SETTING};
+use crate::{controllers::page::Page, render_page_or_error};
+use axum::{
+    extract::{Extension, Form, Path, Query, State},
+    http::StatusCode,
+    response::{Html, Redirect},
+};
+use axum_csrf::CsrfToken;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tower_sessions::Session;
+
+/// Largest stylesheet accepted for an added theme or the custom CSS.
+pub const MAX_CSS_BYTES: usize = 256 * 1024;
+
+#[derive(Deserialize)]
+pub struct ThemePreviewQuery {
+    preview: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ActivateThemeForm {
+    theme: String,
+}
+
+#[derive(Deserialize)]
+pub struct UploadThemeForm {
+    name: String,
+    css: String,
+}
+
+#[derive(Deserialize)]
+pub struct CustomCssForm {
+    custom_css: String,
+}
+
+#[derive(Serialize)]
+struct ThemeRow<'a> {
+    name: &'a str,
+    active: bool,
+    built_in: bool,
+    uploaded: bool,
+    /// Empty unless the theme is a package.
+    version: &'a str,
+    author: &'a str,
+}
+
+view! {
+    "themes.html",
+    struct ThemesView<'a> {
+        csrf_token: &'a str,
+        themes: Vec<ThemeRow<'a>>,
+        problems: &'a [ThemeProblem],
+        /// The theme this page is styled with, if it is not the active one.
+        previewing: &'a str,
+        custom_css: &'a str,
+        max_css_bytes: usize,
+    }
+}
+
+/// Added themes follow the package naming rules and may not take a built-in name.
+pub fn check_theme_name(name: &str) -> Result<(), String> {
+    package::check_name(name)?;
+    if BUILT_IN.iter().any(|(built_in, _)| *built_in == name) {
+        return Err(i18n::t("error-built-in-theme"));
+    }
+    Ok(())
+}
+
+fn check_css_size(css: &str) -> Result<(), AppError> {
+    if css.len() > MAX_CSS_BYTES {
+        let kib = (MAX_CSS_BYTES / 1024).to_string();
+        return Err(AppError::Rejected(StatusCode::PAYLOAD_TOO_LARGE, i18n::t_args("error-stylesheet-too-large", &[("kib", kib)])));
+    }
+    Ok(())
+}
+
+/// Saves a change with `save` and switches every following request to the result.
+async fn save_and_apply<F>(pool: &Pool, save: F) -> Result<(), AppError>
+where
+    F: FnOnce(&mut rusqlite::Connection) -> Result<(), db::DatabaseError> + Send + 'static,
+{
+    db::run(pool, save).await?;
+    Ok(theme_manager::refresh(pool).await?)
+}
+
+pub async fn themes_page(pool: &Pool, query: Query<ThemePreviewQuery>, csrf_token: CsrfToken, session: &Session) -> Html<String> {
+    let manager = theme_manager::current();
+    let uploaded = db::run(pool, |conn| db::get_uploaded_themes(conn)).await.unwrap_or_default();
+    let previewing = query
+        .preview
+        .as_deref()
+        .filter(|name| *name != manager.active_theme())
+        .filter(|name| manager.has_theme(name));
+
+    let mut page = Page::new("Themes", session)
+        .with_csrf_token(csrf_token)
+        .with_meta_description("Choose the site theme and custom CSS");
+    if let Some(theme) = previewing {
+        page = page.with_theme(theme.to_string());
+    }
+    let token_str = page.get_csrf_token().cloned().unwrap_or_default();
+
+    let content = templates::render(&ThemesView {
+        csrf_token: &token_str,
+        themes: manager
+            .theme_names()
+            .into_iter()
+            .map(|name| {
+                let (version, author) = manager.package_details(name).unwrap_or_default();
+                ThemeRow {
+                    name,
+                    active: name == manager.active_theme(),
+                    built_in: BUILT_IN.iter().any(|(built_in, _)| *built_in == name),
+                    uploaded: uploaded.iter().any(|(uploaded, _)| uploaded == name),
+                    version,
+                    author,
+                }
+            })
+            .collect(),
+        problems: manager.problems(),
+        previewing: previewing.unwrap_or_default(),
+        custom_css: manager.custom_css(),
+        max_css_bytes: MAX_CSS_BYTES,
+    });
+
+    page.set_content(content);
+    render_page_or_error!(page, "Themes")
+}
+
+pub async fn theme_activate(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    Form(form): Form<ActivateThemeForm>,
+) -> Result<Redirect, AppError> {
+    let manager = theme_manager::current();
+    let before = manager.active_theme().to_string();
+    if !manager.has_theme(&form.theme) {
+        return Err(AppError::Rejected(StatusCode::NOT_FOUND, i18n::t("error-theme-not-found")));
+    }
+
+    let theme = form.theme.clone();
+    save_and_apply(&pool, move |conn| db::set_setting(conn, ACTIVE_THEME_SETTING, &theme)).await?;
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "theme.activate",
+        "theme",
+        None,
+        Some(json!({ "theme": before })),
+        Some(json!({ "theme": form.theme })),
+    )
+    .await;
+    Ok(Redirect::to("/protected/themes"))
+}
+
+pub async fn theme_upload(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    Form(form): Form<UploadThemeForm>,
+) -> Result<Redirect, AppError> {
+    let name = form.name.trim().to_string();
+    check_theme_name(&name).map_err(|e| AppError::Rejected(StatusCode::BAD_REQUEST, e))?;
+    check_css_size(&form.css)?;
+
+    let (theme, css) = (name.clone(), form.css);
+    save_and_apply(&pool, move |conn| db::save_uploaded_theme(conn, &theme, &css)).await?;
+    audit::record(&pool, &user, &ip, "theme.upload", "theme", None, None, Some(json!({ "theme": name }))).await;
+    Ok(Redirect::to("/protected/themes"))
+}
+
+pub async fn theme_delete(
+    State(pool): State<Pool>,
+    Path(name): Path<String>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    let theme = name.clone();
+    let deleted = db::run(&pool, move |conn| {
+        // A deleted active theme hands over to `ACTIVE_THEME` again.
+        if db::get_setting(conn, ACTIVE_THEME_SETTING)?.as_deref() == Some(theme.as_str()) {
+            db::delete_setting(conn, ACTIVE_THEME_SETTING)?;
+        }
+        db::delete_uploaded_theme(conn, &theme)
+    })
+    .await?;
+    if !deleted {
+        return Err(AppError::Rejected(StatusCode::NOT_FOUND, i18n::t("error-theme-not-found")));
+    }
+
+    theme_manager::refresh(&pool).await?;
+    audit::record(&pool, &user, &ip, "theme.delete", "theme", None, Some(json!({ "theme": name })), None).await;
+    Ok(Redirect::to("/protected/themes"))
+}
+
+pub async fn custom_css_update(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    Form(form): Form<CustomCssForm>,
+) -> Result<Redirect, AppError> {
+    check_css_size(&form.custom_css)?;
+    let before = theme_manager::current().custom_css().to_string();
+
+    let custom_css = form.custom_css.clone();
+    save_and_apply(&pool, move |conn| db::set_setting(conn, CUSTOM_CSS_SETTING, &custom_css)).await?;
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "theme.custom_css",
+        "theme",
+        None,
+        Some(json!({ "custom_css": before })),
+        Some(json!({ "custom_css": form.custom_css })),
+    )
+    .await;
+    Ok(Redirect::to("/protected/themes"))
+}
@@ -0,0 +1,310 @@
+use crate::audit::{self, ClientIp};
+use crate::sessions::{self, UserAgent};
+use crate::db::Pool;
+use crate::error::AppError;
+use crate::{auth::CurrentUser, controllers::page::Page, db, render_page_or_error};
+use crate::views::layout::render_layout;
+use crate::views::templates::{self, filters, view};
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use axum::{
+    extract::{Extension, Form, State},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+use axum_csrf::CsrfToken;
+use qrcode::{render::svg, QrCode};
+use rand_core::{OsRng, RngCore};
+use rusqlite::Connection;
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+use totp_rs::{Algorithm, Secret, TOTP};
+use tower_sessions::Session;
+
+const TOTP_STEP: u64 = 30;
+const RECOVERY_CODE_COUNT: usize = 10;
+/// Wrong codes a user may enter before logins for them are locked for `LOCKOUT_MINUTES`.
+const MAX_LOGIN_ATTEMPTS: u32 = 5;
+const LOCKOUT_MINUTES: u32 = 15;
+
+view! {
+    "account/two_factor.html",
+    struct TwoFactorView<'a> {
+        csrf_token: &'a str,
+        enabled: bool,
+        /// A secret was generated but not confirmed yet.
+        pending: bool,
+        qr_svg: String,
+        secret: String,
+        uri: String,
+    }
+}
+
+view! {
+    "account/recovery_codes.html",
+    struct RecoveryCodesView {
+        codes: Vec<String>,
+    }
+}
+
+view! {
+    "login_totp.html",
+    struct LoginTotpView<'a> {
+        csrf_token: &'a str,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TotpCodeForm {
+    code: String,
+}
+
+fn build_totp(secret: &str, username: &str) -> Option<TOTP> {
+    let issuer = std::env::var("WEBSITE_TITLE")
+        .unwrap_or_else(|_| "MRS-Funding-Software".to_string())
+        .replace(':', "");
+    let secret = Secret::Encoded(secret.to_string()).to_bytes().ok()?;
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        0,
+        TOTP_STEP,
+        secret,
+        Some(issuer),
+        username.replace(':', ""),
+    )
+    .ok()
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    OsRng.fill_bytes(&mut bytes);
+    Secret::Raw(bytes.to_vec()).to_encoded().to_string()
+}
+
+/// Returns the time step the code matched, allowing one step of clock drift.
+/// Steps at or before `last_used_step` are rejected so a code can't be replayed.
+fn verify_totp_code(totp: &TOTP, code: &str, last_used_step: Option<i64>) -> Option<i64> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    let current_step = (now / TOTP_STEP) as i64;
+
+    (current_step - 1..=current_step + 1)
+        .filter(|step| last_used_step.is_none_or(|last| *step > last))
+        .find(|step| totp.check(code, *step as u64 * TOTP_STEP))
+}
+
+fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 5];
+            OsRng.fill_bytes(&mut bytes);
+            let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("{}-{}", &hex[..5], &hex[5..])
+        })
+        .collect()
+}
+
+fn hash_recovery_code(code: &str) -> Option<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(code.as_bytes(), &salt)
+        .ok()
+        .map(|hash| hash.to_string())
+}
+
+fn consume_recovery_code(conn: &Connection, user_id: i64, code: &str) -> Result<bool, db::DatabaseError> {
+    let code = code.trim().to_lowercase();
+    for (id, code_hash) in db::get_unused_recovery_codes(conn, user_id)? {
+        let matches = PasswordHash::new(&code_hash)
+            .map(|hash| Argon2::default().verify_password(code.as_bytes(), &hash).is_ok())
+            .unwrap_or(false);
+        if matches {
+            db::mark_recovery_code_used(conn, id)?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Checks a TOTP code, falling back to the user's unused recovery codes.
+fn verify_second_factor(conn: &Connection, user_id: i64, username: &str, code: &str) -> Result<bool, db::DatabaseError> {
+    let Some((secret, true, last_used_step)) = db::get_user_totp(conn, user_id)? else {
+        return Ok(false);
+    };
+
+    if let Some(totp) = build_totp(&secret, username) {
+        if let Some(step) = verify_totp_code(&totp, code.trim(), last_used_step) {
+            db::update_totp_last_used_step(conn, user_id, step)?;
+            return Ok(true);
+        }
+    }
+
+    consume_recovery_code(conn, user_id, code)
+}
+
+/// The 2FA part of the account page, depending on whether 2FA is off, being set up or on.
+pub async fn two_factor_section(pool: &Pool, user: &CurrentUser, token_str: &str) -> String {
+    let user_id = user.id;
+    let (enabled, secret) = match db::run(pool, move |conn| db::get_user_totp(conn, user_id)).await.unwrap_or(None) {
+        Some((secret, enabled, _)) => (enabled, Some(secret)),
+        None => (false, None),
+    };
+    let mut view = TwoFactorView {
+        csrf_token: token_str,
+        enabled,
+        pending: false,
+        qr_svg: String::new(),
+        secret: String::new(),
+        uri: String::new(),
+    };
+    if let Some(secret) = secret.filter(|_| !enabled) {
+        let uri = build_totp(&secret, &user.username)
+            .map(|totp| totp.get_url())
+            .unwrap_or_default();
+        let qr_svg = QrCode::new(uri.as_bytes())
+            .map(|code| code.render::<svg::Color>().min_dimensions(200, 200).build())
+            .unwrap_or_default();
+        view.qr_svg = qr_svg.find("<svg").map(|i| qr_svg[i..].to_string()).unwrap_or_default();
+        view.pending = true;
+        view.secret = secret;
+        view.uri = uri;
+    }
+    templates::render(&view)
+}
+
+pub async fn two_factor_setup(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+) -> Result<Redirect, AppError> {
+    let secret = generate_secret();
+    db::run(&pool, move |conn| db::save_pending_totp_secret(conn, user.id, &secret))
+        .await?;
+    Ok(Redirect::to("/protected/account"))
+}
+
+pub async fn two_factor_enable(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    session: Session,
+    Form(form): Form<TotpCodeForm>,
+) -> Result<Response, AppError> {
+    let user_id = user.id;
+    let Some((secret, false, _)) = db::run(&pool, move |conn| db::get_user_totp(conn, user_id))
+        .await?
+    else {
+        return Ok(Redirect::to("/protected/account").into_response());
+    };
+
+    let step = build_totp(&secret, &user.username)
+        .and_then(|totp| verify_totp_code(&totp, form.code.trim(), None));
+    let Some(step) = step else {
+        return Ok(Redirect::to("/protected/account").into_response());
+    };
+
+    let recovery_codes = generate_recovery_codes();
+    let code_hashes = recovery_codes
+        .iter()
+        .map(|code| hash_recovery_code(code))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| db::DatabaseError::PasswordHash("Failed to hash recovery codes".to_string()))?;
+    db::run(&pool, move |conn| db::enable_user_totp(conn, user_id, step, &code_hashes))
+        .await?;
+    audit::record(&pool, &user, &ip, "user.2fa_enable", "user", Some(user.id), None, None).await;
+
+    let content = templates::render(&RecoveryCodesView { codes: recovery_codes });
+    Ok(render_layout(&content, &session).await.into_response())
+}
+
+pub async fn two_factor_disable(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    Form(form): Form<TotpCodeForm>,
+) -> Result<Redirect, AppError> {
+    let (user_id, username) = (user.id, user.username.clone());
+    let disabled = db::run(&pool, move |conn| {
+        let verified = verify_second_factor(conn, user_id, &username, &form.code)?;
+        if verified {
+            db::disable_user_totp(conn, user_id)?;
+        }
+        Ok(verified)
+    })
+    .await?;
+
+    if disabled {
+        audit::record(&pool, &user, &ip, "user.2fa_disable", "user", Some(user.id), None, None).await;
+    }
+    Ok(Redirect::to("/protected/account"))
+}
+
+pub async fn login_totp_page(csrf_token: CsrfToken, session: &Session) -> Response {
+    if !matches!(session.get::<i64>("pending_2fa_user").await, Ok(Some(_))) {
+        return Redirect::to("/login").into_response();
+    }
+
+    let mut page = Page::new("Two-Factor Login", session)
+        .with_csrf_token(csrf_token)
+        .with_meta_description("Enter your two-factor authentication code");
+
+    let content = templates::render(&LoginTotpView {
+        csrf_token: page.get_csrf_token().map(String::as_str).unwrap_or(""),
+    });
+
+    page.set_content(content);
+    render_page_or_error!(page, "two-factor login page").into_response()
+}
+
+pub async fn login_totp_post(
+    State(pool): State<Pool>,
+    session: Session,
+    ip: ClientIp,
+    user_agent: UserAgent,
+    Form(form): Form<TotpCodeForm>,
+) -> Result<Redirect, AppError> {
+    let Some(user_id) = session
+        .get::<i64>("pending_2fa_user")
+        .await?
+    else {
+        return Ok(Redirect::to("/login"));
+    };
+
+    let verification = db::run(&pool, move |conn| {
+        let Some((_, username, _)) = db::get_user(conn, user_id)? else {
+            return Ok(None);
+        };
+        if !db::claim_totp_attempt(conn, user_id, MAX_LOGIN_ATTEMPTS, LOCKOUT_MINUTES)? {
+            return Ok(Some((username, None)));
+        }
+        let verified = verify_second_factor(conn, user_id, &username, &form.code)?;
+        if verified {
+            db::reset_totp_attempts(conn, user_id)?;
+        }
+        Ok(Some((username, Some(verified))))
+    })
+    .await?;
+    let Some((username, verified)) = verification else {
+        session.clear().await;
+        return Ok(Redirect::to("/login"));
+    };
+
+    match verified {
+        Some(true) => {}
+        Some(false) => {
+            audit::record_login(&pool, Some(user_id), &username, &ip, "login.2fa_failure").await;
+            return Ok(Redirect::to("/login/totp"));
+        }
+        // Locked out: the code was not checked, and the login starts over once the lock expires.
+        None => {
+            audit::record_login(&pool, Some(user_id), &username, &ip, "login.2fa_locked").await;
+            session.clear().await;
+            return Ok(Redirect::to("/login"));
+        }
+    }
+
+    session.clear().await;
+    sessions::start(&pool, &session, user_id, &ip, &user_agent).await?;
+    audit::record_login(&pool, Some(user_id), &username, &ip, "login.success").await;
+    Ok(Redirect::to("/protected/dashboard"))
+}
@@ -0,0 +1,230 @@
+use crate::audit::{self, ClientIp};
+use crate::auth::CurrentUser;
+use crate::db::{self, Pool};
+use crate::error::AppError;
+use crate::i18n;
+use crate::models::{TranslatedContent, Translation};
+use crate::repositories::{
+    AboutRepository, CampaignRepository, ContactRepository, SqliteRepository, TranslationRepository,
+};
+use crate::views::templates::{self, filters, view};
+use axum::{
+    extract::{Extension, Form, Path, State},
+    response::Redirect,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Selects the language tab of an edit screen with `?lang=<locale>`.
+#[derive(Default, Deserialize)]
+pub struct TranslationQuery {
+    lang: Option<String>,
+}
+
+impl TranslationQuery {
+    /// The selected locale; the default language edits the content itself.
+    pub fn locale(&self) -> &'static str {
+        self.lang.as_deref().and_then(i18n::supported).unwrap_or(i18n::DEFAULT_LOCALE)
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.locale() == i18n::DEFAULT_LOCALE
+    }
+}
+
+/// A language tab of an edit screen.
+#[derive(Serialize)]
+pub struct TranslationTab {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub href: String,
+    pub current: bool,
+}
+
+/// Tabs for every locale of the edit screen at `path`, with `selected` open.
+pub fn tabs(path: &str, selected: &str) -> Vec<TranslationTab> {
+    i18n::LOCALES
+        .iter()
+        .map(|(code, name, _)| TranslationTab {
+            code,
+            name,
+            href: if *code == i18n::DEFAULT_LOCALE { path.to_string() } else { format!("{}?lang={}", path, code) },
+            current: *code == selected,
+        })
+        .collect()
+}
+
+view! {
+    "translations/form.html",
+    struct TranslationFormView<'a> {
+        csrf_token: &'a str,
+        action: &'a str,
+        locale: &'a str,
+        default_locale: &'a str,
+        /// Zero unless the form translates a campaign update.
+        update_id: i64,
+        heading: String,
+        has_title: bool,
+        /// The message labelling the text field.
+        body_label: &'a str,
+        original_title: &'a str,
+        original_body: &'a str,
+        translation: Translation,
+    }
+}
+
+/// What a translation form translates, with its default-language text.
+pub struct TranslationSource<'a> {
+    pub action: &'a str,
+    /// Set for campaign updates, which are translated through their campaign.
+    pub update_id: Option<i64>,
+    pub heading: String,
+    /// `None` for content without a title.
+    pub title: Option<&'a str>,
+    pub body_label: &'static str,
+    pub body: &'a str,
+}
+
+/// The form editing the `translation` of `source` in its language.
+pub fn translation_form(csrf_token: &str, source: &TranslationSource, translation: Translation) -> String {
+    let locale = translation.locale.clone();
+    templates::render(&TranslationFormView {
+        csrf_token,
+        action: source.action,
+        locale: &locale,
+        default_locale: i18n::DEFAULT_LOCALE,
+        update_id: source.update_id.unwrap_or(0),
+        heading: source.heading.clone(),
+        has_title: source.title.is_some(),
+        body_label: source.body_label,
+        original_title: source.title.unwrap_or_default(),
+        original_body: source.body,
+        translation,
+    })
+}
+
+/// The stored translation of content `id` into `locale`, or a blank one.
+pub fn stored_translation(
+    repository: &impl TranslationRepository,
+    content: TranslatedContent,
+    id: i64,
+    locale: &str,
+) -> Result<Translation, db::DatabaseError> {
+    Ok(repository
+        .translation(content, id, locale)?
+        .unwrap_or_else(|| Translation { locale: locale.to_string(), ..Translation::default() }))
+}
+
+#[derive(Deserialize)]
+pub struct TranslationForm {
+    locale: String,
+    #[serde(default)]
+    update_id: Option<i64>,
+    #[serde(default)]
+    title: String,
+    body: String,
+}
+
+impl TranslationForm {
+    /// The submitted translation, if it is for a language other than the default.
+    fn translation(&self, has_title: bool) -> Option<Translation> {
+        let locale = i18n::LOCALES.iter().map(|(code, _, _)| *code).find(|code| *code == self.locale)?;
+        (locale != i18n::DEFAULT_LOCALE).then(|| Translation {
+            locale: locale.to_string(),
+            title: if has_title { self.title.trim().to_string() } else { String::new() },
+            body: self.body.trim().to_string(),
+        })
+    }
+}
+
+/// Stores `translation` of content `id`, recording the change in the audit log as `action`.
+async fn save(
+    pool: &Pool,
+    user: &CurrentUser,
+    ip: &ClientIp,
+    action: &str,
+    content: TranslatedContent,
+    id: i64,
+    translation: Translation,
+) -> Result<(), AppError> {
+    let saved = translation.clone();
+    let before = db::run(pool, move |conn| {
+        let repository = SqliteRepository::new(conn);
+        let before = repository.translation(content, id, &saved.locale)?;
+        repository.save_translation(content, id, &saved)?;
+        Ok(before)
+    })
+    .await?;
+    audit::record(
+        pool,
+        user,
+        ip,
+        action,
+        content.as_str(),
+        Some(id),
+        before.map(|before| json!(before)),
+        (!translation.is_blank()).then(|| json!(translation)),
+    )
+    .await;
+    Ok(())
+}
+
+/// Saves the translation of a campaign, or of one of its updates when the form names one.
+pub async fn save_campaign_translation(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    Form(form): Form<TranslationForm>,
+) -> Result<Redirect, AppError> {
+    match form.update_id {
+        Some(update_id) => {
+            let translation = form.translation(false).ok_or(AppError::NotFound)?;
+            let updates = db::run(&pool, move |conn| SqliteRepository::new(conn).updates(id)).await?;
+            if !updates.iter().any(|update| update.id == update_id) {
+                return Err(AppError::NotFound);
+            }
+            save(&pool, &user, &ip, "campaign_update.translate", TranslatedContent::CampaignUpdate, update_id, translation)
+                .await?;
+        }
+        None => {
+            let translation = form.translation(true).ok_or(AppError::NotFound)?;
+            save(&pool, &user, &ip, "campaign.translate", TranslatedContent::Campaign, id, translation).await?;
+        }
+    }
+    Ok(Redirect::to(&format!("/protected/campaigns/{}/edit?lang={}", id, form.locale)))
+}
+
+/// Saves the translation of an about entry. Unlike its text, it is live right away.
+pub async fn save_about_translation(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    Form(form): Form<TranslationForm>,
+) -> Result<Redirect, AppError> {
+    let translation = form.translation(false).ok_or(AppError::NotFound)?;
+    db::run(&pool, move |conn| AboutRepository::get(&SqliteRepository::new(conn), id))
+        .await?
+        .filter(|entry| entry.deleted_at.is_none())
+        .ok_or(AppError::NotFound)?;
+    save(&pool, &user, &ip, "about.translate", TranslatedContent::About, id, translation).await?;
+    Ok(Redirect::to(&format!("/protected/about/{}?lang={}", id, form.locale)))
+}
+
+/// Saves the translation of a contact entry. Unlike its text, it is live right away.
+pub async fn save_contact_translation(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    Form(form): Form<TranslationForm>,
+) -> Result<Redirect, AppError> {
+    let translation = form.translation(false).ok_or(AppError::NotFound)?;
+    db::run(&pool, move |conn| ContactRepository::get(&SqliteRepository::new(conn), id))
+        .await?
+        .filter(|entry| entry.deleted_at.is_none())
+        .ok_or(AppError::NotFound)?;
+    save(&pool, &user, &ip, "contact.translate", TranslatedContent::Contact, id, translation).await?;
+    Ok(Redirect::to(&format!("/protected/contact/{}?lang={}", id, form.locale)))
+}
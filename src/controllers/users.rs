@@ -0,0 +1,181 @@
+use crate::audit::{self, ClientIp};
+use crate::auth::{self, CurrentUser, Role};
+use crate::db::Pool;
+use crate::error::AppError;
+use crate::i18n;
+use crate::views::templates::{self, filters, view, SelectOption};
+use crate::{controllers::page::Page, db, render_page_or_error};
+use axum::{
+    extract::{Extension, Form, Path, State},
+    http::StatusCode,
+    response::{Html, Redirect},
+};
+use axum_csrf::CsrfToken;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tower_sessions::Session;
+
+#[derive(Deserialize)]
+pub struct NewUserForm {
+    username: String,
+    password: String,
+    role: String,
+}
+
+#[derive(Deserialize)]
+pub struct RoleForm {
+    role: String,
+}
+
+#[derive(Serialize)]
+struct UserRow {
+    id: i64,
+    username: String,
+    roles: Vec<SelectOption>,
+}
+
+view! {
+    "users.html",
+    struct UsersView<'a> {
+        csrf_token: &'a str,
+        users: Vec<UserRow>,
+        min_password_length: usize,
+        new_user_roles: Vec<SelectOption>,
+    }
+}
+
+fn role_options(selected: &str) -> Vec<SelectOption> {
+    let roles: Vec<(&'static str, &'static str)> = Role::ALL.iter().map(|role| (role.as_str(), role.message_key())).collect();
+    SelectOption::list(&roles, selected)
+}
+
+fn parse_role(role: &str) -> Result<Role, AppError> {
+    Role::parse(role).ok_or_else(|| AppError::Rejected(StatusCode::BAD_REQUEST, i18n::t("error-unknown-role")))
+}
+
+pub async fn users_page(pool: &Pool, csrf_token: CsrfToken, session: &Session) -> Html<String> {
+    let mut page = Page::new("User Management", session)
+        .with_csrf_token(csrf_token)
+        .with_meta_description("Manage admin users and their roles");
+    let token_str = page.get_csrf_token().cloned().unwrap_or_default();
+
+    let users = db::run(pool, |conn| db::get_users(conn)).await.unwrap_or_else(|_| vec![]);
+    let content = templates::render(&UsersView {
+        csrf_token: &token_str,
+        users: users
+            .into_iter()
+            .map(|(id, username, role)| UserRow { id, username, roles: role_options(&role) })
+            .collect(),
+        min_password_length: auth::MIN_PASSWORD_LENGTH,
+        new_user_roles: role_options(Role::Editor.as_str()),
+    });
+
+    page.set_content(content);
+    render_page_or_error!(page, "User Management")
+}
+
+pub async fn user_create(
+    State(pool): State<Pool>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    Form(form): Form<NewUserForm>,
+) -> Result<Redirect, AppError> {
+    let role = parse_role(&form.role)?;
+    let username = form.username.trim().to_string();
+
+    auth::check_username(&username).map_err(|e| AppError::Rejected(StatusCode::BAD_REQUEST, e))?;
+    auth::check_password_strength(&form.password, &username).map_err(|e| AppError::Rejected(StatusCode::BAD_REQUEST, e))?;
+
+    let (new_username, password) = (username.clone(), form.password);
+    let id = db::run(&pool, move |conn| {
+        if db::username_exists(conn, &new_username)? {
+            return Ok(None);
+        }
+        let password_hash = auth::hash_password(&password)
+            .map_err(|e| db::DatabaseError::PasswordHash(e.to_string()))?;
+        db::insert_user(conn, &new_username, &password_hash, role.as_str()).map(Some)
+    })
+    .await?
+    .ok_or_else(|| AppError::Rejected(StatusCode::CONFLICT, i18n::t("error-username-taken")))?;
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "user.create",
+        "user",
+        Some(id),
+        None,
+        Some(json!({ "username": username, "role": role.as_str() })),
+    )
+    .await;
+    Ok(Redirect::to("/protected/users"))
+}
+
+pub async fn user_update_role(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+    Form(form): Form<RoleForm>,
+) -> Result<Redirect, AppError> {
+    let role = parse_role(&form.role)?;
+    let (_, _, current_role) = db::run(&pool, move |conn| db::get_user(conn, id))
+        .await?
+        .ok_or_else(|| AppError::Rejected(StatusCode::NOT_FOUND, i18n::t("error-user-not-found")))?;
+
+    if id == user.id && role != Role::Owner {
+        return Err(AppError::Rejected(StatusCode::BAD_REQUEST, i18n::t("error-demote-self")));
+    }
+    if current_role == Role::Owner.as_str() && role != Role::Owner {
+        let owners = db::run(&pool, |conn| db::count_owners(conn))
+            .await?;
+        if owners <= 1 {
+            return Err(AppError::Rejected(StatusCode::BAD_REQUEST, i18n::t("error-last-owner")));
+        }
+    }
+
+    db::run(&pool, move |conn| db::update_user_role(conn, id, role.as_str()))
+        .await?;
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "user.role_change",
+        "user",
+        Some(id),
+        Some(json!({ "role": current_role })),
+        Some(json!({ "role": role.as_str() })),
+    )
+    .await;
+    Ok(Redirect::to("/protected/users"))
+}
+
+pub async fn user_delete(
+    State(pool): State<Pool>,
+    Path(id): Path<i64>,
+    Extension(user): Extension<CurrentUser>,
+    ip: ClientIp,
+) -> Result<Redirect, AppError> {
+    if id == user.id {
+        return Err(AppError::Rejected(StatusCode::BAD_REQUEST, i18n::t("error-delete-self")));
+    }
+
+    let before = db::run(&pool, move |conn| {
+        let before = db::get_user(conn, id)?;
+        db::delete_user(conn, id)?;
+        Ok(before)
+    })
+    .await?;
+    audit::record(
+        &pool,
+        &user,
+        &ip,
+        "user.delete",
+        "user",
+        Some(id),
+        before.map(|(_, username, role)| json!({ "username": username, "role": role })),
+        None,
+    )
+    .await;
+    Ok(Redirect::to("/protected/users"))
+}
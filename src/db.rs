@@ -1,6 +1,12 @@
-use rusqlite::{params, Connection};
+use crate::i18n;
+use chrono::NaiveDateTime;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,173 +15,180 @@ pub enum DatabaseError {
     SqliteError(#[from] rusqlite::Error),
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+    #[error("Password hashing error: {0}")]
+    PasswordHash(String),
+    #[error("Migration {version} ({name}) failed: {source}")]
+    MigrationFailed {
+        version: i64,
+        name: &'static str,
+        source: rusqlite::Error,
+    },
+    #[error("Database schema version {0} is newer than this build supports")]
+    SchemaTooNew(i64),
+    #[error("Invalid database snapshot: {0}")]
+    InvalidSnapshot(String),
+    #[error("Export bundle version {0} is newer than this build supports")]
+    BundleTooNew(u32),
+    #[error("Connection pool error: {0}")]
+    Pool(#[from] r2d2::Error),
+    #[error("Database task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
 }
 
-pub fn get_connection() -> Result<Connection, DatabaseError> {
-    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let conn = Connection::open(&database_url)?;
-    Ok(conn)
-}
+pub type Pool = r2d2::Pool<SqliteConnectionManager>;
 
-pub fn setup_database() -> Result<(), DatabaseError> {
-    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let database_path = Path::new(&database_url);
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
 
-    if let Some(parent) = database_path.parent() {
-        fs::create_dir_all(parent)?;
-    }
+pub fn database_url() -> String {
+    std::env::var("DATABASE_URL").expect("DATABASE_URL must be set")
+}
 
-    let conn = get_connection()?;
+/// Opens a connection pool, creating the database directory if needed.
+/// Every connection uses WAL, enforces foreign keys and waits on locks instead of failing.
+pub fn create_pool(database_url: &str) -> Result<Pool, DatabaseError> {
+    let in_memory = database_url == ":memory:";
+    let manager = if in_memory {
+        SqliteConnectionManager::memory()
+    } else {
+        if let Some(parent) = Path::new(database_url).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        SqliteConnectionManager::file(database_url)
+    };
+    let manager = manager.with_init(|conn| {
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")
+    });
 
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS aboutme (
-            id INTEGER PRIMARY KEY,
-            description TEXT NOT NULL,
-            active BOOLEAN NOT NULL DEFAULT 0
-        )",
-        [],
-    )?;
-    Ok(())
+    // Each in-memory connection is its own database, so share a single one.
+    let builder = if in_memory { Pool::builder().max_size(1) } else { Pool::builder() };
+    Ok(builder.build(manager)?)
 }
-pub fn create_contact_table() -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS contact (
-            id INTEGER PRIMARY KEY,
-            description TEXT NOT NULL,
-            admin_mail TEXT NOT NULL,
-            active BOOLEAN NOT NULL DEFAULT 0
-        )",
-        [],
-    )?;
-    Ok(())
-}
-
-pub fn create_users_table() -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
 
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS users (
-            id INTEGER PRIMARY KEY,
-            username TEXT NOT NULL,
-            password TEXT NOT NULL
-        )",
-        [],
-    )?;
+/// Runs `f` on the blocking thread pool with a pooled connection.
+pub async fn run<T, F>(pool: &Pool, f: F) -> Result<T, DatabaseError>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Connection) -> Result<T, DatabaseError> + Send + 'static,
+{
+    let pool = pool.clone();
+    // Carries the request's locale along for rendering done with the connection.
+    tokio::task::spawn_blocking(i18n::carry(move || {
+        let mut conn = pool.get()?;
+        f(&mut conn)
+    }))
+    .await?
+}
 
-    Ok(())
+/// Applies pending migrations, returning their versions.
+pub fn setup_database(pool: &Pool) -> Result<Vec<i64>, DatabaseError> {
+    let mut conn = pool.get()?;
+    crate::migrations::migrate(&mut conn)
 }
-pub fn insert_user_from_env() -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
 
+/// Seeds the initial owner from the environment on a fresh database.
+pub fn insert_user_from_env(conn: &Connection) -> Result<(), DatabaseError> {
     let username = std::env::var("ADMIN_USERNAME").expect("ADMIN_USERNAME must be set");
     let password = std::env::var("ADMIN_PASSWORD").expect("ADMIN_PASSWORD must be set");
 
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))?;
+    if count > 0 {
+        return Ok(());
+    }
+
+    let password_hash = crate::auth::hash_password(&password)
+        .map_err(|e| DatabaseError::PasswordHash(e.to_string()))?;
     conn.execute(
-        "INSERT INTO users (username, password) VALUES (?, ?)",
-        &[&username, &password],
+        "INSERT INTO users (username, password, role) VALUES (?, ?, 'owner')",
+        params![username, password_hash],
     )?;
 
     Ok(())
 }
 
-pub fn create_goals_table() -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS goals (
-            id INTEGER PRIMARY KEY,
-            campaign_id INTEGER NOT NULL,
-            description TEXT NOT NULL,
-            amount REAL NOT NULL,
-            FOREIGN KEY(campaign_id) REFERENCES campaigns(id)
-        )",
-        [],
-    )?;
-    Ok(())
+pub fn get_user(conn: &Connection, id: i64) -> Result<Option<(i64, String, String)>, DatabaseError> {
+    let result = conn.query_row(
+        "SELECT id, username, role FROM users WHERE id = ?",
+        [id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    );
+    match result {
+        Ok(user) => Ok(Some(user)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
 }
 
-pub fn create_campaigns_table() -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS campaigns (
-            id INTEGER PRIMARY KEY,
-            title TEXT NOT NULL,
-            description TEXT NOT NULL,
-            creator_id INTEGER NOT NULL,
-            start_date TEXT NOT NULL,
-            end_date TEXT NOT NULL,
-            goal_amount REAL NOT NULL,
-            current_amount REAL NOT NULL DEFAULT 0,
-            status TEXT NOT NULL,
-            xmr_address TEXT NOT NULL
-        )",
-        [],
-    )?;
-    Ok(())
+pub fn get_users(conn: &Connection) -> Result<Vec<(i64, String, String)>, DatabaseError> {
+    let mut stmt = conn.prepare("SELECT id, username, role FROM users ORDER BY id")?;
+    let user_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+
+    let mut users = Vec::new();
+    for user in user_iter {
+        users.push(user?);
+    }
+    Ok(users)
 }
 
-pub fn insert_campaign(
-    title: &str,
-    description: &str,
-    creator_id: i64,
-    start_date: &str,
-    end_date: &str,
-    goal_amount: f64,
-    status: &str,
-    xmr_address: &str,
-) -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
-    conn.execute(
-        "INSERT INTO campaigns (title, description, creator_id, start_date, end_date, goal_amount, status, xmr_address) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-        params![title, description, creator_id, start_date, end_date, goal_amount, status, xmr_address],
+pub fn username_exists(conn: &Connection, username: &str) -> Result<bool, DatabaseError> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM users WHERE username = ?",
+        [username],
+        |row| row.get(0),
     )?;
-    Ok(())
+    Ok(count > 0)
 }
 
-pub fn get_campaigns() -> Result<Vec<(i64, String, String, f64, f64, String, String, String, String)>, DatabaseError> {
-    let conn = get_connection()?;
-    let mut stmt = conn.prepare(
-        "SELECT id, title, description, goal_amount, current_amount, status, xmr_address, start_date, end_date FROM campaigns",
+pub fn insert_user(conn: &Connection, username: &str, password_hash: &str, role: &str) -> Result<i64, DatabaseError> {
+    conn.execute(
+        "INSERT INTO users (username, password, role) VALUES (?, ?, ?)",
+        params![username, password_hash, role],
     )?;
-    let campaign_iter = stmt.query_map([], |row| {
-        Ok((
-            row.get(0)?,
-            row.get(1)?,
-            row.get(2)?,
-            row.get(3)?,
-            row.get(4)?,
-            row.get(5)?,
-            row.get(6)?,
-            row.get(7)?,
-            row.get(8)?,
-        ))
-    })?;
-
-    let mut campaigns = Vec::new();
-    for campaign in campaign_iter {
-        campaigns.push(campaign?);
-    }
-    Ok(campaigns)
+    Ok(conn.last_insert_rowid())
 }
 
+pub fn update_username(conn: &Connection, id: i64, username: &str) -> Result<(), DatabaseError> {
+    conn.execute("UPDATE users SET username = ? WHERE id = ?", params![username, id])?;
+    Ok(())
+}
 
+pub fn update_user_role(conn: &Connection, id: i64, role: &str) -> Result<(), DatabaseError> {
+    conn.execute("UPDATE users SET role = ? WHERE id = ?", params![role, id])?;
+    Ok(())
+}
 
-pub fn update_campaign_current_amount(campaign_id: i64, amount: f64) -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
-    conn.execute(
-        "UPDATE campaigns SET current_amount = current_amount + ? WHERE id = ?",
-        params![amount, campaign_id],
+/// Removes the user and their credentials. Address changes they requested or decided
+/// stay as history; those still pending are superseded, as nobody can vouch for them.
+pub fn delete_user(conn: &mut Connection, id: i64) -> Result<(), DatabaseError> {
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM user_totp WHERE user_id = ?", [id])?;
+    tx.execute("DELETE FROM recovery_codes WHERE user_id = ?", [id])?;
+    tx.execute("DELETE FROM user_sessions WHERE user_id = ?", [id])?;
+    tx.execute("DELETE FROM api_tokens WHERE user_id = ?", [id])?;
+    tx.execute(
+        "UPDATE address_change_requests SET status = 'superseded', decided_at = CURRENT_TIMESTAMP
+         WHERE requested_by = ? AND status = 'pending'",
+        [id],
     )?;
+    tx.execute("DELETE FROM users WHERE id = ?", [id])?;
+    tx.commit()?;
     Ok(())
 }
 
-pub fn delete_campaign(campaign_id: i64) -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
-    conn.execute("DELETE FROM campaigns WHERE id = ?", params![campaign_id])?;
-    Ok(())
+pub fn count_owners(conn: &Connection) -> Result<i64, DatabaseError> {
+    let count = conn.query_row("SELECT COUNT(*) FROM users WHERE role = 'owner'", [], |row| row.get(0))?;
+    Ok(count)
+}
+
+/// The longest-standing owner, who is credited with imported campaigns.
+pub fn first_owner_id(conn: &Connection) -> Result<Option<i64>, DatabaseError> {
+    let id = conn
+        .query_row("SELECT id FROM users WHERE role = 'owner' ORDER BY id LIMIT 1", [], |row| row.get(0))
+        .optional()?;
+    Ok(id)
 }
-pub fn create_donation_crypto_table() -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
+
+pub fn create_donation_crypto_table(conn: &Connection) -> Result<(), DatabaseError> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS donation_crypto (
             id INTEGER PRIMARY KEY,
@@ -188,16 +201,14 @@ pub fn create_donation_crypto_table() -> Result<(), DatabaseError> {
     Ok(())
 }
 
-pub fn insert_donation_crypto(cryptoname: &str, wallet_address: &str) -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
+pub fn insert_donation_crypto(conn: &Connection, cryptoname: &str, wallet_address: &str) -> Result<(), DatabaseError> {
     conn.execute(
         "INSERT INTO donation_crypto (cryptoname, wallet_address) VALUES (?, ?)",
         params![cryptoname, wallet_address],
     )?;
     Ok(())
 }
-pub fn get_all_donation_cryptos() -> Result<Vec<(i64, String, String)>, DatabaseError> {
-    let conn = get_connection()?;
+pub fn get_all_donation_cryptos(conn: &Connection) -> Result<Vec<(i64, String, String)>, DatabaseError> {
     let mut stmt = conn.prepare("SELECT id, cryptoname, wallet_address FROM donation_crypto")?;
     let crypto_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
 
@@ -208,108 +219,665 @@ pub fn get_all_donation_cryptos() -> Result<Vec<(i64, String, String)>, Database
     Ok(cryptos)
 }
 pub fn update_donation_crypto(
+    conn: &Connection,
     id: i64,
     cryptoname: &str,
     wallet_address: &str,
 ) -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
     conn.execute(
         "UPDATE donation_crypto SET cryptoname = ?, wallet_address = ? WHERE id = ?",
         params![cryptoname, wallet_address, id],
     )?;
     Ok(())
 }
-pub fn delete_donation_crypto(id: i64) -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
+pub fn delete_donation_crypto(conn: &Connection, id: i64) -> Result<(), DatabaseError> {
     conn.execute("DELETE FROM donation_crypto WHERE id = ?", params![id])?;
     Ok(())
 }
-pub fn update_campaign(
-    id: i64,
-    title: String,
-    description: String,
-    start_date: String,
-    end_date: String,
-    goal_amount: f64,
-    xmr_address: String,
-) -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
+
+pub fn get_user_totp(conn: &Connection, user_id: i64) -> Result<Option<(String, bool, Option<i64>)>, DatabaseError> {
+    let result = conn.query_row(
+        "SELECT secret, enabled, last_used_step FROM user_totp WHERE user_id = ?",
+        [user_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    );
+    match result {
+        Ok(totp) => Ok(Some(totp)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn save_pending_totp_secret(conn: &Connection, user_id: i64, secret: &str) -> Result<(), DatabaseError> {
     conn.execute(
-        "UPDATE campaigns SET 
-            title = ?, 
-            description = ?, 
-            start_date = ?,
-            end_date = ?,
-            goal_amount = ?,
-            xmr_address = ?
-        WHERE id = ?",
-        params![title, description, start_date, end_date, goal_amount, xmr_address, id],
+        "INSERT INTO user_totp (user_id, secret, enabled) VALUES (?, ?, 0)
+         ON CONFLICT(user_id) DO UPDATE SET secret = excluded.secret, last_used_step = NULL
+         WHERE enabled = 0",
+        params![user_id, secret],
     )?;
     Ok(())
 }
 
-pub fn create_campaign_updates_table() -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
+pub fn enable_user_totp(conn: &mut Connection, user_id: i64, used_step: i64, code_hashes: &[String]) -> Result<(), DatabaseError> {
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE user_totp SET enabled = 1, last_used_step = ? WHERE user_id = ?",
+        params![used_step, user_id],
+    )?;
+    tx.execute("DELETE FROM recovery_codes WHERE user_id = ?", [user_id])?;
+    for code_hash in code_hashes {
+        tx.execute(
+            "INSERT INTO recovery_codes (user_id, code_hash) VALUES (?, ?)",
+            params![user_id, code_hash],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn disable_user_totp(conn: &mut Connection, user_id: i64) -> Result<(), DatabaseError> {
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM user_totp WHERE user_id = ?", [user_id])?;
+    tx.execute("DELETE FROM recovery_codes WHERE user_id = ?", [user_id])?;
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn update_totp_last_used_step(conn: &Connection, user_id: i64, step: i64) -> Result<(), DatabaseError> {
     conn.execute(
-        "CREATE TABLE IF NOT EXISTS campaign_updates (
-            id INTEGER PRIMARY KEY,
-            campaign_id INTEGER NOT NULL,
-            update_text TEXT NOT NULL,
-            update_hash TEXT NOT NULL,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY(campaign_id) REFERENCES campaigns(id)
-        )",
-        [],
+        "UPDATE user_totp SET last_used_step = ? WHERE user_id = ?",
+        params![step, user_id],
     )?;
     Ok(())
 }
 
-pub fn add_campaign_update(campaign_id: i64, update_text: &str) -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
-    let update_hash = format!("{:x}", md5::compute(update_text));
-    
+/// Counts a second factor attempt for `user_id` before the code is checked, returning
+/// false while the user is locked out. The `max_attempts`th attempt since the last
+/// success locks them out for `lockout_minutes`.
+pub fn claim_totp_attempt(
+    conn: &Connection,
+    user_id: i64,
+    max_attempts: u32,
+    lockout_minutes: u32,
+) -> Result<bool, DatabaseError> {
+    let attempts: Option<u32> = conn
+        .query_row(
+            "UPDATE user_totp SET failed_attempts = failed_attempts + 1
+             WHERE user_id = ? AND (locked_until IS NULL OR locked_until <= datetime('now'))
+             RETURNING failed_attempts",
+            [user_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match attempts {
+        Some(attempts) if attempts >= max_attempts => {
+            conn.execute(
+                "UPDATE user_totp SET failed_attempts = 0, locked_until = datetime('now', ?) WHERE user_id = ?",
+                params![format!("+{} minutes", lockout_minutes), user_id],
+            )?;
+            Ok(true)
+        }
+        Some(_) => Ok(true),
+        None => Ok(false),
+    }
+}
+
+pub fn reset_totp_attempts(conn: &Connection, user_id: i64) -> Result<(), DatabaseError> {
+    conn.execute("UPDATE user_totp SET failed_attempts = 0, locked_until = NULL WHERE user_id = ?", [user_id])?;
+    Ok(())
+}
+
+pub fn get_unused_recovery_codes(conn: &Connection, user_id: i64) -> Result<Vec<(i64, String)>, DatabaseError> {
+    let mut stmt = conn.prepare("SELECT id, code_hash FROM recovery_codes WHERE user_id = ? AND used = 0")?;
+    let code_iter = stmt.query_map([user_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    let mut codes = Vec::new();
+    for code in code_iter {
+        codes.push(code?);
+    }
+    Ok(codes)
+}
+
+pub fn mark_recovery_code_used(conn: &Connection, id: i64) -> Result<(), DatabaseError> {
+    conn.execute("UPDATE recovery_codes SET used = 1 WHERE id = ?", [id])?;
+    Ok(())
+}
+
+/// Returns the user's id and stored password (hash) for a login attempt.
+pub fn get_login(conn: &Connection, username: &str) -> Result<Option<(i64, String)>, DatabaseError> {
+    let result = conn.query_row(
+        "SELECT id, password FROM users WHERE username = ?",
+        [username],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    );
+    match result {
+        Ok(login) => Ok(Some(login)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn get_user_password(conn: &Connection, id: i64) -> Result<Option<String>, DatabaseError> {
+    let result = conn.query_row("SELECT password FROM users WHERE id = ?", [id], |row| row.get(0));
+    match result {
+        Ok(password) => Ok(Some(password)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn update_user_password(conn: &Connection, id: i64, password_hash: &str) -> Result<(), DatabaseError> {
+    conn.execute("UPDATE users SET password = ? WHERE id = ?", params![password_hash, id])?;
+    Ok(())
+}
+
+pub fn create_address_change_request(
+    conn: &mut Connection,
+    campaign_id: i64,
+    old_address: &str,
+    new_address: &str,
+    requested_by: i64,
+) -> Result<i64, DatabaseError> {
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE address_change_requests SET status = 'superseded', decided_at = CURRENT_TIMESTAMP
+         WHERE campaign_id = ? AND status = 'pending'",
+        [campaign_id],
+    )?;
+    tx.execute(
+        "INSERT INTO address_change_requests (campaign_id, old_address, new_address, requested_by) VALUES (?, ?, ?, ?)",
+        params![campaign_id, old_address, new_address, requested_by],
+    )?;
+    let id = tx.last_insert_rowid();
+    tx.commit()?;
+    Ok(id)
+}
+
+/// A payout address change awaiting approval, as listed on the approvals page.
+pub struct PendingAddressChange {
+    pub id: i64,
+    pub campaign_id: i64,
+    pub campaign_title: String,
+    pub old_address: String,
+    pub new_address: String,
+    pub requested_by: i64,
+    pub requested_by_name: String,
+    pub requested_at: NaiveDateTime,
+}
+
+pub fn get_pending_address_changes(conn: &Connection) -> Result<Vec<PendingAddressChange>, DatabaseError> {
+    let mut stmt = conn.prepare(
+        "SELECT r.id, r.campaign_id, c.title, r.old_address, r.new_address, r.requested_by, u.username, r.requested_at
+         FROM address_change_requests r
+         JOIN campaigns c ON c.id = r.campaign_id
+         JOIN users u ON u.id = r.requested_by
+         WHERE r.status = 'pending'
+         ORDER BY r.requested_at",
+    )?;
+    let request_iter = stmt.query_map([], |row| {
+        Ok(PendingAddressChange {
+            id: row.get(0)?,
+            campaign_id: row.get(1)?,
+            campaign_title: row.get(2)?,
+            old_address: row.get(3)?,
+            new_address: row.get(4)?,
+            requested_by: row.get(5)?,
+            requested_by_name: row.get(6)?,
+            requested_at: row.get(7)?,
+        })
+    })?;
+
+    let mut requests = Vec::new();
+    for request in request_iter {
+        requests.push(request?);
+    }
+    Ok(requests)
+}
+
+/// A payout address change request, whatever its status.
+pub struct AddressChangeRequest {
+    pub campaign_id: i64,
+    /// `None` once the requester is deleted.
+    pub requested_by: Option<i64>,
+    pub requested_at: NaiveDateTime,
+    pub status: String,
+}
+
+pub fn get_address_change_request(conn: &Connection, id: i64) -> Result<Option<AddressChangeRequest>, DatabaseError> {
+    let result = conn.query_row(
+        "SELECT campaign_id, requested_by, requested_at, status FROM address_change_requests WHERE id = ?",
+        [id],
+        |row| {
+            Ok(AddressChangeRequest {
+                campaign_id: row.get(0)?,
+                requested_by: row.get(1)?,
+                requested_at: row.get(2)?,
+                status: row.get(3)?,
+            })
+        },
+    );
+    match result {
+        Ok(request) => Ok(Some(request)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn get_pending_address_for_campaign(conn: &Connection, campaign_id: i64) -> Result<Option<String>, DatabaseError> {
+    let result = conn.query_row(
+        "SELECT new_address FROM address_change_requests WHERE campaign_id = ? AND status = 'pending'
+         ORDER BY requested_at DESC LIMIT 1",
+        [campaign_id],
+        |row| row.get(0),
+    );
+    match result {
+        Ok(address) => Ok(Some(address)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Applies a pending request, returning `false` if it is no longer pending or the
+/// campaign address changed since it was requested.
+pub fn approve_address_change(conn: &mut Connection, id: i64, approved_by: i64) -> Result<bool, DatabaseError> {
+    let tx = conn.transaction()?;
+    let applied = tx.execute(
+        "UPDATE campaigns SET xmr_address = (SELECT new_address FROM address_change_requests WHERE id = ?1)
+         WHERE id = (SELECT campaign_id FROM address_change_requests WHERE id = ?1 AND status = 'pending')
+         AND xmr_address = (SELECT old_address FROM address_change_requests WHERE id = ?1)",
+        [id],
+    )?;
+    if applied == 0 {
+        return Ok(false);
+    }
+    tx.execute(
+        "UPDATE address_change_requests SET status = 'approved', decided_by = ?, decided_at = CURRENT_TIMESTAMP WHERE id = ?",
+        params![approved_by, id],
+    )?;
+    tx.commit()?;
+    Ok(true)
+}
+
+pub fn reject_address_change(conn: &Connection, id: i64, rejected_by: i64) -> Result<(), DatabaseError> {
     conn.execute(
-        "INSERT INTO campaign_updates (campaign_id, update_text, update_hash) VALUES (?, ?, ?)",
-        params![campaign_id, update_text, update_hash],
+        "UPDATE address_change_requests SET status = 'rejected', decided_by = ?, decided_at = CURRENT_TIMESTAMP
+         WHERE id = ? AND status = 'pending'",
+        params![rejected_by, id],
     )?;
     Ok(())
 }
 
-pub fn get_campaign_updates(campaign_id: i64) -> Result<Vec<(i64, String, String, String)>, DatabaseError> {
-    let conn = get_connection()?;
+pub fn get_last_address_change(conn: &Connection, campaign_id: i64) -> Result<Option<NaiveDateTime>, DatabaseError> {
+    let changed_at = conn.query_row(
+        "SELECT MAX(decided_at) FROM address_change_requests WHERE campaign_id = ? AND status = 'approved'",
+        [campaign_id],
+        |row| row.get(0),
+    )?;
+    Ok(changed_at)
+}
+
+pub struct NewAuditEntry {
+    pub actor_id: Option<i64>,
+    pub actor_name: String,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<i64>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub ip: String,
+}
+
+#[derive(Serialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub created_at: String,
+    pub actor_id: Option<i64>,
+    pub actor_name: String,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<i64>,
+    pub before_state: Option<String>,
+    pub after_state: Option<String>,
+    pub ip: String,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+#[derive(Clone, Default, Deserialize)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl AuditFilter {
+    fn params(&self) -> [Option<&str>; 5] {
+        [&self.actor, &self.action, &self.target_type, &self.from, &self.to]
+            .map(|value| value.as_deref().map(str::trim).filter(|value| !value.is_empty()))
+    }
+}
+
+const AUDIT_FILTER_SQL: &str = "(?1 IS NULL OR actor_name = ?1)
+    AND (?2 IS NULL OR action = ?2)
+    AND (?3 IS NULL OR target_type = ?3)
+    AND (?4 IS NULL OR created_at >= ?4)
+    AND (?5 IS NULL OR created_at < date(?5, '+1 day'))";
+
+/// Hashes every field except `id` and `hash` itself, chained to the previous entry.
+fn audit_hash(entry: &AuditEntry) -> String {
+    let mut hasher = Sha256::new();
+    for field in [
+        entry.prev_hash.clone(),
+        entry.created_at.clone(),
+        entry.actor_id.map(|id| id.to_string()).unwrap_or_default(),
+        entry.actor_name.clone(),
+        entry.action.clone(),
+        entry.target_type.clone(),
+        entry.target_id.map(|id| id.to_string()).unwrap_or_default(),
+        entry.before_state.clone().unwrap_or_default(),
+        entry.after_state.clone().unwrap_or_default(),
+        entry.ip.clone(),
+    ] {
+        hasher.update(field.len().to_le_bytes());
+        hasher.update(field.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+pub fn insert_audit_entry(conn: &mut Connection, entry: &NewAuditEntry) -> Result<(), DatabaseError> {
+    let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+    append_audit_entry(&tx, entry)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Chains `entry` onto the audit log inside the caller's write transaction, which
+/// must have been started `Immediate` so no other entry can take the same place.
+pub fn append_audit_entry(conn: &Connection, entry: &NewAuditEntry) -> Result<(), DatabaseError> {
+    let prev_hash: String = conn
+        .query_row("SELECT hash FROM audit_log ORDER BY id DESC LIMIT 1", [], |row| row.get(0))
+        .or_else(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Ok(String::new()),
+            e => Err(e),
+        })?;
+    let mut row = AuditEntry {
+        id: 0,
+        created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        actor_id: entry.actor_id,
+        actor_name: entry.actor_name.clone(),
+        action: entry.action.clone(),
+        target_type: entry.target_type.clone(),
+        target_id: entry.target_id,
+        before_state: entry.before.as_ref().map(|value| value.to_string()),
+        after_state: entry.after.as_ref().map(|value| value.to_string()),
+        ip: entry.ip.clone(),
+        prev_hash,
+        hash: String::new(),
+    };
+    row.hash = audit_hash(&row);
+
+    conn.execute(
+        "INSERT INTO audit_log (created_at, actor_id, actor_name, action, target_type, target_id, before_state, after_state, ip, prev_hash, hash)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            row.created_at,
+            row.actor_id,
+            row.actor_name,
+            row.action,
+            row.target_type,
+            row.target_id,
+            row.before_state,
+            row.after_state,
+            row.ip,
+            row.prev_hash,
+            row.hash
+        ],
+    )?;
+    Ok(())
+}
+
+fn audit_entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<AuditEntry> {
+    Ok(AuditEntry {
+        id: row.get(0)?,
+        created_at: row.get(1)?,
+        actor_id: row.get(2)?,
+        actor_name: row.get(3)?,
+        action: row.get(4)?,
+        target_type: row.get(5)?,
+        target_id: row.get(6)?,
+        before_state: row.get(7)?,
+        after_state: row.get(8)?,
+        ip: row.get(9)?,
+        prev_hash: row.get(10)?,
+        hash: row.get(11)?,
+    })
+}
+
+pub fn get_audit_entries(conn: &Connection, filter: &AuditFilter, limit: i64, offset: i64) -> Result<Vec<AuditEntry>, DatabaseError> {
+    let [actor, action, target_type, from, to] = filter.params();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, created_at, actor_id, actor_name, action, target_type, target_id, before_state, after_state, ip, prev_hash, hash
+         FROM audit_log WHERE {} ORDER BY id DESC LIMIT ?6 OFFSET ?7",
+        AUDIT_FILTER_SQL
+    ))?;
+    let entry_iter = stmt.query_map(
+        params![actor, action, target_type, from, to, limit, offset],
+        audit_entry_from_row,
+    )?;
+
+    let mut entries = Vec::new();
+    for entry in entry_iter {
+        entries.push(entry?);
+    }
+    Ok(entries)
+}
+
+pub fn count_audit_entries(conn: &Connection, filter: &AuditFilter) -> Result<i64, DatabaseError> {
+    let [actor, action, target_type, from, to] = filter.params();
+    let count = conn.query_row(
+        &format!("SELECT COUNT(*) FROM audit_log WHERE {}", AUDIT_FILTER_SQL),
+        params![actor, action, target_type, from, to],
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+/// Recomputes the hash chain, returning the id of the first entry that doesn't match.
+pub fn verify_audit_chain(conn: &Connection) -> Result<Option<i64>, DatabaseError> {
     let mut stmt = conn.prepare(
-        "SELECT id, update_text, update_hash, created_at FROM campaign_updates WHERE campaign_id = ? ORDER BY created_at DESC"
+        "SELECT id, created_at, actor_id, actor_name, action, target_type, target_id, before_state, after_state, ip, prev_hash, hash
+         FROM audit_log ORDER BY id",
     )?;
-    
-    let updates = stmt.query_map([campaign_id], |row| {
-        Ok((
-            row.get(0)?,
-            row.get(1)?,
-            row.get(2)?,
-            row.get(3)?,
-        ))
+    let entry_iter = stmt.query_map([], audit_entry_from_row)?;
+
+    let mut expected_prev = String::new();
+    for entry in entry_iter {
+        let entry = entry?;
+        if entry.prev_hash != expected_prev || entry.hash != audit_hash(&entry) {
+            return Ok(Some(entry.id));
+        }
+        expected_prev = entry.hash;
+    }
+    Ok(None)
+}
+
+/// Session data lives in the in-memory store, so no tracked session survives a restart.
+pub fn clear_user_sessions(conn: &Connection) -> Result<(), DatabaseError> {
+    conn.execute("DELETE FROM user_sessions", [])?;
+    Ok(())
+}
+
+pub fn insert_user_session(conn: &Connection, user_id: i64, token_hash: &str, ip: &str, user_agent: &str) -> Result<(), DatabaseError> {
+    conn.execute(
+        "INSERT INTO user_sessions (user_id, token_hash, ip, user_agent) VALUES (?, ?, ?, ?)",
+        params![user_id, token_hash, ip, user_agent],
+    )?;
+    Ok(())
+}
+
+/// Marks a session as seen, returning `false` if it has been revoked.
+pub fn touch_user_session(conn: &Connection, user_id: i64, token_hash: &str) -> Result<bool, DatabaseError> {
+    let updated = conn.execute(
+        "UPDATE user_sessions SET last_seen = CURRENT_TIMESTAMP WHERE user_id = ? AND token_hash = ?",
+        params![user_id, token_hash],
+    )?;
+    Ok(updated > 0)
+}
+
+/// A login session as listed on the sessions page.
+pub struct UserSession {
+    pub id: i64,
+    pub token_hash: String,
+    pub created_at: NaiveDateTime,
+    pub last_seen: NaiveDateTime,
+    pub ip: String,
+    pub user_agent: String,
+}
+
+pub fn get_user_sessions(conn: &Connection, user_id: i64) -> Result<Vec<UserSession>, DatabaseError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, token_hash, created_at, last_seen, ip, user_agent FROM user_sessions
+         WHERE user_id = ? ORDER BY last_seen DESC",
+    )?;
+    let session_iter = stmt.query_map([user_id], |row| {
+        Ok(UserSession {
+            id: row.get(0)?,
+            token_hash: row.get(1)?,
+            created_at: row.get(2)?,
+            last_seen: row.get(3)?,
+            ip: row.get(4)?,
+            user_agent: row.get(5)?,
+        })
+    })?;
+
+    let mut sessions = Vec::new();
+    for session in session_iter {
+        sessions.push(session?);
+    }
+    Ok(sessions)
+}
+
+pub fn delete_user_session(conn: &Connection, id: i64, user_id: i64) -> Result<(), DatabaseError> {
+    conn.execute("DELETE FROM user_sessions WHERE id = ? AND user_id = ?", params![id, user_id])?;
+    Ok(())
+}
+
+pub fn delete_user_session_by_token(conn: &Connection, token_hash: &str) -> Result<(), DatabaseError> {
+    conn.execute("DELETE FROM user_sessions WHERE token_hash = ?", [token_hash])?;
+    Ok(())
+}
+
+/// Revokes every session of the user except the one with `keep_token_hash`, if given.
+pub fn delete_other_user_sessions(conn: &Connection, user_id: i64, keep_token_hash: Option<&str>) -> Result<usize, DatabaseError> {
+    let deleted = conn.execute(
+        "DELETE FROM user_sessions WHERE user_id = ?1 AND (?2 IS NULL OR token_hash != ?2)",
+        params![user_id, keep_token_hash],
+    )?;
+    Ok(deleted)
+}
+
+pub fn insert_api_token(conn: &Connection, user_id: i64, name: &str, token_hash: &str) -> Result<i64, DatabaseError> {
+    conn.execute(
+        "INSERT INTO api_tokens (user_id, name, token_hash) VALUES (?, ?, ?)",
+        params![user_id, name, token_hash],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// An API token as listed on the account page; the token itself is only stored hashed.
+pub struct ApiToken {
+    pub id: i64,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+}
+
+pub fn get_api_tokens(conn: &Connection, user_id: i64) -> Result<Vec<ApiToken>, DatabaseError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, created_at, last_used_at FROM api_tokens WHERE user_id = ? ORDER BY created_at DESC",
+    )?;
+    let token_iter = stmt.query_map([user_id], |row| {
+        Ok(ApiToken { id: row.get(0)?, name: row.get(1)?, created_at: row.get(2)?, last_used_at: row.get(3)? })
     })?;
 
-    let mut result = Vec::new();
-    for update in updates {
-        result.push(update?);
+    let mut tokens = Vec::new();
+    for token in token_iter {
+        tokens.push(token?);
+    }
+    Ok(tokens)
+}
+
+pub fn delete_api_token(conn: &Connection, id: i64, user_id: i64) -> Result<bool, DatabaseError> {
+    let deleted = conn.execute("DELETE FROM api_tokens WHERE id = ? AND user_id = ?", params![id, user_id])?;
+    Ok(deleted > 0)
+}
+
+/// Looks up the owner of an API token and records its use.
+pub fn use_api_token(conn: &Connection, token_hash: &str) -> Result<Option<i64>, DatabaseError> {
+    let result = conn.query_row(
+        "SELECT user_id FROM api_tokens WHERE token_hash = ?",
+        [token_hash],
+        |row| row.get(0),
+    );
+    match result {
+        Ok(user_id) => {
+            conn.execute(
+                "UPDATE api_tokens SET last_used_at = CURRENT_TIMESTAMP WHERE token_hash = ?",
+                [token_hash],
+            )?;
+            Ok(Some(user_id))
+        }
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>, DatabaseError> {
+    let result = conn.query_row("SELECT value FROM settings WHERE key = ?", [key], |row| row.get(0));
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
     }
-    Ok(result)
 }
-pub fn update_campaign_amount(id: i64, new_amount: f64) -> Result<(), DatabaseError> {
-    let conn = get_connection()?;
+
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<(), DatabaseError> {
     conn.execute(
-        "UPDATE campaigns SET current_amount = ? WHERE id = ?",
-        params![new_amount, id],
+        "INSERT INTO settings (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
     )?;
     Ok(())
 }
-pub fn get_campaign_amount(id: i64) -> Result<f64, DatabaseError> {
-    let conn = get_connection()?;
-    let current_amount: f64 = conn.query_row(
-        "SELECT current_amount FROM campaigns WHERE id = ?",
-        [id],
-        |row| row.get(0)
+
+pub fn delete_setting(conn: &Connection, key: &str) -> Result<(), DatabaseError> {
+    conn.execute("DELETE FROM settings WHERE key = ?", [key])?;
+    Ok(())
+}
+
+/// Themes added from the dashboard, as (name, css).
+pub fn get_uploaded_themes(conn: &Connection) -> Result<Vec<(String, String)>, DatabaseError> {
+    let mut stmt = conn.prepare("SELECT name, css FROM themes ORDER BY name")?;
+    let theme_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    let mut themes = Vec::new();
+    for theme in theme_iter {
+        themes.push(theme?);
+    }
+    Ok(themes)
+}
+
+pub fn save_uploaded_theme(conn: &Connection, name: &str, css: &str) -> Result<(), DatabaseError> {
+    conn.execute(
+        "INSERT INTO themes (name, css) VALUES (?, ?)
+         ON CONFLICT(name) DO UPDATE SET css = excluded.css, updated_at = CURRENT_TIMESTAMP",
+        params![name, css],
     )?;
-    Ok(current_amount)
+    Ok(())
+}
+
+pub fn delete_uploaded_theme(conn: &Connection, name: &str) -> Result<bool, DatabaseError> {
+    let deleted = conn.execute("DELETE FROM themes WHERE name = ?", [name])?;
+    Ok(deleted > 0)
 }
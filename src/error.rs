@@ -0,0 +1,68 @@
+use crate::db::DatabaseError;
+use crate::views::layout::render_layout;
+use axum::{
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+};
+use rand_core::{OsRng, RngCore};
+use thiserror::Error;
+use tower_sessions::Session;
+
+/// Errors returned by page handlers. Not-found and internal errors are turned
+/// into full pages by `Router::render_error_pages`, which has the session.
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error("Not found")]
+    NotFound,
+    /// The submitted form, rendered again with the problem explained.
+    #[error("Invalid form submission")]
+    Invalid(Html<String>),
+    /// The request was refused; `status` and the translated message are sent as they are.
+    #[error("{1}")]
+    Rejected(StatusCode, String),
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+    #[error(transparent)]
+    Session(#[from] tower_sessions::session::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl AppError {
+    /// Renders `content`, the form with its error message, in the site layout.
+    pub async fn invalid_form(content: &str, session: &Session) -> Self {
+        AppError::Invalid(render_layout(content, session).await)
+    }
+}
+
+/// Marks a response whose body should be replaced with a rendered error page.
+#[derive(Clone, Debug)]
+pub enum ErrorPage {
+    NotFound,
+    Internal { correlation_id: String },
+}
+
+fn correlation_id() -> String {
+    let mut bytes = [0u8; 8];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status, page) = match self {
+            AppError::NotFound => (StatusCode::NOT_FOUND, ErrorPage::NotFound),
+            AppError::Invalid(form) => return (StatusCode::UNPROCESSABLE_ENTITY, form).into_response(),
+            AppError::Rejected(status, message) => return (status, message).into_response(),
+            e @ (AppError::Database(_) | AppError::Session(_) | AppError::Json(_)) => {
+                let correlation_id = correlation_id();
+                eprintln!("Error [{}]: {}", correlation_id, e);
+                (StatusCode::INTERNAL_SERVER_ERROR, ErrorPage::Internal { correlation_id })
+            }
+        };
+
+        let mut response = status.into_response();
+        response.extensions_mut().insert(page);
+        response
+    }
+}
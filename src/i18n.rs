@@ -0,0 +1,207 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+/// Locales with a message catalog in `locales/`, as (code, name in that language, catalog).
+pub const LOCALES: &[(&str, &str, &str)] = &[
+    ("en", "English", include_str!("../locales/en.ftl")),
+    ("es", "Español", include_str!("../locales/es.ftl")),
+    ("pl", "Polski", include_str!("../locales/pl.ftl")),
+];
+
+/// Used when nothing else matches, and for messages a catalog lacks.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// The cookie holding the locale chosen through a URL prefix.
+pub const COOKIE: &str = "locale";
+
+fn catalogs() -> &'static HashMap<&'static str, FluentBundle<FluentResource>> {
+    static CATALOGS: OnceLock<HashMap<&'static str, FluentBundle<FluentResource>>> = OnceLock::new();
+    CATALOGS.get_or_init(|| {
+        LOCALES
+            .iter()
+            .map(|(code, _, source)| {
+                let language: LanguageIdentifier = code.parse().expect("locale codes are valid language tags");
+                let resource = FluentResource::try_new(source.to_string())
+                    .unwrap_or_else(|(_, errors)| panic!("locales/{}.ftl does not parse: {:?}", code, errors));
+                let mut bundle = FluentBundle::new_concurrent(vec![language]);
+                // Isolation marks would end up in attributes and plain-text responses.
+                bundle.set_use_isolating(false);
+                bundle
+                    .add_resource(resource)
+                    .unwrap_or_else(|errors| panic!("locales/{}.ftl repeats messages: {:?}", code, errors));
+                (*code, bundle)
+            })
+            .collect()
+    })
+}
+
+/// The supported locale for a language tag such as `pl` or `pl-PL`.
+pub fn supported(tag: &str) -> Option<&'static str> {
+    let tag = tag.trim();
+    let primary = tag.split(['-', '_']).next().unwrap_or_default();
+    LOCALES
+        .iter()
+        .map(|(code, _, _)| *code)
+        .find(|code| code.eq_ignore_ascii_case(tag) || code.eq_ignore_ascii_case(primary))
+}
+
+/// The best supported locale for an `Accept-Language` header, by quality.
+pub fn from_accept_language(header: &str) -> Option<&'static str> {
+    let mut tags: Vec<(&str, f32)> = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|part| part.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (quality > 0.0 && !tag.is_empty()).then_some((tag, quality))
+        })
+        .collect();
+    tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+    tags.into_iter().find_map(|(tag, _)| supported(tag))
+}
+
+/// The locale stored in a `Cookie` request header.
+pub fn from_cookie_header(header: &str) -> Option<&'static str> {
+    header
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == COOKIE)
+        .and_then(|(_, value)| supported(value))
+}
+
+/// The `Set-Cookie` value remembering `locale` for a year.
+pub fn set_cookie(locale: &str) -> String {
+    format!("{}={}; Path=/; Max-Age=31536000; SameSite=Lax; HttpOnly", COOKIE, locale)
+}
+
+/// Splits a `/<locale>` prefix off `path`, returning the locale and the rest of the path.
+pub fn split_prefix(path: &str) -> Option<(&'static str, &str)> {
+    let rest = path.strip_prefix('/')?;
+    let (segment, rest) = rest.find('/').map_or((rest, ""), |slash| rest.split_at(slash));
+    let locale = LOCALES.iter().map(|(code, _, _)| *code).find(|code| *code == segment)?;
+    Some((locale, rest))
+}
+
+struct RequestLocale {
+    locale: &'static str,
+    /// The path and query of the request without a locale prefix.
+    path: String,
+}
+
+tokio::task_local! {
+    static REQUEST: RequestLocale;
+}
+
+/// Runs `request` with `locale` as the language pages are rendered in; `path` is the
+/// page's address without a locale prefix.
+pub async fn scope<F: Future>(locale: &'static str, path: String, request: F) -> F::Output {
+    REQUEST.scope(RequestLocale { locale, path }, request).await
+}
+
+/// Runs `f` in the locale and path of the request being handled, for work moved off its task.
+pub fn carry<R>(f: impl FnOnce() -> R + Send) -> impl FnOnce() -> R + Send {
+    let request = RequestLocale { locale: current(), path: current_path() };
+    move || REQUEST.sync_scope(request, f)
+}
+
+/// The locale of the request being handled.
+pub fn current() -> &'static str {
+    REQUEST.try_with(|request| request.locale).unwrap_or(DEFAULT_LOCALE)
+}
+
+/// The path and query of the request being handled, without a locale prefix.
+pub fn current_path() -> String {
+    REQUEST.try_with(|request| request.path.clone()).unwrap_or_else(|_| "/".to_string())
+}
+
+fn format(locale: &str, key: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let bundle = catalogs().get(locale)?;
+    let pattern = bundle.get_message(key)?.value()?;
+    let mut errors = Vec::new();
+    let message = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        eprintln!("Error: message {} in {}: {:?}", key, locale, errors);
+    }
+    Some(message.into_owned())
+}
+
+/// The message `key` in `locale`, falling back to the default locale and then the key itself.
+pub fn translate(locale: &str, key: &str, args: &[(&str, String)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    let args = (!args.is_empty()).then_some(&fluent_args);
+    format(locale, key, args).or_else(|| format(DEFAULT_LOCALE, key, args)).unwrap_or_else(|| key.to_string())
+}
+
+/// The message `key` in the current locale.
+pub fn t(key: &str) -> String {
+    translate(current(), key, &[])
+}
+
+/// The message `key` in the current locale, with named values filled in.
+pub fn t_args(key: &str, args: &[(&str, String)]) -> String {
+    translate(current(), key, args)
+}
+
+/// The inline `onsubmit` handler asking the confirmation question `key` in `locale`.
+pub fn confirm_handler(locale: &str, key: &str) -> String {
+    let question = translate(locale, key, &[]).replace('\\', "\\\\").replace('\'', "\\'");
+    format!("return confirm('{}')", question)
+}
+
+/// `value` with the current locale's digit grouping and decimal separator.
+pub fn format_number(value: f64) -> String {
+    let decimal = t("number-decimal-separator");
+    let group = t("number-group-separator");
+    let plain = value.abs().to_string();
+    let (integer, fraction) = plain.split_once('.').unwrap_or((&plain, ""));
+
+    let mut formatted = String::new();
+    if value < 0.0 {
+        formatted.push('-');
+    }
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            formatted.push_str(&group);
+        }
+        formatted.push(digit);
+    }
+    if !fraction.is_empty() {
+        formatted.push_str(&decimal);
+        formatted.push_str(fraction);
+    }
+    formatted
+}
+
+/// Formats a chrono value, or `None` if the catalog's pattern is invalid.
+fn try_format(value: impl std::fmt::Display) -> Option<String> {
+    let mut formatted = String::new();
+    write!(formatted, "{}", value).ok()?;
+    Some(formatted)
+}
+
+/// A `YYYY-MM-DD` date in the current locale's format; other values are returned unchanged.
+pub fn format_date(value: &str) -> String {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| try_format(date.format(&t("date-format"))))
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// A `YYYY-MM-DD HH:MM[:SS[.fff]]` time in the current locale's format; other values are returned unchanged.
+pub fn format_datetime(value: &str) -> String {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M"))
+        .ok()
+        .and_then(|time| try_format(time.format(&t("datetime-format"))))
+        .unwrap_or_else(|| value.to_string())
+}
@@ -2,14 +2,29 @@
 mod tests;
 
 use anyhow::Result;
+use cli::Command;
 use dotenv::dotenv;
 use routes::Router;
 use server::start_server;
 
+mod audit;
+mod auth;
+mod backup;
+mod cli;
 mod controllers;
 mod db;
+mod diff;
+mod error;
+mod i18n;
+mod markdown;
+mod migrations;
+mod models;
+mod repositories;
 mod routes;
+mod sanitize;
 mod server;
+mod sessions;
+mod transfer;
 mod views;
 mod macros;
 
@@ -17,16 +32,24 @@ mod macros;
 async fn main() -> Result<()> {
     dotenv().ok();
 
-    db::setup_database()?;
-    db::create_users_table()?;
-    db::insert_user_from_env()?;
-    db::create_contact_table()?;
-    db::create_campaigns_table()?;
-    db::create_goals_table()?;
-    //db::create_donation_crypto_table()?;
-    db::create_campaign_updates_table()?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match Command::parse(&args)? {
+        Command::Serve => {}
+        command => return cli::run(command),
+    }
 
-    let router = Router::new();
+    let pool = db::create_pool(&db::database_url())?;
+    db::setup_database(&pool)?;
+    {
+        let conn = pool.get()?;
+        db::insert_user_from_env(&conn)?;
+        db::clear_user_sessions(&conn)?;
+        views::themes::theme_manager::init(&conn);
+    }
+
+    backup::spawn_scheduled(pool.clone(), backup::BackupConfig::from_env());
+
+    let router = Router::new(pool);
     let app = router.create_router();
     start_server(app).await;
 
@@ -0,0 +1,51 @@
+use crate::sanitize;
+use pulldown_cmark::{html, Options, Parser};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+
+/// Rendered sources kept; past this the oldest is dropped for each new one.
+const MAX_CACHED: usize = 1024;
+
+/// Rendered HTML by SHA-256 of the source, with keys in insertion order for eviction.
+#[derive(Default)]
+struct Cache {
+    html: HashMap<[u8; 32], String>,
+    order: VecDeque<[u8; 32]>,
+}
+
+fn options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_FOOTNOTES | Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH
+}
+
+fn render_uncached(markdown: &str) -> String {
+    let mut html = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut html, Parser::new_ext(markdown, options()));
+    sanitize::rich_text(&html)
+}
+
+/// Renders CommonMark with tables, footnotes and task lists to sanitized HTML.
+/// Raw HTML in the source passes through the same allowlist, so older HTML
+/// content keeps working. Results are cached by a hash of the source, so each
+/// saved revision is rendered once and an edit can never be served stale.
+pub fn render(markdown: &str) -> String {
+    static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+    let cache = CACHE.get_or_init(Default::default);
+    let key: [u8; 32] = Sha256::digest(markdown).into();
+
+    if let Some(html) = cache.lock().unwrap_or_else(|e| e.into_inner()).html.get(&key) {
+        return html.clone();
+    }
+    let html = render_uncached(markdown);
+    let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+    // Another request may have rendered the same source meanwhile.
+    if cache.html.insert(key, html.clone()).is_none() {
+        cache.order.push_back(key);
+        if cache.order.len() > MAX_CACHED {
+            if let Some(oldest) = cache.order.pop_front() {
+                cache.html.remove(&oldest);
+            }
+        }
+    }
+    html
+}
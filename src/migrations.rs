@@ -0,0 +1,143 @@
+use crate::db::DatabaseError;
+use rusqlite::{params, Connection, Transaction, TransactionBehavior};
+
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    sql: &'static str,
+}
+
+/// All migrations, in the order they are applied. Never edit a released one; add a new one.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sql: include_str!("../migrations/0001_initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "campaign_indexes",
+        sql: include_str!("../migrations/0002_campaign_indexes.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "page_revisions",
+        sql: include_str!("../migrations/0003_page_revisions.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "single_active_page",
+        sql: include_str!("../migrations/0004_single_active_page.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "theme_settings",
+        sql: include_str!("../migrations/0005_theme_settings.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "content_translations",
+        sql: include_str!("../migrations/0006_content_translations.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "address_change_history",
+        sql: include_str!("../migrations/0007_address_change_history.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "totp_lockout",
+        sql: include_str!("../migrations/0008_totp_lockout.sql"),
+    },
+];
+
+fn create_schema_version_table(conn: &Connection) -> Result<(), DatabaseError> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Applied migrations as (version, applied_at).
+pub fn applied_versions(conn: &Connection) -> Result<Vec<(i64, String)>, DatabaseError> {
+    let tracked: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'schema_version'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !tracked {
+        return Ok(vec![]);
+    }
+
+    let mut stmt = conn.prepare("SELECT version, applied_at FROM schema_version ORDER BY version")?;
+    let version_iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    let mut versions = Vec::new();
+    for version in version_iter {
+        versions.push(version?);
+    }
+    Ok(versions)
+}
+
+/// Databases created before migrations existed may predate the `users.role` column.
+fn adopt_legacy_schema(tx: &Transaction) -> rusqlite::Result<()> {
+    let has_users: bool = tx.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'users'",
+        [],
+        |row| row.get(0),
+    )?;
+    let has_role: bool = tx.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('users') WHERE name = 'role'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_users && !has_role {
+        tx.execute("ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT 'owner'", [])?;
+    }
+    Ok(())
+}
+
+/// Applies all pending migrations in one transaction and returns the versions applied.
+/// If any migration fails nothing is applied.
+pub fn migrate(conn: &mut Connection) -> Result<Vec<i64>, DatabaseError> {
+    create_schema_version_table(conn)?;
+    let applied = applied_versions(conn)?;
+    let latest_known = MIGRATIONS.last().map(|migration| migration.version).unwrap_or(0);
+    if let Some((version, _)) = applied.iter().find(|(version, _)| *version > latest_known) {
+        return Err(DatabaseError::SchemaTooNew(*version));
+    }
+
+    let pending: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .filter(|migration| !applied.iter().any(|(version, _)| *version == migration.version))
+        .collect();
+    if pending.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    for migration in &pending {
+        let failed = |source| DatabaseError::MigrationFailed {
+            version: migration.version,
+            name: migration.name,
+            source,
+        };
+        if migration.version == 1 {
+            adopt_legacy_schema(&tx).map_err(failed)?;
+        }
+        tx.execute_batch(migration.sql).map_err(failed)?;
+        tx.execute(
+            "INSERT INTO schema_version (version, name) VALUES (?, ?)",
+            params![migration.version, migration.name],
+        )
+        .map_err(failed)?;
+    }
+    tx.commit()?;
+
+    Ok(pending.iter().map(|migration| migration.version).collect())
+}
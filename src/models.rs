@@ -0,0 +1,304 @@
+use rusqlite::Row;
+use serde::{Deserialize, Serialize};
+
+/// Maps a row selected with `Self::COLUMNS` from `Self::TABLE` onto the model.
+pub trait FromRow: Sized {
+    const TABLE: &'static str;
+    const COLUMNS: &'static str;
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self>;
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Campaign {
+    pub id: i64,
+    pub title: String,
+    pub description: String,
+    pub creator_id: i64,
+    pub start_date: String,
+    pub end_date: String,
+    pub goal_amount: f64,
+    pub current_amount: f64,
+    pub status: String,
+    pub xmr_address: String,
+}
+
+impl FromRow for Campaign {
+    const TABLE: &'static str = "campaigns";
+    const COLUMNS: &'static str =
+        "id, title, description, creator_id, start_date, end_date, goal_amount, current_amount, status, xmr_address";
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Campaign {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            description: row.get(2)?,
+            creator_id: row.get(3)?,
+            start_date: row.get(4)?,
+            end_date: row.get(5)?,
+            goal_amount: row.get(6)?,
+            current_amount: row.get(7)?,
+            status: row.get(8)?,
+            xmr_address: row.get(9)?,
+        })
+    }
+}
+
+/// Narrows campaign listings; blank fields are ignored. `from` and `to` keep
+/// campaigns that run at some point within that date range.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CampaignFilter {
+    pub status: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl CampaignFilter {
+    pub fn active() -> Self {
+        CampaignFilter {
+            status: Some("active".to_string()),
+            ..CampaignFilter::default()
+        }
+    }
+
+    /// `[status, from, to]` with blank values dropped.
+    pub fn params(&self) -> [Option<&str>; 3] {
+        [&self.status, &self.from, &self.to]
+            .map(|value| value.as_deref().map(str::trim).filter(|value| !value.is_empty()))
+    }
+}
+
+/// Fields for creating a campaign; the id and current amount are assigned by the store.
+#[derive(Clone, Debug)]
+pub struct NewCampaign {
+    pub title: String,
+    pub description: String,
+    pub creator_id: i64,
+    pub start_date: String,
+    pub end_date: String,
+    pub goal_amount: f64,
+    pub status: String,
+    pub xmr_address: String,
+}
+
+/// The text fields any campaign editor may change.
+#[derive(Clone, Debug)]
+pub struct CampaignDetails {
+    pub title: String,
+    pub description: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub goal_amount: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CampaignUpdate {
+    pub id: i64,
+    pub campaign_id: i64,
+    pub update_text: String,
+    pub update_hash: String,
+    pub created_at: String,
+}
+
+impl FromRow for CampaignUpdate {
+    const TABLE: &'static str = "campaign_updates";
+    const COLUMNS: &'static str = "id, campaign_id, update_text, update_hash, created_at";
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(CampaignUpdate {
+            id: row.get(0)?,
+            campaign_id: row.get(1)?,
+            update_text: row.get(2)?,
+            update_hash: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: i64,
+    pub campaign_id: i64,
+    pub description: String,
+    pub amount: f64,
+}
+
+impl FromRow for Goal {
+    const TABLE: &'static str = "goals";
+    const COLUMNS: &'static str = "id, campaign_id, description, amount";
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Goal {
+            id: row.get(0)?,
+            campaign_id: row.get(1)?,
+            description: row.get(2)?,
+            amount: row.get(3)?,
+        })
+    }
+}
+
+/// Who saved a revision. The name is kept so history survives deleting the user.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Author {
+    pub id: Option<i64>,
+    pub name: String,
+}
+
+/// `description` is the published text; edits are saved as revisions until one is published.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AboutEntry {
+    pub id: i64,
+    pub description: String,
+    pub active: bool,
+    #[serde(default)]
+    pub published_revision_id: Option<i64>,
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+}
+
+impl FromRow for AboutEntry {
+    const TABLE: &'static str = "aboutme";
+    const COLUMNS: &'static str = "id, description, active, published_revision_id, deleted_at";
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(AboutEntry {
+            id: row.get(0)?,
+            description: row.get(1)?,
+            active: row.get(2)?,
+            published_revision_id: row.get(3)?,
+            deleted_at: row.get(4)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AboutRevision {
+    pub id: i64,
+    pub about_id: i64,
+    pub description: String,
+    pub author_id: Option<i64>,
+    pub author_name: String,
+    pub created_at: String,
+}
+
+impl FromRow for AboutRevision {
+    const TABLE: &'static str = "about_revisions";
+    const COLUMNS: &'static str = "id, about_id, description, author_id, author_name, created_at";
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(AboutRevision {
+            id: row.get(0)?,
+            about_id: row.get(1)?,
+            description: row.get(2)?,
+            author_id: row.get(3)?,
+            author_name: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }
+}
+
+/// Like `AboutEntry`, `description` and `admin_mail` are the published values.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ContactEntry {
+    pub id: i64,
+    pub description: String,
+    pub admin_mail: String,
+    pub active: bool,
+    #[serde(default)]
+    pub published_revision_id: Option<i64>,
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+}
+
+impl FromRow for ContactEntry {
+    const TABLE: &'static str = "contact";
+    const COLUMNS: &'static str = "id, description, admin_mail, active, published_revision_id, deleted_at";
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(ContactEntry {
+            id: row.get(0)?,
+            description: row.get(1)?,
+            admin_mail: row.get(2)?,
+            active: row.get(3)?,
+            published_revision_id: row.get(4)?,
+            deleted_at: row.get(5)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ContactRevision {
+    pub id: i64,
+    pub contact_id: i64,
+    pub description: String,
+    pub admin_mail: String,
+    pub author_id: Option<i64>,
+    pub author_name: String,
+    pub created_at: String,
+}
+
+impl FromRow for ContactRevision {
+    const TABLE: &'static str = "contact_revisions";
+    const COLUMNS: &'static str = "id, contact_id, description, admin_mail, author_id, author_name, created_at";
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(ContactRevision {
+            id: row.get(0)?,
+            contact_id: row.get(1)?,
+            description: row.get(2)?,
+            admin_mail: row.get(3)?,
+            author_id: row.get(4)?,
+            author_name: row.get(5)?,
+            created_at: row.get(6)?,
+        })
+    }
+}
+
+/// Content with per-language versions, by its `content_translations.content_type`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslatedContent {
+    Campaign,
+    CampaignUpdate,
+    About,
+    Contact,
+}
+
+impl TranslatedContent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TranslatedContent::Campaign => "campaign",
+            TranslatedContent::CampaignUpdate => "campaign_update",
+            TranslatedContent::About => "about",
+            TranslatedContent::Contact => "contact",
+        }
+    }
+}
+
+/// One language's version of a piece of content. `title` is only used for campaigns;
+/// `body` is the description or update text. Blank fields show the default language.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Translation {
+    pub locale: String,
+    pub title: String,
+    pub body: String,
+}
+
+impl Translation {
+    pub fn is_blank(&self) -> bool {
+        self.title.trim().is_empty() && self.body.trim().is_empty()
+    }
+}
+
+impl FromRow for Translation {
+    const TABLE: &'static str = "content_translations";
+    const COLUMNS: &'static str = "locale, title, body";
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Translation {
+            locale: row.get(0)?,
+            title: row.get(1)?,
+            body: row.get(2)?,
+        })
+    }
+}
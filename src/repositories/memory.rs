@@ -0,0 +1,376 @@
+use super::{update_hash, AboutRepository, CampaignRepository, ContactRepository, TranslationRepository};
+use crate::db::DatabaseError;
+use crate::models::{
+    AboutEntry, AboutRevision, Author, Campaign, CampaignDetails, CampaignFilter, CampaignUpdate, ContactEntry,
+    ContactRevision, Goal, NewCampaign, TranslatedContent, Translation,
+};
+use std::sync::Mutex;
+
+/// Repositories kept in memory, for exercising controllers without SQLite.
+#[derive(Default)]
+pub struct InMemoryRepository {
+    campaigns: Mutex<Vec<Campaign>>,
+    updates: Mutex<Vec<CampaignUpdate>>,
+    goals: Mutex<Vec<Goal>>,
+    about: Mutex<Vec<AboutEntry>>,
+    about_revisions: Mutex<Vec<AboutRevision>>,
+    contact: Mutex<Vec<ContactEntry>>,
+    contact_revisions: Mutex<Vec<ContactRevision>>,
+    translations: Mutex<Vec<(TranslatedContent, i64, Translation)>>,
+    next_id: Mutex<i64>,
+}
+
+impl InMemoryRepository {
+    fn next_id(&self) -> i64 {
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        *next_id
+    }
+
+    fn remove_translations(&self, content: TranslatedContent, id: i64) {
+        self.translations.lock().unwrap().retain(|(kind, content_id, _)| (*kind, *content_id) != (content, id));
+    }
+}
+
+fn matches(filter: &CampaignFilter, campaign: &Campaign) -> bool {
+    let [status, from, to] = filter.params();
+    status.is_none_or(|status| campaign.status == status)
+        && from.is_none_or(|from| campaign.end_date.as_str() >= from)
+        && to.is_none_or(|to| campaign.start_date.as_str() <= to)
+}
+
+impl CampaignRepository for InMemoryRepository {
+    fn list(&self, filter: &CampaignFilter, limit: i64, offset: i64) -> Result<Vec<Campaign>, DatabaseError> {
+        let campaigns = self.campaigns.lock().unwrap();
+        Ok(campaigns
+            .iter()
+            .filter(|campaign| matches(filter, campaign))
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    fn count(&self, filter: &CampaignFilter) -> Result<i64, DatabaseError> {
+        let campaigns = self.campaigns.lock().unwrap();
+        Ok(campaigns.iter().filter(|campaign| matches(filter, campaign)).count() as i64)
+    }
+
+    fn get(&self, id: i64) -> Result<Option<Campaign>, DatabaseError> {
+        Ok(self.campaigns.lock().unwrap().iter().find(|campaign| campaign.id == id).cloned())
+    }
+
+    fn insert(&self, campaign: &NewCampaign) -> Result<i64, DatabaseError> {
+        let id = self.next_id();
+        self.campaigns.lock().unwrap().push(Campaign {
+            id,
+            title: campaign.title.clone(),
+            description: campaign.description.clone(),
+            creator_id: campaign.creator_id,
+            start_date: campaign.start_date.clone(),
+            end_date: campaign.end_date.clone(),
+            goal_amount: campaign.goal_amount,
+            current_amount: 0.0,
+            status: campaign.status.clone(),
+            xmr_address: campaign.xmr_address.clone(),
+        });
+        Ok(id)
+    }
+
+    fn update_details(&self, id: i64, details: &CampaignDetails) -> Result<(), DatabaseError> {
+        if let Some(campaign) = self.campaigns.lock().unwrap().iter_mut().find(|campaign| campaign.id == id) {
+            campaign.title = details.title.clone();
+            campaign.description = details.description.clone();
+            campaign.start_date = details.start_date.clone();
+            campaign.end_date = details.end_date.clone();
+            campaign.goal_amount = details.goal_amount;
+        }
+        Ok(())
+    }
+
+    fn set_current_amount(&self, id: i64, amount: f64) -> Result<(), DatabaseError> {
+        if let Some(campaign) = self.campaigns.lock().unwrap().iter_mut().find(|campaign| campaign.id == id) {
+            campaign.current_amount = amount;
+        }
+        Ok(())
+    }
+
+    fn delete(&self, id: i64) -> Result<(), DatabaseError> {
+        self.remove_translations(TranslatedContent::Campaign, id);
+        self.campaigns.lock().unwrap().retain(|campaign| campaign.id != id);
+        Ok(())
+    }
+
+    fn updates(&self, campaign_id: i64) -> Result<Vec<CampaignUpdate>, DatabaseError> {
+        let updates = self.updates.lock().unwrap();
+        Ok(updates.iter().rev().filter(|update| update.campaign_id == campaign_id).cloned().collect())
+    }
+
+    fn add_update(&self, campaign_id: i64, update_text: &str) -> Result<i64, DatabaseError> {
+        self.add_update_at(campaign_id, update_text, "2024-01-01 00:00:00")
+    }
+
+    fn add_update_at(&self, campaign_id: i64, update_text: &str, created_at: &str) -> Result<i64, DatabaseError> {
+        let id = self.next_id();
+        self.updates.lock().unwrap().push(CampaignUpdate {
+            id,
+            campaign_id,
+            update_text: update_text.to_string(),
+            update_hash: update_hash(update_text),
+            created_at: created_at.to_string(),
+        });
+        Ok(id)
+    }
+
+    fn goals(&self, campaign_id: i64) -> Result<Vec<Goal>, DatabaseError> {
+        let goals = self.goals.lock().unwrap();
+        Ok(goals.iter().filter(|goal| goal.campaign_id == campaign_id).cloned().collect())
+    }
+
+    fn add_goal(&self, campaign_id: i64, description: &str, amount: f64) -> Result<i64, DatabaseError> {
+        let id = self.next_id();
+        self.goals.lock().unwrap().push(Goal {
+            id,
+            campaign_id,
+            description: description.to_string(),
+            amount,
+        });
+        Ok(id)
+    }
+}
+
+impl AboutRepository for InMemoryRepository {
+    fn list(&self) -> Result<Vec<AboutEntry>, DatabaseError> {
+        let about = self.about.lock().unwrap();
+        Ok(about.iter().rev().filter(|entry| entry.deleted_at.is_none()).cloned().collect())
+    }
+
+    fn get(&self, id: i64) -> Result<Option<AboutEntry>, DatabaseError> {
+        Ok(self.about.lock().unwrap().iter().find(|entry| entry.id == id).cloned())
+    }
+
+    fn active(&self) -> Result<Option<AboutEntry>, DatabaseError> {
+        let about = self.about.lock().unwrap();
+        Ok(about.iter().rev().find(|entry| entry.active && entry.deleted_at.is_none()).cloned())
+    }
+
+    fn insert(&self, description: &str, author: &Author) -> Result<i64, DatabaseError> {
+        let id = self.next_id();
+        self.about.lock().unwrap().push(AboutEntry {
+            id,
+            description: description.to_string(),
+            active: false,
+            published_revision_id: None,
+            deleted_at: None,
+        });
+        AboutRepository::save(self, id, description, author)?;
+        Ok(id)
+    }
+
+    fn save(&self, id: i64, description: &str, author: &Author) -> Result<i64, DatabaseError> {
+        let revision_id = self.next_id();
+        self.about_revisions.lock().unwrap().push(AboutRevision {
+            id: revision_id,
+            about_id: id,
+            description: description.to_string(),
+            author_id: author.id,
+            author_name: author.name.clone(),
+            created_at: "2024-01-01 00:00:00".to_string(),
+        });
+        Ok(revision_id)
+    }
+
+    fn revisions(&self, id: i64) -> Result<Vec<AboutRevision>, DatabaseError> {
+        let revisions = self.about_revisions.lock().unwrap();
+        Ok(revisions.iter().rev().filter(|revision| revision.about_id == id).cloned().collect())
+    }
+
+    fn revision(&self, revision_id: i64) -> Result<Option<AboutRevision>, DatabaseError> {
+        let revisions = self.about_revisions.lock().unwrap();
+        Ok(revisions.iter().find(|revision| revision.id == revision_id).cloned())
+    }
+
+    fn publish(&self, id: i64, revision_id: i64) -> Result<(), DatabaseError> {
+        let revision = AboutRepository::revision(self, revision_id)?
+            .filter(|revision| revision.about_id == id)
+            .ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        let mut entries = self.about.lock().unwrap();
+        if !entries.iter().any(|entry| entry.id == id && entry.deleted_at.is_none()) {
+            return Err(rusqlite::Error::QueryReturnedNoRows.into());
+        }
+        for entry in entries.iter_mut() {
+            if entry.id == id {
+                entry.description = revision.description.clone();
+                entry.active = true;
+                entry.published_revision_id = Some(revision_id);
+            } else {
+                entry.active = false;
+            }
+        }
+        Ok(())
+    }
+
+    fn unpublish(&self, id: i64) -> Result<(), DatabaseError> {
+        if let Some(entry) = self.about.lock().unwrap().iter_mut().find(|entry| entry.id == id) {
+            entry.active = false;
+        }
+        Ok(())
+    }
+
+    fn delete(&self, id: i64) -> Result<(), DatabaseError> {
+        if let Some(entry) = self.about.lock().unwrap().iter_mut().find(|entry| entry.id == id) {
+            entry.active = false;
+            entry.deleted_at = Some("2024-01-01 00:00:00".to_string());
+        }
+        Ok(())
+    }
+
+    fn trash(&self) -> Result<Vec<AboutEntry>, DatabaseError> {
+        let about = self.about.lock().unwrap();
+        Ok(about.iter().rev().filter(|entry| entry.deleted_at.is_some()).cloned().collect())
+    }
+
+    fn undelete(&self, id: i64) -> Result<(), DatabaseError> {
+        if let Some(entry) = self.about.lock().unwrap().iter_mut().find(|entry| entry.id == id) {
+            entry.deleted_at = None;
+        }
+        Ok(())
+    }
+
+    fn purge(&self, id: i64) -> Result<(), DatabaseError> {
+        self.remove_translations(TranslatedContent::About, id);
+        self.about_revisions.lock().unwrap().retain(|revision| revision.about_id != id);
+        self.about.lock().unwrap().retain(|entry| entry.id != id);
+        Ok(())
+    }
+}
+
+impl ContactRepository for InMemoryRepository {
+    fn list(&self) -> Result<Vec<ContactEntry>, DatabaseError> {
+        let contact = self.contact.lock().unwrap();
+        Ok(contact.iter().rev().filter(|entry| entry.deleted_at.is_none()).cloned().collect())
+    }
+
+    fn get(&self, id: i64) -> Result<Option<ContactEntry>, DatabaseError> {
+        Ok(self.contact.lock().unwrap().iter().find(|entry| entry.id == id).cloned())
+    }
+
+    fn active(&self) -> Result<Option<ContactEntry>, DatabaseError> {
+        let contact = self.contact.lock().unwrap();
+        Ok(contact.iter().rev().find(|entry| entry.active && entry.deleted_at.is_none()).cloned())
+    }
+
+    fn insert(&self, description: &str, admin_mail: &str, author: &Author) -> Result<i64, DatabaseError> {
+        let id = self.next_id();
+        self.contact.lock().unwrap().push(ContactEntry {
+            id,
+            description: description.to_string(),
+            admin_mail: admin_mail.to_string(),
+            active: false,
+            published_revision_id: None,
+            deleted_at: None,
+        });
+        ContactRepository::save(self, id, description, admin_mail, author)?;
+        Ok(id)
+    }
+
+    fn save(&self, id: i64, description: &str, admin_mail: &str, author: &Author) -> Result<i64, DatabaseError> {
+        let revision_id = self.next_id();
+        self.contact_revisions.lock().unwrap().push(ContactRevision {
+            id: revision_id,
+            contact_id: id,
+            description: description.to_string(),
+            admin_mail: admin_mail.to_string(),
+            author_id: author.id,
+            author_name: author.name.clone(),
+            created_at: "2024-01-01 00:00:00".to_string(),
+        });
+        Ok(revision_id)
+    }
+
+    fn revisions(&self, id: i64) -> Result<Vec<ContactRevision>, DatabaseError> {
+        let revisions = self.contact_revisions.lock().unwrap();
+        Ok(revisions.iter().rev().filter(|revision| revision.contact_id == id).cloned().collect())
+    }
+
+    fn revision(&self, revision_id: i64) -> Result<Option<ContactRevision>, DatabaseError> {
+        let revisions = self.contact_revisions.lock().unwrap();
+        Ok(revisions.iter().find(|revision| revision.id == revision_id).cloned())
+    }
+
+    fn publish(&self, id: i64, revision_id: i64) -> Result<(), DatabaseError> {
+        let revision = ContactRepository::revision(self, revision_id)?
+            .filter(|revision| revision.contact_id == id)
+            .ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        let mut entries = self.contact.lock().unwrap();
+        if !entries.iter().any(|entry| entry.id == id && entry.deleted_at.is_none()) {
+            return Err(rusqlite::Error::QueryReturnedNoRows.into());
+        }
+        for entry in entries.iter_mut() {
+            if entry.id == id {
+                entry.description = revision.description.clone();
+                entry.admin_mail = revision.admin_mail.clone();
+                entry.active = true;
+                entry.published_revision_id = Some(revision_id);
+            } else {
+                entry.active = false;
+            }
+        }
+        Ok(())
+    }
+
+    fn unpublish(&self, id: i64) -> Result<(), DatabaseError> {
+        if let Some(entry) = self.contact.lock().unwrap().iter_mut().find(|entry| entry.id == id) {
+            entry.active = false;
+        }
+        Ok(())
+    }
+
+    fn delete(&self, id: i64) -> Result<(), DatabaseError> {
+        if let Some(entry) = self.contact.lock().unwrap().iter_mut().find(|entry| entry.id == id) {
+            entry.active = false;
+            entry.deleted_at = Some("2024-01-01 00:00:00".to_string());
+        }
+        Ok(())
+    }
+
+    fn trash(&self) -> Result<Vec<ContactEntry>, DatabaseError> {
+        let contact = self.contact.lock().unwrap();
+        Ok(contact.iter().rev().filter(|entry| entry.deleted_at.is_some()).cloned().collect())
+    }
+
+    fn undelete(&self, id: i64) -> Result<(), DatabaseError> {
+        if let Some(entry) = self.contact.lock().unwrap().iter_mut().find(|entry| entry.id == id) {
+            entry.deleted_at = None;
+        }
+        Ok(())
+    }
+
+    fn purge(&self, id: i64) -> Result<(), DatabaseError> {
+        self.remove_translations(TranslatedContent::Contact, id);
+        self.contact_revisions.lock().unwrap().retain(|revision| revision.contact_id != id);
+        self.contact.lock().unwrap().retain(|entry| entry.id != id);
+        Ok(())
+    }
+}
+
+impl TranslationRepository for InMemoryRepository {
+    fn translation(&self, content: TranslatedContent, id: i64, locale: &str) -> Result<Option<Translation>, DatabaseError> {
+        let translations = self.translations.lock().unwrap();
+        Ok(translations
+            .iter()
+            .find(|(kind, content_id, translation)| (*kind, *content_id, translation.locale.as_str()) == (content, id, locale))
+            .map(|(_, _, translation)| translation.clone()))
+    }
+
+    fn save_translation(&self, content: TranslatedContent, id: i64, translation: &Translation) -> Result<(), DatabaseError> {
+        let mut translations = self.translations.lock().unwrap();
+        translations.retain(|(kind, content_id, existing)| {
+            (*kind, *content_id, existing.locale.as_str()) != (content, id, translation.locale.as_str())
+        });
+        if !translation.is_blank() {
+            translations.push((content, id, translation.clone()));
+        }
+        Ok(())
+    }
+}
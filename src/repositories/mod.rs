@@ -0,0 +1,130 @@
+use crate::db::DatabaseError;
+use crate::i18n;
+use crate::models::{
+    AboutEntry, AboutRevision, Author, Campaign, CampaignDetails, CampaignFilter, CampaignUpdate, ContactEntry,
+    ContactRevision, Goal, NewCampaign, TranslatedContent, Translation,
+};
+
+#[cfg(test)]
+pub mod memory;
+pub mod sqlite;
+
+pub use sqlite::SqliteRepository;
+
+pub trait CampaignRepository {
+    /// Oldest first, at most `limit` campaigns starting at `offset`.
+    fn list(&self, filter: &CampaignFilter, limit: i64, offset: i64) -> Result<Vec<Campaign>, DatabaseError>;
+    fn count(&self, filter: &CampaignFilter) -> Result<i64, DatabaseError>;
+    fn get(&self, id: i64) -> Result<Option<Campaign>, DatabaseError>;
+    fn insert(&self, campaign: &NewCampaign) -> Result<i64, DatabaseError>;
+    fn update_details(&self, id: i64, details: &CampaignDetails) -> Result<(), DatabaseError>;
+    fn set_current_amount(&self, id: i64, amount: f64) -> Result<(), DatabaseError>;
+    fn delete(&self, id: i64) -> Result<(), DatabaseError>;
+    /// Newest first.
+    fn updates(&self, campaign_id: i64) -> Result<Vec<CampaignUpdate>, DatabaseError>;
+    fn add_update(&self, campaign_id: i64, update_text: &str) -> Result<i64, DatabaseError>;
+    /// Adds an update with its original timestamp, for imports.
+    fn add_update_at(&self, campaign_id: i64, update_text: &str, created_at: &str) -> Result<i64, DatabaseError>;
+    fn goals(&self, campaign_id: i64) -> Result<Vec<Goal>, DatabaseError>;
+    fn add_goal(&self, campaign_id: i64, description: &str, amount: f64) -> Result<i64, DatabaseError>;
+}
+
+/// About entries keep every save as a revision. At most one entry is active,
+/// showing the revision last published for it.
+pub trait AboutRepository {
+    /// Newest first, without entries in the trash.
+    fn list(&self) -> Result<Vec<AboutEntry>, DatabaseError>;
+    /// Also finds entries in the trash.
+    fn get(&self, id: i64) -> Result<Option<AboutEntry>, DatabaseError>;
+    fn active(&self) -> Result<Option<AboutEntry>, DatabaseError>;
+    /// Creates an unpublished entry with `description` as its first revision.
+    fn insert(&self, description: &str, author: &Author) -> Result<i64, DatabaseError>;
+    /// Stores a new revision, returning its id. Nothing changes on the site until it is published.
+    fn save(&self, id: i64, description: &str, author: &Author) -> Result<i64, DatabaseError>;
+    /// Newest first.
+    fn revisions(&self, id: i64) -> Result<Vec<AboutRevision>, DatabaseError>;
+    fn revision(&self, revision_id: i64) -> Result<Option<AboutRevision>, DatabaseError>;
+    /// Shows the revision on the about page in place of whichever entry was active.
+    fn publish(&self, id: i64, revision_id: i64) -> Result<(), DatabaseError>;
+    fn unpublish(&self, id: i64) -> Result<(), DatabaseError>;
+    /// Moves the entry to the trash, unpublishing it.
+    fn delete(&self, id: i64) -> Result<(), DatabaseError>;
+    /// Newest first.
+    fn trash(&self) -> Result<Vec<AboutEntry>, DatabaseError>;
+    fn undelete(&self, id: i64) -> Result<(), DatabaseError>;
+    /// Removes an entry and its revisions for good.
+    fn purge(&self, id: i64) -> Result<(), DatabaseError>;
+}
+
+/// Contact entries follow the same revision, publish and trash rules as about entries.
+pub trait ContactRepository {
+    /// Newest first, without entries in the trash.
+    fn list(&self) -> Result<Vec<ContactEntry>, DatabaseError>;
+    /// Also finds entries in the trash.
+    fn get(&self, id: i64) -> Result<Option<ContactEntry>, DatabaseError>;
+    fn active(&self) -> Result<Option<ContactEntry>, DatabaseError>;
+    fn insert(&self, description: &str, admin_mail: &str, author: &Author) -> Result<i64, DatabaseError>;
+    fn save(&self, id: i64, description: &str, admin_mail: &str, author: &Author) -> Result<i64, DatabaseError>;
+    /// Newest first.
+    fn revisions(&self, id: i64) -> Result<Vec<ContactRevision>, DatabaseError>;
+    fn revision(&self, revision_id: i64) -> Result<Option<ContactRevision>, DatabaseError>;
+    fn publish(&self, id: i64, revision_id: i64) -> Result<(), DatabaseError>;
+    fn unpublish(&self, id: i64) -> Result<(), DatabaseError>;
+    fn delete(&self, id: i64) -> Result<(), DatabaseError>;
+    /// Newest first.
+    fn trash(&self) -> Result<Vec<ContactEntry>, DatabaseError>;
+    fn undelete(&self, id: i64) -> Result<(), DatabaseError>;
+    fn purge(&self, id: i64) -> Result<(), DatabaseError>;
+}
+
+/// Per-language versions of campaign, update, about and contact text. The content
+/// itself holds the default language. Deleting or purging content removes its translations.
+pub trait TranslationRepository {
+    fn translation(&self, content: TranslatedContent, id: i64, locale: &str) -> Result<Option<Translation>, DatabaseError>;
+    /// Stores `translation`, replacing that language's previous one; a blank one is removed.
+    fn save_translation(&self, content: TranslatedContent, id: i64, translation: &Translation) -> Result<(), DatabaseError>;
+}
+
+/// The `locale` version of content `id`. Missing translations, and lookup errors,
+/// give a blank one so every field falls back to the default language.
+fn translation_in(repository: &impl TranslationRepository, content: TranslatedContent, id: i64, locale: &str) -> Translation {
+    if locale == i18n::DEFAULT_LOCALE {
+        return Translation::default();
+    }
+    repository.translation(content, id, locale).ok().flatten().unwrap_or_default()
+}
+
+fn fall_back(value: &mut String, translated: String) {
+    if !translated.trim().is_empty() {
+        *value = translated;
+    }
+}
+
+/// Shows `campaign` in `locale` where its title and description are translated.
+pub fn localize_campaign(repository: &impl TranslationRepository, campaign: &mut Campaign, locale: &str) {
+    let translation = translation_in(repository, TranslatedContent::Campaign, campaign.id, locale);
+    fall_back(&mut campaign.title, translation.title);
+    fall_back(&mut campaign.description, translation.body);
+}
+
+/// Shows `update` in `locale` if it is translated. The hash stays that of the original text.
+pub fn localize_update(repository: &impl TranslationRepository, update: &mut CampaignUpdate, locale: &str) {
+    let translation = translation_in(repository, TranslatedContent::CampaignUpdate, update.id, locale);
+    fall_back(&mut update.update_text, translation.body);
+}
+
+/// Shows the about or contact `description` of entry `id` in `locale` if it is translated.
+pub fn localize_description(
+    repository: &impl TranslationRepository,
+    content: TranslatedContent,
+    id: i64,
+    description: &mut String,
+    locale: &str,
+) {
+    fall_back(description, translation_in(repository, content, id, locale).body);
+}
+
+/// md5 of the update text, shown next to each update so edits are noticeable.
+pub fn update_hash(update_text: &str) -> String {
+    format!("{:x}", md5::compute(update_text))
+}
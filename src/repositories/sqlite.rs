@@ -0,0 +1,391 @@
+use super::{update_hash, AboutRepository, CampaignRepository, ContactRepository, TranslationRepository};
+use crate::db::DatabaseError;
+use crate::models::{
+    AboutEntry, AboutRevision, Author, Campaign, CampaignDetails, CampaignFilter, CampaignUpdate, ContactEntry,
+    ContactRevision, FromRow, Goal, NewCampaign, TranslatedContent, Translation,
+};
+use rusqlite::{params, Connection, OptionalExtension, Params, Transaction, TransactionBehavior};
+
+/// Repositories over a (pooled) SQLite connection.
+#[derive(Clone, Copy)]
+pub struct SqliteRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> SqliteRepository<'a> {
+    pub fn new(conn: &'a Connection) -> Self {
+        SqliteRepository { conn }
+    }
+
+    /// Starts a transaction around a write of several statements. If the caller
+    /// already holds one, as an import does, the statements join it instead.
+    fn begin(&self, behavior: TransactionBehavior) -> Result<Write<'a>, DatabaseError> {
+        if !self.conn.is_autocommit() {
+            return Ok(Write(None));
+        }
+        Ok(Write(Some(Transaction::new_unchecked(self.conn, behavior)?)))
+    }
+}
+
+/// A write started by `SqliteRepository::begin`; dropping it uncommitted rolls its own transaction back.
+struct Write<'a>(Option<Transaction<'a>>);
+
+impl Write<'_> {
+    fn commit(self) -> Result<(), DatabaseError> {
+        if let Some(tx) = self.0 {
+            tx.commit()?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `SELECT <columns> FROM <table> <sql_tail>` for the model `T`.
+fn query_all<T: FromRow, P: Params>(conn: &Connection, sql_tail: &str, params: P) -> Result<Vec<T>, DatabaseError> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM {} {}", T::COLUMNS, T::TABLE, sql_tail))?;
+    let rows = stmt.query_map(params, T::from_row)?;
+
+    let mut items = Vec::new();
+    for item in rows {
+        items.push(item?);
+    }
+    Ok(items)
+}
+
+const CAMPAIGN_FILTER_SQL: &str = "WHERE (?1 IS NULL OR status = ?1)
+    AND (?2 IS NULL OR end_date >= ?2)
+    AND (?3 IS NULL OR start_date <= ?3)";
+
+fn query_one<T: FromRow, P: Params>(conn: &Connection, sql_tail: &str, params: P) -> Result<Option<T>, DatabaseError> {
+    let item = conn
+        .query_row(&format!("SELECT {} FROM {} {}", T::COLUMNS, T::TABLE, sql_tail), params, T::from_row)
+        .optional()?;
+    Ok(item)
+}
+
+impl CampaignRepository for SqliteRepository<'_> {
+    fn list(&self, filter: &CampaignFilter, limit: i64, offset: i64) -> Result<Vec<Campaign>, DatabaseError> {
+        let [status, from, to] = filter.params();
+        query_all(
+            self.conn,
+            &format!("{} ORDER BY id LIMIT ?4 OFFSET ?5", CAMPAIGN_FILTER_SQL),
+            params![status, from, to, limit, offset],
+        )
+    }
+
+    fn count(&self, filter: &CampaignFilter) -> Result<i64, DatabaseError> {
+        let [status, from, to] = filter.params();
+        let count = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM campaigns {}", CAMPAIGN_FILTER_SQL),
+            params![status, from, to],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    fn get(&self, id: i64) -> Result<Option<Campaign>, DatabaseError> {
+        query_one(self.conn, "WHERE id = ?", [id])
+    }
+
+    fn insert(&self, campaign: &NewCampaign) -> Result<i64, DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO campaigns (title, description, creator_id, start_date, end_date, goal_amount, status, xmr_address) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                campaign.title,
+                campaign.description,
+                campaign.creator_id,
+                campaign.start_date,
+                campaign.end_date,
+                campaign.goal_amount,
+                campaign.status,
+                campaign.xmr_address
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    fn update_details(&self, id: i64, details: &CampaignDetails) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            "UPDATE campaigns SET 
+                title = ?, 
+                description = ?, 
+                start_date = ?,
+                end_date = ?,
+                goal_amount = ?
+            WHERE id = ?",
+            params![details.title, details.description, details.start_date, details.end_date, details.goal_amount, id],
+        )?;
+        Ok(())
+    }
+
+    fn set_current_amount(&self, id: i64, amount: f64) -> Result<(), DatabaseError> {
+        self.conn.execute("UPDATE campaigns SET current_amount = ? WHERE id = ?", params![amount, id])?;
+        Ok(())
+    }
+
+    fn delete(&self, id: i64) -> Result<(), DatabaseError> {
+        let tx = self.begin(TransactionBehavior::Deferred)?;
+        self.conn.execute(
+            "DELETE FROM content_translations
+            WHERE (content_type = 'campaign' AND content_id = ?1)
+               OR (content_type = 'campaign_update'
+                   AND content_id IN (SELECT id FROM campaign_updates WHERE campaign_id = ?1))",
+            [id],
+        )?;
+        self.conn.execute("DELETE FROM goals WHERE campaign_id = ?", [id])?;
+        self.conn.execute("DELETE FROM campaign_updates WHERE campaign_id = ?", [id])?;
+        self.conn.execute("DELETE FROM address_change_requests WHERE campaign_id = ?", [id])?;
+        self.conn.execute("DELETE FROM campaigns WHERE id = ?", [id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn updates(&self, campaign_id: i64) -> Result<Vec<CampaignUpdate>, DatabaseError> {
+        query_all(self.conn, "WHERE campaign_id = ? ORDER BY created_at DESC, id DESC", [campaign_id])
+    }
+
+    fn add_update(&self, campaign_id: i64, update_text: &str) -> Result<i64, DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO campaign_updates (campaign_id, update_text, update_hash) VALUES (?, ?, ?)",
+            params![campaign_id, update_text, update_hash(update_text)],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    fn add_update_at(&self, campaign_id: i64, update_text: &str, created_at: &str) -> Result<i64, DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO campaign_updates (campaign_id, update_text, update_hash, created_at) VALUES (?, ?, ?, ?)",
+            params![campaign_id, update_text, update_hash(update_text), created_at],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    fn goals(&self, campaign_id: i64) -> Result<Vec<Goal>, DatabaseError> {
+        query_all(self.conn, "WHERE campaign_id = ? ORDER BY amount", [campaign_id])
+    }
+
+    fn add_goal(&self, campaign_id: i64, description: &str, amount: f64) -> Result<i64, DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO goals (campaign_id, description, amount) VALUES (?, ?, ?)",
+            params![campaign_id, description, amount],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+}
+
+impl AboutRepository for SqliteRepository<'_> {
+    fn list(&self) -> Result<Vec<AboutEntry>, DatabaseError> {
+        query_all(self.conn, "WHERE deleted_at IS NULL ORDER BY id DESC", [])
+    }
+
+    fn get(&self, id: i64) -> Result<Option<AboutEntry>, DatabaseError> {
+        query_one(self.conn, "WHERE id = ?", [id])
+    }
+
+    fn active(&self) -> Result<Option<AboutEntry>, DatabaseError> {
+        // Ordered so the choice stays deterministic even without the unique index.
+        query_one(self.conn, "WHERE active = 1 AND deleted_at IS NULL ORDER BY id DESC LIMIT 1", [])
+    }
+
+    fn insert(&self, description: &str, author: &Author) -> Result<i64, DatabaseError> {
+        let tx = self.begin(TransactionBehavior::Deferred)?;
+        self.conn.execute("INSERT INTO aboutme (description, active) VALUES (?, 0)", [description])?;
+        let id = self.conn.last_insert_rowid();
+        self.conn.execute(
+            "INSERT INTO about_revisions (about_id, description, author_id, author_name) VALUES (?, ?, ?, ?)",
+            params![id, description, author.id, author.name],
+        )?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    fn save(&self, id: i64, description: &str, author: &Author) -> Result<i64, DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO about_revisions (about_id, description, author_id, author_name) VALUES (?, ?, ?, ?)",
+            params![id, description, author.id, author.name],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    fn revisions(&self, id: i64) -> Result<Vec<AboutRevision>, DatabaseError> {
+        query_all(self.conn, "WHERE about_id = ? ORDER BY id DESC", [id])
+    }
+
+    fn revision(&self, revision_id: i64) -> Result<Option<AboutRevision>, DatabaseError> {
+        query_one(self.conn, "WHERE id = ?", [revision_id])
+    }
+
+    fn publish(&self, id: i64, revision_id: i64) -> Result<(), DatabaseError> {
+        // Take the write lock up front so concurrent publishes queue instead of racing.
+        let tx = self.begin(TransactionBehavior::Immediate)?;
+        let description: String = self.conn.query_row(
+            "SELECT description FROM about_revisions WHERE id = ? AND about_id = ?",
+            params![revision_id, id],
+            |row| row.get(0),
+        )?;
+        self.conn.execute("UPDATE aboutme SET active = 0 WHERE active = 1 AND id != ?", [id])?;
+        let published = self.conn.execute(
+            "UPDATE aboutme SET description = ?, active = 1, published_revision_id = ? WHERE id = ? AND deleted_at IS NULL",
+            params![description, revision_id, id],
+        )?;
+        // A trashed entry cannot go live; dropping the transaction keeps the live one.
+        if published == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows.into());
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn unpublish(&self, id: i64) -> Result<(), DatabaseError> {
+        self.conn.execute("UPDATE aboutme SET active = 0 WHERE id = ?", [id])?;
+        Ok(())
+    }
+
+    fn delete(&self, id: i64) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            "UPDATE aboutme SET active = 0, deleted_at = CURRENT_TIMESTAMP WHERE id = ?",
+            [id],
+        )?;
+        Ok(())
+    }
+
+    fn trash(&self) -> Result<Vec<AboutEntry>, DatabaseError> {
+        query_all(self.conn, "WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id DESC", [])
+    }
+
+    fn undelete(&self, id: i64) -> Result<(), DatabaseError> {
+        self.conn.execute("UPDATE aboutme SET deleted_at = NULL WHERE id = ?", [id])?;
+        Ok(())
+    }
+
+    fn purge(&self, id: i64) -> Result<(), DatabaseError> {
+        let tx = self.begin(TransactionBehavior::Deferred)?;
+        self.conn.execute("DELETE FROM content_translations WHERE content_type = 'about' AND content_id = ?", [id])?;
+        self.conn.execute("DELETE FROM about_revisions WHERE about_id = ?", [id])?;
+        self.conn.execute("DELETE FROM aboutme WHERE id = ?", [id])?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+impl ContactRepository for SqliteRepository<'_> {
+    fn list(&self) -> Result<Vec<ContactEntry>, DatabaseError> {
+        query_all(self.conn, "WHERE deleted_at IS NULL ORDER BY id DESC", [])
+    }
+
+    fn get(&self, id: i64) -> Result<Option<ContactEntry>, DatabaseError> {
+        query_one(self.conn, "WHERE id = ?", [id])
+    }
+
+    fn active(&self) -> Result<Option<ContactEntry>, DatabaseError> {
+        query_one(self.conn, "WHERE active = 1 AND deleted_at IS NULL ORDER BY id DESC LIMIT 1", [])
+    }
+
+    fn insert(&self, description: &str, admin_mail: &str, author: &Author) -> Result<i64, DatabaseError> {
+        let tx = self.begin(TransactionBehavior::Deferred)?;
+        self.conn.execute(
+            "INSERT INTO contact (description, admin_mail, active) VALUES (?, ?, 0)",
+            params![description, admin_mail],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.conn.execute(
+            "INSERT INTO contact_revisions (contact_id, description, admin_mail, author_id, author_name) VALUES (?, ?, ?, ?, ?)",
+            params![id, description, admin_mail, author.id, author.name],
+        )?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    fn save(&self, id: i64, description: &str, admin_mail: &str, author: &Author) -> Result<i64, DatabaseError> {
+        self.conn.execute(
+            "INSERT INTO contact_revisions (contact_id, description, admin_mail, author_id, author_name) VALUES (?, ?, ?, ?, ?)",
+            params![id, description, admin_mail, author.id, author.name],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    fn revisions(&self, id: i64) -> Result<Vec<ContactRevision>, DatabaseError> {
+        query_all(self.conn, "WHERE contact_id = ? ORDER BY id DESC", [id])
+    }
+
+    fn revision(&self, revision_id: i64) -> Result<Option<ContactRevision>, DatabaseError> {
+        query_one(self.conn, "WHERE id = ?", [revision_id])
+    }
+
+    fn publish(&self, id: i64, revision_id: i64) -> Result<(), DatabaseError> {
+        let tx = self.begin(TransactionBehavior::Immediate)?;
+        let (description, admin_mail): (String, String) = self.conn.query_row(
+            "SELECT description, admin_mail FROM contact_revisions WHERE id = ? AND contact_id = ?",
+            params![revision_id, id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        self.conn.execute("UPDATE contact SET active = 0 WHERE active = 1 AND id != ?", [id])?;
+        let published = self.conn.execute(
+            "UPDATE contact SET description = ?, admin_mail = ?, active = 1, published_revision_id = ?
+             WHERE id = ? AND deleted_at IS NULL",
+            params![description, admin_mail, revision_id, id],
+        )?;
+        if published == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows.into());
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn unpublish(&self, id: i64) -> Result<(), DatabaseError> {
+        self.conn.execute("UPDATE contact SET active = 0 WHERE id = ?", [id])?;
+        Ok(())
+    }
+
+    fn delete(&self, id: i64) -> Result<(), DatabaseError> {
+        self.conn.execute(
+            "UPDATE contact SET active = 0, deleted_at = CURRENT_TIMESTAMP WHERE id = ?",
+            [id],
+        )?;
+        Ok(())
+    }
+
+    fn trash(&self) -> Result<Vec<ContactEntry>, DatabaseError> {
+        query_all(self.conn, "WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id DESC", [])
+    }
+
+    fn undelete(&self, id: i64) -> Result<(), DatabaseError> {
+        self.conn.execute("UPDATE contact SET deleted_at = NULL WHERE id = ?", [id])?;
+        Ok(())
+    }
+
+    fn purge(&self, id: i64) -> Result<(), DatabaseError> {
+        let tx = self.begin(TransactionBehavior::Deferred)?;
+        self.conn.execute("DELETE FROM content_translations WHERE content_type = 'contact' AND content_id = ?", [id])?;
+        self.conn.execute("DELETE FROM contact_revisions WHERE contact_id = ?", [id])?;
+        self.conn.execute("DELETE FROM contact WHERE id = ?", [id])?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+impl TranslationRepository for SqliteRepository<'_> {
+    fn translation(&self, content: TranslatedContent, id: i64, locale: &str) -> Result<Option<Translation>, DatabaseError> {
+        query_one(
+            self.conn,
+            "WHERE content_type = ? AND content_id = ? AND locale = ?",
+            params![content.as_str(), id, locale],
+        )
+    }
+
+    fn save_translation(&self, content: TranslatedContent, id: i64, translation: &Translation) -> Result<(), DatabaseError> {
+        if translation.is_blank() {
+            self.conn.execute(
+                "DELETE FROM content_translations WHERE content_type = ? AND content_id = ? AND locale = ?",
+                params![content.as_str(), id, translation.locale],
+            )?;
+            return Ok(());
+        }
+        self.conn.execute(
+            "INSERT INTO content_translations (content_type, content_id, locale, title, body) VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (content_type, content_id, locale)
+            DO UPDATE SET title = excluded.title, body = excluded.body, updated_at = CURRENT_TIMESTAMP",
+            params![content.as_str(), id, translation.locale, translation.title, translation.body],
+        )?;
+        Ok(())
+    }
+}
@@ -1,40 +1,117 @@
 use axum::{
-    extract::{Path, Request},
-    middleware::{from_fn, Next},
+    extract::{Path, Query, Request, State},
+    Extension,
+    middleware::{from_fn, from_fn_with_state, Next},
     response::{IntoResponse, Redirect, Response},
     routing::{get, post},
 };
 use axum_csrf::{CsrfConfig, CsrfLayer, CsrfToken, Key};
-use hyper::StatusCode;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hyper::{header, header::HeaderValue, StatusCode, Uri};
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
 use tower_sessions::{MemoryStore, Session, SessionManagerLayer};
 
+use crate::auth::{self, CurrentUser, Role};
+use crate::controllers::audit::AuditPageQuery;
+use crate::controllers::campaigns::CampaignPageQuery;
+use crate::controllers::revisions::DiffQuery;
+use crate::db::{self, AuditFilter, Pool};
+use crate::error::{AppError, ErrorPage};
+use crate::i18n;
+use crate::models::CampaignFilter;
+use crate::repositories::{CampaignRepository, SqliteRepository};
+use crate::sessions;
+use crate::views::themes::color_scheme::{self, ColorScheme};
+
 use crate::controllers::{
+    assets::theme_asset,
+    account::{account_page, change_password, change_username, create_api_token, revoke_api_token},
     about::{
-        about, about_all, about_create, about_delete, about_details, about_insert_created,
-        about_update,
+        about, about_all, about_create, about_delete, about_details, about_diff, about_insert_created,
+        about_publish, about_purge, about_restore, about_trash, about_undelete, about_unpublish, about_update,
     },
+    address_changes::{address_changes_page, approve_address_change, reject_address_change},
+    audit::{audit_export, audit_page},
     campaigns::{
-        campaign_details, create_campaign, delete_campaign, edit_campaign, edit_campaign_page, list_all_campaigns, list_campaigns, new_campaign_page, update_amount_page, update_campaign, update_campaign_amount
+        campaign_details, create_campaign, create_campaign_update, delete_campaign, edit_campaign, edit_campaign_page, list_all_campaigns, list_campaigns, new_campaign_page, new_update_page, update_amount_page, update_campaign_amount
     },
     contact::{
-        contact, contact_all, contact_create, contact_delete, contact_details,
-        contact_insert_created, contact_update,
+        contact, contact_all, contact_create, contact_delete, contact_details, contact_diff,
+        contact_insert_created, contact_publish, contact_purge, contact_restore, contact_trash,
+        contact_undelete, contact_unpublish, contact_update,
     },
     dashboard::dashboard,
     fourofour::fourofour,
     home::home,
+    internal_error::internal_error,
     login::{login_page, login_post},
     logout::logout,
+    preferences::set_color_scheme,
+    sessions::{revoke_all_sessions, revoke_session, sessions_page},
+    themes::{custom_css_update, theme_activate, theme_delete, theme_upload, themes_page, ThemePreviewQuery},
+    totp::{
+        login_totp_page, login_totp_post, two_factor_disable, two_factor_enable, two_factor_setup,
+    },
+    translations::{save_about_translation, save_campaign_translation, save_contact_translation, TranslationQuery},
+    users::{user_create, user_delete, user_update_role, users_page},
 };
 
+async fn campaign_creator(pool: &Pool, id: i64) -> Result<Option<i64>, db::DatabaseError> {
+    db::run(pool, move |conn| {
+        Ok(CampaignRepository::get(&SqliteRepository::new(conn), id)?.map(|campaign| campaign.creator_id))
+    })
+    .await
+}
+
+/// Confirmation questions the built-in templates ask through the `confirm` filter; their
+/// handlers in every locale are allowed by hash in the Content-Security-Policy.
+/// Templates may not use any other inline script.
+pub const INLINE_CONFIRMATIONS: &[&str] = &[
+    "confirm-generic",
+    "confirm-delete-campaign",
+    "confirm-delete-user",
+    "confirm-revoke-all-sessions",
+];
+
+/// The inline handlers allowed by the Content-Security-Policy.
+pub fn inline_handlers() -> Vec<String> {
+    let mut handlers: Vec<String> = i18n::LOCALES
+        .iter()
+        .flat_map(|(locale, _, _)| INLINE_CONFIRMATIONS.iter().map(|key| i18n::confirm_handler(locale, key)))
+        .collect();
+    handlers.sort();
+    handlers.dedup();
+    handlers
+}
+
+/// Stylesheets, forms and images come from this site only (rich text may embed
+/// `https` images); no inline styles and no scripts beyond the confirmation prompts.
+pub fn content_security_policy() -> &'static str {
+    static POLICY: OnceLock<String> = OnceLock::new();
+    POLICY.get_or_init(|| {
+        let handlers: String = inline_handlers()
+            .iter()
+            .map(|handler| format!(" 'sha256-{}'", BASE64.encode(Sha256::digest(handler.as_bytes()))))
+            .collect();
+        format!(
+            "default-src 'self'; style-src 'self'; img-src 'self' https:; script-src 'unsafe-hashes'{}; \
+             object-src 'none'; base-uri 'self'; form-action 'self'; frame-ancestors 'none'",
+            handlers
+        )
+    })
+}
+
 pub struct Router {
+    pool: Pool,
     session_store: MemoryStore,
     csrf_key: Key,
 }
 
 impl Router {
-    pub fn new() -> Self {
+    pub fn new(pool: Pool) -> Self {
         Self {
+            pool,
             session_store: MemoryStore::default(),
             csrf_key: Key::generate(),
         }
@@ -49,125 +126,539 @@ impl Router {
             .with_key(Some(self.csrf_key.clone()))
             .with_cookie_name("csrf-token");
         let csrf_layer = CsrfLayer::new(csrf_config);
+        let pool = self.pool.clone();
 
-        axum::Router::new()
-            .route("/", get(|session: Session| async move { home(&session).await }))
-            .route("/about", get(|session: Session| async move { about(&session).await }))
+        let routes = axum::Router::new()
+            .route("/", get(|State(pool): State<Pool>, session: Session| async move { home(&pool, &session).await }))
+            .route("/about", get(|State(pool): State<Pool>, session: Session| async move { about(&pool, &session).await }))
             .route(
                 "/login",
                 get(|csrf_token: CsrfToken, session: Session| async move { login_page(csrf_token, &session).await })
                     .layer(from_fn(Self::already_logged_in))
                     .post(login_post),
             )
+            .route(
+                "/login/totp",
+                get(|csrf_token: CsrfToken, session: Session| async move { login_totp_page(csrf_token, &session).await })
+                    .post(login_totp_post),
+            )
             .route("/logout", get(logout))
+            .route("/assets/:file", get(theme_asset))
+            .route(
+                "/protected/2fa",
+                get(|| async { Redirect::permanent("/protected/account") }).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/2fa/setup",
+                post(two_factor_setup).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/2fa/enable",
+                post(two_factor_enable).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/2fa/disable",
+                post(two_factor_disable).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
             .route(
                 "/protected/about/new",
                 get(|csrf_token: CsrfToken, session: Session| async move { about_create(csrf_token, &session).await })
                     .post(about_insert_created)
-                    .layer(from_fn(Self::require_auth)),
+                    .layer(from_fn(Self::require_page_editor))
+                    .layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
             .route(
                 "/protected/about/all",
-                get(|session: Session| async move { about_all(&session).await }).layer(from_fn(Self::require_auth)),
+                get(|State(pool): State<Pool>, session: Session| async move { about_all(&pool, &session).await }).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
             .route(
                 "/protected/about/:id",
-                get(|path: axum::extract::Path<i64>, csrf_token: CsrfToken, session: Session| async move {
-                    about_details(path, csrf_token, &session).await
-                }).layer(from_fn(Self::require_auth)),
+                get(|State(pool): State<Pool>, path: axum::extract::Path<i64>, query: Query<TranslationQuery>, csrf_token: CsrfToken, session: Session| async move {
+                    about_details(&pool, path, query, csrf_token, &session).await
+                }).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/about/:id/translations",
+                post(save_about_translation).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
             .route(
                 "/protected/about/:id/update",
-                post(about_update).layer(from_fn(Self::require_auth)),
+                post(about_update).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
             .route(
                 "/protected/about/:id/delete",
-                post(about_delete).layer(from_fn(Self::require_auth)),
+                post(about_delete).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/about/trash",
+                get(|State(pool): State<Pool>, csrf_token: CsrfToken, session: Session| async move {
+                    about_trash(&pool, csrf_token, &session).await
+                }).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/about/:id/diff",
+                get(|State(pool): State<Pool>, path: axum::extract::Path<i64>, query: Query<DiffQuery>, session: Session| async move {
+                    about_diff(&pool, path, query, &session).await
+                }).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/about/:id/revisions/:revision_id/publish",
+                post(about_publish).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/about/:id/revisions/:revision_id/restore",
+                post(about_restore).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/about/:id/unpublish",
+                post(about_unpublish).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/about/:id/undelete",
+                post(about_undelete).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/about/:id/purge",
+                post(about_purge).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
             .nest(
                 "/protected",
                 axum::Router::new()
-                    .route("/dashboard", get(|session: Session| async move { dashboard(session).await }))
-                    .layer(from_fn(Self::require_auth)),
+                    .route(
+                        "/dashboard",
+                        get(|Extension(user): Extension<CurrentUser>, session: Session| async move {
+                            dashboard(&user, session).await
+                        }),
+                    )
+                    .route(
+                        "/account",
+                        get(|State(pool): State<Pool>, Extension(user): Extension<CurrentUser>, csrf_token: CsrfToken, session: Session| async move {
+                            account_page(&pool, &user, csrf_token, &session).await
+                        }),
+                    )
+                    .route("/account/password", post(change_password))
+                    .route("/account/username", post(change_username))
+                    .route("/account/tokens", post(create_api_token))
+                    .route("/account/tokens/:id/revoke", post(revoke_api_token))
+                    .route(
+                        "/sessions",
+                        get(|State(pool): State<Pool>, Extension(user): Extension<CurrentUser>, csrf_token: CsrfToken, session: Session| async move {
+                            sessions_page(&pool, &user, csrf_token, &session).await
+                        }),
+                    )
+                    .route("/sessions/:id/revoke", post(revoke_session))
+                    .route("/sessions/revoke-all", post(revoke_all_sessions))
+                    .route(
+                        "/users",
+                        get(|State(pool): State<Pool>, csrf_token: CsrfToken, session: Session| async move { users_page(&pool, csrf_token, &session).await })
+                            .post(user_create)
+                            .layer(from_fn(Self::require_owner)),
+                    )
+                    .route(
+                        "/address-changes",
+                        get(|State(pool): State<Pool>, Extension(user): Extension<CurrentUser>, csrf_token: CsrfToken, session: Session| async move {
+                            address_changes_page(&pool, &user, csrf_token, &session).await
+                        })
+                        .layer(from_fn(Self::require_campaign_creator)),
+                    )
+                    .route(
+                        "/address-changes/:id/approve",
+                        post(approve_address_change).layer(from_fn_with_state(pool.clone(), Self::require_address_change_manager)),
+                    )
+                    .route(
+                        "/address-changes/:id/reject",
+                        post(reject_address_change).layer(from_fn_with_state(pool.clone(), Self::require_address_change_manager)),
+                    )
+                    .route(
+                        "/audit",
+                        get(|State(pool): State<Pool>, filter: Query<AuditFilter>, page: Query<AuditPageQuery>, session: Session| async move {
+                            audit_page(&pool, filter, page, &session).await
+                        })
+                        .layer(from_fn(Self::require_owner)),
+                    )
+                    .route("/audit/export", get(audit_export).layer(from_fn(Self::require_owner)))
+                    .route("/users/:id/role", post(user_update_role).layer(from_fn(Self::require_owner)))
+                    .route("/users/:id/delete", post(user_delete).layer(from_fn(Self::require_owner)))
+                    .route(
+                        "/themes",
+                        get(|State(pool): State<Pool>, query: Query<ThemePreviewQuery>, csrf_token: CsrfToken, session: Session| async move {
+                            themes_page(&pool, query, csrf_token, &session).await
+                        })
+                        .layer(from_fn(Self::require_owner)),
+                    )
+                    .route("/themes/active", post(theme_activate).layer(from_fn(Self::require_owner)))
+                    .route("/themes/custom-css", post(custom_css_update).layer(from_fn(Self::require_owner)))
+                    .route("/themes/upload", post(theme_upload).layer(from_fn(Self::require_owner)))
+                    .route("/themes/:name/delete", post(theme_delete).layer(from_fn(Self::require_owner)))
+                    .layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
-            .route("/contact", get(|session: Session| async move { contact(&session).await }))
+            .route("/color-scheme", post(set_color_scheme))
+            .route("/contact", get(|State(pool): State<Pool>, session: Session| async move { contact(&pool, &session).await }))
             .route(
                 "/protected/contact/new",
                 get(|csrf_token: CsrfToken, session: Session| async move { contact_create(csrf_token, &session).await })
                     .post(contact_insert_created)
-                    .layer(from_fn(Self::require_auth)),
+                    .layer(from_fn(Self::require_page_editor))
+                    .layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
             .route(
                 "/protected/contact/all",
-                get(|session: Session| async move { contact_all(&session).await }).layer(from_fn(Self::require_auth)),
+                get(|State(pool): State<Pool>, session: Session| async move { contact_all(&pool, &session).await }).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
             .route(
                 "/protected/contact/:id",
-                get(|path: axum::extract::Path<i64>, csrf_token: CsrfToken, session: Session| async move {
-                    contact_details(path, csrf_token, &session).await
-                }).layer(from_fn(Self::require_auth)),
+                get(|State(pool): State<Pool>, path: axum::extract::Path<i64>, query: Query<TranslationQuery>, csrf_token: CsrfToken, session: Session| async move {
+                    contact_details(&pool, path, query, csrf_token, &session).await
+                }).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/contact/:id/translations",
+                post(save_contact_translation).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
             .route(
                 "/protected/contact/:id/update",
-                post(contact_update).layer(from_fn(Self::require_auth)),
+                post(contact_update).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/contact/:id/delete",
+                post(contact_delete).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/contact/trash",
+                get(|State(pool): State<Pool>, csrf_token: CsrfToken, session: Session| async move {
+                    contact_trash(&pool, csrf_token, &session).await
+                }).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/contact/:id/diff",
+                get(|State(pool): State<Pool>, path: axum::extract::Path<i64>, query: Query<DiffQuery>, session: Session| async move {
+                    contact_diff(&pool, path, query, &session).await
+                }).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/contact/:id/revisions/:revision_id/publish",
+                post(contact_publish).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/contact/:id/revisions/:revision_id/restore",
+                post(contact_restore).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/contact/:id/unpublish",
+                post(contact_unpublish).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/contact/:id/undelete",
+                post(contact_undelete).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/contact/:id/purge",
+                post(contact_purge).layer(from_fn(Self::require_page_editor)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
             .route(
                 "/protected/campaigns/:id/edit",
+                get(|State(pool): State<Pool>, Extension(user): Extension<CurrentUser>, path: Path<i64>, query: Query<TranslationQuery>, csrf_token: CsrfToken, session: Session| async move {
+                    edit_campaign_page(&pool, &user, path, query, csrf_token, &session).await
+                })
+                .layer(from_fn_with_state(pool.clone(), Self::require_campaign_editor))
+                .layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/campaigns/:id/edit",
+                post(edit_campaign)
+                    .layer(from_fn_with_state(pool.clone(), Self::require_campaign_manager))
+                    .layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/campaigns/:id/translations",
+                post(save_campaign_translation)
+                    .layer(from_fn_with_state(pool.clone(), Self::require_campaign_editor))
+                    .layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/protected/campaigns/:id/updates",
                 get(|path: Path<i64>, csrf_token: CsrfToken, session: Session| async move {
-                    edit_campaign_page(path, csrf_token, &session).await
+                    new_update_page(path, csrf_token, &session).await
                 })
-                .post(edit_campaign)
-                .layer(from_fn(Self::require_auth)),
+                .post(create_campaign_update)
+                .layer(from_fn_with_state(pool.clone(), Self::require_campaign_editor))
+                .layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
 
-            .route("/protected/campaigns", get(|session: Session| async move { list_all_campaigns(&session).await }).layer(from_fn(Self::require_auth)))
+            .route(
+                "/protected/campaigns",
+                get(|State(pool): State<Pool>, Extension(user): Extension<CurrentUser>, filter: Query<CampaignFilter>, page: Query<CampaignPageQuery>, session: Session| async move {
+                    list_all_campaigns(&pool, &user, filter, page, &session).await
+                })
+                .layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
             .route(
                 "/protected/campaigns/new",
                 get(|csrf_token: CsrfToken, session: Session| async move { new_campaign_page(csrf_token, &session).await })
                     .post(create_campaign)
-                    .layer(from_fn(Self::require_auth)),
+                    .layer(from_fn(Self::require_campaign_creator))
+                    .layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )            
             .route(
                 "/protected/campaigns/:id/delete",
-                post(delete_campaign).layer(from_fn(Self::require_auth)),
+                post(delete_campaign).layer(from_fn_with_state(pool.clone(), Self::require_campaign_manager)).layer(from_fn_with_state(pool.clone(), Self::require_auth)),
+            )
+            .route(
+                "/campaigns",
+                get(|State(pool): State<Pool>, filter: Query<CampaignFilter>, page: Query<CampaignPageQuery>, session: Session| async move {
+                    list_campaigns(&pool, filter, page, &session).await
+                }),
             )
             .route(
                 "/campaigns/:id",
-                get(|path: axum::extract::Path<i64>, session: Session| async move {
-                    campaign_details(path, &session).await
+                get(|State(pool): State<Pool>, path: axum::extract::Path<i64>, session: Session| async move {
+                    campaign_details(&pool, path, &session).await
                 }),
             )
             .route(
                 "/protected/campaigns/:id/amount",
                 post(update_campaign_amount)
-                .get(|path: Path<i64>, csrf_token: CsrfToken, session: Session| async move {
-                    update_amount_page(path, csrf_token, &session).await
+                .get(|State(pool): State<Pool>, path: Path<i64>, csrf_token: CsrfToken, session: Session| async move {
+                    update_amount_page(&pool, path, csrf_token, &session).await
                 })
-                .layer(from_fn(Self::require_auth)),
+                .layer(from_fn_with_state(pool.clone(), Self::require_campaign_manager))
+                .layer(from_fn_with_state(pool.clone(), Self::require_auth)),
             )
             
 
-            .fallback(get(|session: Session| async move { fourofour(&session).await }))
+            .fallback(|| async { AppError::NotFound })
+            .layer(from_fn(Self::render_error_pages))
+            .layer(from_fn(Self::color_scheme))
             .layer(session_layer)
             .layer(csrf_layer)
+            .layer(from_fn(Self::security_headers))
+            .with_state(pool);
+
+        axum::Router::new().fallback_service(routes).layer(from_fn(Self::locale))
+    }
+
+    /// Picks the language of the request's pages: a `/<locale>` URL prefix, which is
+    /// stripped before routing and remembered in a cookie, else that cookie, else
+    /// `Accept-Language`.
+    async fn locale(mut request: Request, next: Next) -> Response {
+        let headers = request.headers();
+        let negotiated = headers
+            .get(header::COOKIE)
+            .and_then(|cookie| cookie.to_str().ok())
+            .and_then(i18n::from_cookie_header)
+            .or_else(|| {
+                headers
+                    .get(header::ACCEPT_LANGUAGE)
+                    .and_then(|accept| accept.to_str().ok())
+                    .and_then(i18n::from_accept_language)
+            })
+            .unwrap_or(i18n::DEFAULT_LOCALE);
+
+        let prefix = match i18n::split_prefix(request.uri().path()) {
+            Some((locale, rest)) => {
+                let path = if rest.is_empty() { "/" } else { rest };
+                let path_and_query = match request.uri().query() {
+                    Some(query) => format!("{}?{}", path, query),
+                    None => path.to_string(),
+                };
+                Some((locale, path_and_query))
+            }
+            None => None,
+        };
+        if let Some(uri) = prefix.as_ref().and_then(|(_, path_and_query)| Uri::try_from(path_and_query.as_str()).ok()) {
+            *request.uri_mut() = uri;
+        }
+        let locale = prefix.map_or(negotiated, |(locale, _)| locale);
+
+        let path = request.uri().path_and_query().map_or("/", |path| path.as_str()).to_string();
+        let mut response = i18n::scope(locale, path, next.run(request)).await;
+        let headers = response.headers_mut();
+        headers.insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale));
+        headers.append(header::VARY, HeaderValue::from_static("Accept-Language, Cookie"));
+        if locale != negotiated {
+            if let Ok(cookie) = HeaderValue::from_str(&i18n::set_cookie(locale)) {
+                headers.append(header::SET_COOKIE, cookie);
+            }
+        }
+        response
+    }
+
+    /// Sets the Content-Security-Policy on every response that has none yet.
+    async fn security_headers(request: Request, next: Next) -> Response {
+        let mut response = next.run(request).await;
+        response
+            .headers_mut()
+            .entry(header::CONTENT_SECURITY_POLICY)
+            .or_insert_with(|| HeaderValue::from_static(content_security_policy()));
+        response
+    }
+
+    /// Renders the request's pages in the colour scheme chosen with the footer toggle.
+    async fn color_scheme(request: Request, next: Next) -> Response {
+        let scheme = request
+            .headers()
+            .get(header::COOKIE)
+            .and_then(|cookie| cookie.to_str().ok())
+            .map(ColorScheme::from_cookie_header)
+            .unwrap_or_default();
+        color_scheme::scope(scheme, next.run(request)).await
     }
 
     async fn require_auth(
+        State(pool): State<Pool>,
         session: Session,
+        mut request: Request,
+        next: Next,
+    ) -> Result<Response, StatusCode> {
+        let user = if let Some(token) = Self::bearer_token(&request) {
+            let token_hash = auth::hash_token(&token);
+            let user = db::run(&pool, move |conn| match db::use_api_token(conn, &token_hash)? {
+                Some(user_id) => Ok(Some(db::get_user(conn, user_id).unwrap_or(None))),
+                None => Ok(None),
+            })
+            .await;
+            match user {
+                Ok(Some(user)) => user,
+                _ => return Err(StatusCode::UNAUTHORIZED),
+            }
+        } else {
+            match session.get::<i64>("user_id").await {
+                Ok(Some(user_id)) if sessions::is_active(&pool, &session, user_id).await => {
+                    db::run(&pool, move |conn| db::get_user(conn, user_id)).await.unwrap_or(None)
+                }
+                Ok(Some(_)) => {
+                    session.clear().await;
+                    None
+                }
+                _ => None,
+            }
+        };
+
+        match user {
+            Some((id, username, role)) => {
+                let role = Role::parse(&role).unwrap_or(Role::Editor);
+                request.extensions_mut().insert(CurrentUser { id, username, role });
+                Ok(next.run(request).await)
+            }
+            None => Ok(Redirect::to("/login").into_response()),
+        }
+    }
+
+    /// Replaces the body of `AppError` responses with the matching page.
+    async fn render_error_pages(session: Session, request: Request, next: Next) -> Response {
+        let response = next.run(request).await;
+        let Some(page) = response.extensions().get::<ErrorPage>().cloned() else {
+            return response;
+        };
+
+        let body = match page {
+            ErrorPage::NotFound => fourofour(&session).await,
+            ErrorPage::Internal { correlation_id } => internal_error(&session, &correlation_id).await,
+        };
+        (response.status(), body).into_response()
+    }
+
+    fn bearer_token(request: &Request) -> Option<String> {
+        request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| token.trim().to_string())
+    }
+
+    async fn require_owner(
+        Extension(user): Extension<CurrentUser>,
         request: Request,
         next: Next,
     ) -> Result<Response, StatusCode> {
-        match session.get::<String>("user_id").await {
-            Ok(Some(_)) => Ok(next.run(request).await),
-            _ => Ok(Redirect::to("/login").into_response()),
+        if user.can_manage_users() {
+            Ok(next.run(request).await)
+        } else {
+            Err(StatusCode::FORBIDDEN)
         }
     }
+
+    async fn require_page_editor(
+        Extension(user): Extension<CurrentUser>,
+        request: Request,
+        next: Next,
+    ) -> Result<Response, StatusCode> {
+        if user.can_edit_pages() {
+            Ok(next.run(request).await)
+        } else {
+            Err(StatusCode::FORBIDDEN)
+        }
+    }
+
+    async fn require_campaign_creator(
+        Extension(user): Extension<CurrentUser>,
+        request: Request,
+        next: Next,
+    ) -> Result<Response, StatusCode> {
+        if user.can_create_campaigns() {
+            Ok(next.run(request).await)
+        } else {
+            Err(StatusCode::FORBIDDEN)
+        }
+    }
+
+    async fn require_campaign_manager(
+        State(pool): State<Pool>,
+        Path(id): Path<i64>,
+        Extension(user): Extension<CurrentUser>,
+        request: Request,
+        next: Next,
+    ) -> Result<Response, StatusCode> {
+        match campaign_creator(&pool, id).await {
+            Ok(Some(creator_id)) if user.can_manage_campaign(creator_id) => Ok(next.run(request).await),
+            Ok(Some(_)) => Err(StatusCode::FORBIDDEN),
+            Ok(None) => Err(StatusCode::NOT_FOUND),
+            Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    }
+
+    async fn require_campaign_editor(
+        State(pool): State<Pool>,
+        Path(id): Path<i64>,
+        Extension(user): Extension<CurrentUser>,
+        request: Request,
+        next: Next,
+    ) -> Result<Response, StatusCode> {
+        match campaign_creator(&pool, id).await {
+            Ok(Some(creator_id)) if user.can_edit_campaign_content(creator_id) => Ok(next.run(request).await),
+            Ok(Some(_)) => Err(StatusCode::FORBIDDEN),
+            Ok(None) => Err(StatusCode::NOT_FOUND),
+            Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    }
+    async fn require_address_change_manager(
+        State(pool): State<Pool>,
+        Path(id): Path<i64>,
+        Extension(user): Extension<CurrentUser>,
+        request: Request,
+        next: Next,
+    ) -> Result<Response, StatusCode> {
+        let campaign_id = match db::run(&pool, move |conn| db::get_address_change_request(conn, id)).await {
+            Ok(Some(request)) => request.campaign_id,
+            Ok(None) => return Err(StatusCode::NOT_FOUND),
+            Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        };
+
+        match campaign_creator(&pool, campaign_id).await {
+            Ok(Some(creator_id)) if user.can_manage_campaign(creator_id) => Ok(next.run(request).await),
+            Ok(Some(_)) => Err(StatusCode::FORBIDDEN),
+            Ok(None) => Err(StatusCode::NOT_FOUND),
+            Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    }
+
     async fn already_logged_in(
         session: Session,
         request: Request,
         next: Next,
     ) -> Result<Response, StatusCode> {
-        match session.get::<String>("user_id").await {
+        match session.get::<i64>("user_id").await {
             Ok(Some(_)) if request.uri().path() == "/login" => {
                 Ok(Redirect::to("/protected/dashboard").into_response())
             }
@@ -0,0 +1,58 @@
+use ammonia::Builder;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Elements editors may use in fields that accept HTML.
+const TAGS: &[&str] = &[
+    "a", "abbr", "b", "blockquote", "br", "code", "del", "div", "em", "h1", "h2", "h3", "h4", "h5", "h6", "hr", "i",
+    "img", "input", "li", "ol", "p", "pre", "s", "small", "strong", "sub", "sup", "table", "tbody", "td", "th",
+    "thead", "tr", "u", "ul",
+];
+
+const URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Put in front of every `id`, and of in-page links to them, so content can't
+/// clash with or hijack the ids of the surrounding page.
+const ID_PREFIX: &str = "user-content-";
+
+fn builder() -> &'static Builder<'static> {
+    static BUILDER: OnceLock<Builder<'static>> = OnceLock::new();
+    BUILDER.get_or_init(|| {
+        let mut builder = Builder::empty();
+        builder
+            .tags(TAGS.iter().copied().collect())
+            .clean_content_tags(HashSet::from(["script", "style"]))
+            .tag_attributes(HashMap::from([
+                ("a", HashSet::from(["href", "title"])),
+                ("abbr", HashSet::from(["title"])),
+                ("div", HashSet::from(["id"])),
+                ("img", HashSet::from(["src", "alt", "title", "width", "height"])),
+                ("input", HashSet::from(["checked"])),
+                ("td", HashSet::from(["colspan", "rowspan"])),
+                ("th", HashSet::from(["colspan", "rowspan"])),
+            ]))
+            // Footnotes and task lists as rendered from Markdown.
+            .allowed_classes(HashMap::from([
+                ("div", HashSet::from(["footnote-definition"])),
+                ("sup", HashSet::from(["footnote-reference", "footnote-definition-label"])),
+            ]))
+            .set_tag_attribute_value("input", "type", "checkbox")
+            .set_tag_attribute_value("input", "disabled", "")
+            .url_schemes(URL_SCHEMES.iter().copied().collect())
+            .url_relative(ammonia::UrlRelative::PassThrough)
+            .link_rel(Some("noopener noreferrer nofollow"))
+            .id_prefix(Some(ID_PREFIX))
+            .attribute_filter(|element, attribute, value| match (element, attribute, value.strip_prefix('#')) {
+                ("a", "href", Some(fragment)) => Some(Cow::Owned(format!("#{}{}", ID_PREFIX, fragment))),
+                _ => Some(Cow::Borrowed(value)),
+            });
+        builder
+    })
+}
+
+/// Cleans editor HTML down to the allowed tags, attributes and URL schemes.
+/// Scripts, styles, event handlers and `javascript:` links are dropped.
+pub fn rich_text(html: &str) -> String {
+    builder().clean(html).to_string()
+}
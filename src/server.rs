@@ -12,7 +12,7 @@ pub async fn start_server(app: Router) {
     let addr = SocketAddr::new(server_host.parse().unwrap(), server_port);
     println!("listening on {}", addr);
     axum_server::bind(addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .unwrap();
 }
@@ -0,0 +1,88 @@
+use crate::audit::ClientIp;
+use crate::auth::{generate_token, hash_token};
+use crate::db;
+use crate::error::AppError;
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use std::convert::Infallible;
+use tower_sessions::Session;
+
+const SESSION_TOKEN_KEY: &str = "session_token";
+const MAX_USER_AGENT_LEN: usize = 256;
+
+/// The client's `User-Agent` header, truncated for storage.
+#[derive(Clone, Debug)]
+pub struct UserAgent(pub String);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for UserAgent
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(MAX_USER_AGENT_LEN).collect())
+            .unwrap_or_else(|| "unknown".to_string());
+        Ok(UserAgent(user_agent))
+    }
+}
+
+/// Logs the user in under a new session id, so one planted before login is
+/// useless, and registers the session so it can be listed and revoked.
+pub async fn start(
+    pool: &db::Pool,
+    session: &Session,
+    user_id: i64,
+    ip: &ClientIp,
+    user_agent: &UserAgent,
+) -> Result<(), AppError> {
+    session.cycle_id().await?;
+    let token = generate_token();
+    let (token_hash, ip, user_agent) = (hash_token(&token), ip.0.clone(), user_agent.0.clone());
+    db::run(pool, move |conn| db::insert_user_session(conn, user_id, &token_hash, &ip, &user_agent))
+        .await?;
+    session
+        .insert("user_id", user_id)
+        .await?;
+    session
+        .insert(SESSION_TOKEN_KEY, token)
+        .await?;
+    Ok(())
+}
+
+/// Hash of the current session's token, as stored in `user_sessions`.
+pub async fn current_token_hash(session: &Session) -> Option<String> {
+    session
+        .get::<String>(SESSION_TOKEN_KEY)
+        .await
+        .ok()
+        .flatten()
+        .map(|token| hash_token(&token))
+}
+
+/// Checks that the session hasn't been revoked and updates its last-seen time.
+pub async fn is_active(pool: &db::Pool, session: &Session, user_id: i64) -> bool {
+    match current_token_hash(session).await {
+        Some(token_hash) => db::run(pool, move |conn| db::touch_user_session(conn, user_id, &token_hash))
+            .await
+            .unwrap_or(false),
+        None => false,
+    }
+}
+
+pub async fn end(pool: &db::Pool, session: &Session) {
+    if let Some(token_hash) = current_token_hash(session).await {
+        if let Err(e) = db::run(pool, move |conn| db::delete_user_session_by_token(conn, &token_hash)).await {
+            eprintln!("Error: failed to remove session record: {}", e);
+        }
+    }
+    session.clear().await;
+}
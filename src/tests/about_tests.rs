@@ -3,13 +3,11 @@ use axum::{
     body::Body,
     http::{Request, StatusCode},
 };
-use std::env;
 use tower::ServiceExt;
 
 #[tokio::test]
 async fn test_about_page_display() {
-    env::set_var("DATABASE_URL", ":memory:");
-    let router = Router::new();
+    let router = Router::new(super::test_pool());
     let app = router.create_router();
 
     let response = app
@@ -0,0 +1,52 @@
+use crate::auth::{check_password_strength, check_username, hash_password, needs_rehash, verify_password};
+use crate::routes::Router;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+
+#[test]
+fn test_password_strength_rules() {
+    assert!(check_password_strength("short1A!", "admin").is_err());
+    assert!(check_password_strength("alllowercaseletters", "admin").is_err());
+    assert!(check_password_strength("Admin-password-2024", "admin").is_err());
+    assert!(check_password_strength("Correct-horse-battery", "admin").is_ok());
+}
+
+#[test]
+fn test_username_rules() {
+    assert!(check_username("jane.doe-2").is_ok());
+    assert!(check_username("").is_err());
+    assert!(check_username("<script>").is_err());
+}
+
+#[test]
+fn test_password_hashes_and_legacy_plaintext() {
+    let hash = hash_password("Correct-horse-battery").unwrap();
+    assert!(verify_password(&hash, "Correct-horse-battery"));
+    assert!(!verify_password(&hash, "wrong"));
+    assert!(!needs_rehash(&hash));
+
+    assert!(verify_password("legacy-plaintext", "legacy-plaintext"));
+    assert!(needs_rehash("legacy-plaintext"));
+}
+
+#[tokio::test]
+async fn test_account_page_requires_auth() {
+    let router = Router::new(super::test_pool());
+    let app = router.create_router();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/protected/account")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers().get("location").unwrap(), "/login");
+}
@@ -0,0 +1,63 @@
+use crate::auth::Role;
+use crate::db;
+use crate::models::NewCampaign;
+use crate::repositories::{CampaignRepository, SqliteRepository};
+use crate::routes::Router;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_address_change_approval_requires_auth() {
+    let router = Router::new(super::test_pool());
+    let app = router.create_router();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/protected/address-changes/1/approve")
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(Body::from("password=admin"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers().get("location").unwrap(), "/login");
+}
+
+#[test]
+fn test_deleting_a_user_keeps_address_change_history() {
+    let pool = super::test_pool();
+    let mut conn = pool.get().unwrap();
+    let owner = db::insert_user(&conn, "owner", "unused", Role::Owner.as_str()).unwrap();
+    let manager = db::insert_user(&conn, "manager", "unused", Role::Manager.as_str()).unwrap();
+    let campaign = CampaignRepository::insert(
+        &SqliteRepository::new(&conn),
+        &NewCampaign {
+            title: "Node".to_string(),
+            description: "Fund the node".to_string(),
+            creator_id: owner,
+            start_date: "2024-01-01".to_string(),
+            end_date: "2024-12-31".to_string(),
+            goal_amount: 100.0,
+            status: "active".to_string(),
+            xmr_address: "old".to_string(),
+        },
+    )
+    .unwrap();
+    let approved = db::create_address_change_request(&mut conn, campaign, "old", "new", manager).unwrap();
+    assert!(db::approve_address_change(&mut conn, approved, owner).unwrap());
+    db::create_address_change_request(&mut conn, campaign, "new", "newer", manager).unwrap();
+
+    db::delete_user(&mut conn, manager).unwrap();
+
+    assert!(db::get_last_address_change(&conn, campaign).unwrap().is_some());
+    let request = db::get_address_change_request(&conn, approved).unwrap().unwrap();
+    assert_eq!((request.requested_by, request.status.as_str()), (None, "approved"));
+    assert!(db::get_pending_address_changes(&conn).unwrap().is_empty());
+}
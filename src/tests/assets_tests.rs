@@ -0,0 +1,68 @@
+use crate::routes::{content_security_policy, inline_handlers, Router, INLINE_CONFIRMATIONS};
+use crate::views::templates::SOURCES;
+use crate::views::themes::theme_manager;
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use tower::ServiceExt;
+
+async fn get(uri: &str) -> axum::response::Response {
+    let app = Router::new(super::test_pool()).create_router();
+    app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap()).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_pages_link_the_theme_stylesheet() {
+    let response = get("/").await;
+    let policy = response.headers()[header::CONTENT_SECURITY_POLICY].to_str().unwrap().to_string();
+    assert!(policy.contains("style-src 'self';"));
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+
+    let stylesheet = theme_manager::current().stylesheet_path();
+    assert!(body.contains(&format!("<link rel=\"stylesheet\" href=\"{}\">", stylesheet)));
+    assert!(!body.contains("<style"));
+}
+
+#[tokio::test]
+async fn test_theme_stylesheet_is_served_immutable() {
+    let themes = theme_manager::current();
+    let response = get(&themes.stylesheet_path()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "text/css; charset=utf-8");
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "public, max-age=31536000, immutable");
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, themes.get_active_theme_css().as_bytes());
+}
+
+#[tokio::test]
+async fn test_stale_theme_stylesheet_is_not_found() {
+    assert_eq!(get("/assets/theme-0000000000000000.css").await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(get("/assets/app.js").await.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn test_templates_stay_within_the_content_security_policy() {
+    for (name, source) in SOURCES {
+        assert!(!source.contains("<style") && !source.contains("style="), "{} has inline styles", name);
+        assert!(!source.contains("<script"), "{} has an inline script", name);
+        for (start, _) in source.match_indices(" on") {
+            let rest = &source[start + 3..];
+            let Some(attribute) = rest.split_once("=\"").filter(|(event, _)| event.chars().all(|c| c.is_ascii_lowercase())) else {
+                continue;
+            };
+            let key = attribute.1.strip_prefix("{{ \"").and_then(|rest| rest.split_once("\"|confirm }}\""));
+            let Some((key, _)) = key else {
+                panic!("{}: inline handlers must come from the confirm filter", name);
+            };
+            assert!(INLINE_CONFIRMATIONS.contains(&key), "{}: {} is not in INLINE_CONFIRMATIONS", name, key);
+        }
+    }
+}
+
+#[test]
+fn test_policy_allows_each_inline_handler_by_hash() {
+    let policy = content_security_policy();
+    assert!(policy.contains("script-src 'unsafe-hashes' 'sha256-"));
+    assert_eq!(policy.matches("'sha256-").count(), inline_handlers().len());
+    assert!(!policy.contains("unsafe-inline"));
+}
@@ -0,0 +1,38 @@
+use crate::audit::{self, ClientIp};
+use crate::routes::Router;
+use axum::{
+    body::Body,
+    extract::FromRequestParts,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_client_ip_trusts_real_ip_header_only_from_proxy() {
+    let client_ip = |peer: &str| audit::client_ip(Some(peer.parse().unwrap()), Some(" 203.0.113.7"), "10.0.0.2, 127.0.0.1");
+    assert_eq!(client_ip("127.0.0.1"), "203.0.113.7");
+    assert_eq!(client_ip("198.51.100.9"), "198.51.100.9");
+    assert_eq!(audit::client_ip(Some("127.0.0.1".parse().unwrap()), None, "127.0.0.1"), "127.0.0.1");
+
+    let (mut parts, _) = Request::builder().uri("/").header("x-real-ip", "203.0.113.7").body(()).unwrap().into_parts();
+    assert_eq!(ClientIp::from_request_parts(&mut parts, &()).await.unwrap().0, "unknown");
+}
+
+#[tokio::test]
+async fn test_audit_export_requires_auth() {
+    let router = Router::new(super::test_pool());
+    let app = router.create_router();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/protected/audit/export")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers().get("location").unwrap(), "/login");
+}
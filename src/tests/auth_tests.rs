@@ -7,7 +7,7 @@ use tower::ServiceExt;
 
 #[tokio::test]
 async fn test_home_page_access() {
-    let router = Router::new();
+    let router = Router::new(super::test_pool());
     let app = router.create_router();
 
     let response = app
@@ -20,7 +20,7 @@ async fn test_home_page_access() {
 
 #[tokio::test]
 async fn test_protected_dashboard_redirect() {
-    let router = Router::new();
+    let router = Router::new(super::test_pool());
     let app = router.create_router();
 
     let response = app
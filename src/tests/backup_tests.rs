@@ -0,0 +1,77 @@
+use crate::backup::{self, BackupConfig};
+use crate::db::{self, DatabaseError};
+use std::fs;
+use std::path::PathBuf;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mrs-funding-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn config(dir: &std::path::Path, compress: bool, keep: usize) -> BackupConfig {
+    BackupConfig {
+        dir: dir.join("backups"),
+        compress,
+        keep,
+        interval: None,
+    }
+}
+
+#[test]
+fn test_snapshot_and_restore_round_trip() {
+    let dir = scratch_dir("backup-round-trip");
+    let database = dir.join("live.db");
+    let pool = db::create_pool(database.to_str().unwrap()).unwrap();
+    db::setup_database(&pool).unwrap();
+    db::insert_user(&pool.get().unwrap(), "before", "unused", "owner").unwrap();
+
+    let snapshot = backup::create_snapshot(&pool.get().unwrap(), &config(&dir, true, 3)).unwrap();
+    assert!(snapshot.to_str().unwrap().ends_with(".db.gz"));
+
+    db::insert_user(&pool.get().unwrap(), "after", "unused", "owner").unwrap();
+    drop(pool);
+
+    backup::restore(&snapshot, &database).unwrap();
+    let pool = db::create_pool(database.to_str().unwrap()).unwrap();
+    let conn = pool.get().unwrap();
+    assert!(db::username_exists(&conn, "before").unwrap());
+    assert!(!db::username_exists(&conn, "after").unwrap());
+    assert!(fs::read_dir(&dir)
+        .unwrap()
+        .any(|entry| entry.unwrap().file_name().to_string_lossy().starts_with("live.db.pre-restore-")));
+}
+
+#[test]
+fn test_prune_keeps_newest_snapshots() {
+    let dir = scratch_dir("backup-prune");
+    let config = config(&dir, false, 2);
+    fs::create_dir_all(&config.dir).unwrap();
+    for name in ["snapshot-20240101-000000.db", "snapshot-20240102-000000.db.gz", "snapshot-20240103-000000.db", "notes.txt"] {
+        fs::write(config.dir.join(name), b"").unwrap();
+    }
+
+    let removed = backup::prune(&config).unwrap();
+    assert_eq!(removed, vec![config.dir.join("snapshot-20240101-000000.db")]);
+    assert!(config.dir.join("notes.txt").exists());
+}
+
+#[test]
+fn test_restore_rejects_newer_schema() {
+    let dir = scratch_dir("backup-newer");
+    let snapshot = dir.join("snapshot-20240101-000000.db");
+    let pool = db::create_pool(snapshot.to_str().unwrap()).unwrap();
+    db::setup_database(&pool).unwrap();
+    pool.get()
+        .unwrap()
+        .execute("INSERT INTO schema_version (version, name) VALUES (9999, 'future')", [])
+        .unwrap();
+    drop(pool);
+
+    let database = dir.join("live.db");
+    fs::write(&database, b"current").unwrap();
+    assert!(matches!(backup::restore(&snapshot, &database), Err(DatabaseError::SchemaTooNew(9999))));
+    assert_eq!(fs::read(&database).unwrap(), b"current");
+    assert!(!database.with_extension("restore").exists());
+}
@@ -0,0 +1,163 @@
+use crate::routes::Router;
+use crate::views::themes::color_scheme::ColorScheme;
+use crate::views::themes::theme_manager::{self, ThemeManager};
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use std::fs;
+use tower::ServiceExt;
+
+fn theme_dir(test: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("mrs-funding-dark-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_preference_is_read_from_the_cookie() {
+    assert_eq!(ColorScheme::from_cookie_header("session=abc; color-scheme=dark"), ColorScheme::Dark);
+    assert_eq!(ColorScheme::from_cookie_header("color-scheme=light"), ColorScheme::Light);
+    assert_eq!(ColorScheme::from_cookie_header("color-scheme=purple"), ColorScheme::Auto);
+    assert_eq!(ColorScheme::from_cookie_header("other-color-scheme=dark"), ColorScheme::Auto);
+    assert_eq!(ColorScheme::from_cookie_header(""), ColorScheme::Auto);
+
+    assert!(ColorScheme::Dark.set_cookie().starts_with("color-scheme=dark; Path=/; Max-Age=31536000"));
+    assert!(ColorScheme::Auto.set_cookie().starts_with("color-scheme=; Path=/; Max-Age=0"));
+}
+
+#[test]
+fn test_built_in_dark_variant_follows_the_preference() {
+    let manager = ThemeManager::load(None, "default");
+    assert!(manager.has_dark_variant());
+
+    let auto = manager.styles(None, ColorScheme::Auto);
+    assert_eq!(auto.color_scheme, "light dark");
+    assert_eq!(auto.dark_media, Some("(prefers-color-scheme: dark)"));
+    let dark_file = auto.dark_stylesheet.clone().unwrap();
+    assert_ne!(dark_file, auto.stylesheet);
+    let dark_css = manager.stylesheet(dark_file.trim_start_matches("/assets/")).unwrap();
+    assert!(dark_css.contains(".progress-badge .progress-track"));
+
+    let light = manager.styles(None, ColorScheme::Light);
+    assert_eq!((light.color_scheme, light.dark_stylesheet), ("light", None));
+
+    let dark = manager.styles(None, ColorScheme::Dark);
+    assert_eq!((dark.color_scheme, dark.dark_media), ("dark", None));
+    assert_eq!(dark.dark_stylesheet, Some(dark_file));
+    assert_eq!(dark.stylesheet, auto.stylesheet);
+}
+
+#[test]
+fn test_themes_without_a_variant_keep_their_scheme() {
+    let manager = ThemeManager::load(None, "minimal");
+    assert!(!manager.has_dark_variant());
+    for preference in ColorScheme::ALL {
+        let styles = manager.styles(None, preference);
+        assert_eq!((styles.color_scheme, styles.dark_stylesheet), ("dark", None));
+    }
+    let default = manager.styles(Some("default"), ColorScheme::Light);
+    assert_eq!(default.color_scheme, "light");
+    assert_eq!(default.stylesheet, manager.theme_stylesheet_path("default").unwrap());
+}
+
+#[test]
+fn test_theme_directory_dark_variants() {
+    let dir = theme_dir("files");
+    fs::write(dir.join("paper.css"), "body { color: black; }").unwrap();
+    fs::write(dir.join("paper.dark.css"), "body { color: white; }").unwrap();
+    fs::write(dir.join("orphan.dark.css"), "body { color: gray; }").unwrap();
+    fs::write(dir.join("default.css"), "body { color: red; }").unwrap();
+
+    let manager = ThemeManager::load(Some(&dir), "paper");
+    let dark = manager.styles(None, ColorScheme::Dark).dark_stylesheet.unwrap();
+    assert_eq!(manager.stylesheet(dark.trim_start_matches("/assets/")), Some("body { color: white; }"));
+    assert!(!manager.has_theme("orphan") && !manager.has_theme("orphan.dark"));
+    // A replaced built-in theme no longer gets the built-in dark variant.
+    assert_eq!(manager.styles(Some("default"), ColorScheme::Dark).dark_stylesheet, None);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_theme_package_dark_section() {
+    let dir = theme_dir("package");
+    let package = dir.join("dusk");
+    fs::create_dir_all(&package).unwrap();
+    fs::write(
+        package.join("theme.json"),
+        r##"{
+            "name": "dusk",
+            "version": "1.0.0",
+            "variables": { "background": "#fafafa" },
+            "dark": { "stylesheet": "dark.css", "variables": { "background": "#101010" } }
+        }"##,
+    )
+    .unwrap();
+    fs::write(package.join("theme.css"), "body { background: var(--background); }").unwrap();
+    fs::write(package.join("dark.css"), "img { opacity: 0.9; }").unwrap();
+    fs::create_dir_all(dir.join("night")).unwrap();
+    fs::write(dir.join("night/theme.json"), r#"{ "name": "night", "version": "1", "color_scheme": "dark" }"#).unwrap();
+    fs::write(dir.join("night/theme.css"), "body { background: black; }").unwrap();
+    fs::create_dir_all(dir.join("odd")).unwrap();
+    fs::write(dir.join("odd/theme.json"), r#"{ "name": "odd", "version": "1", "color_scheme": "sepia" }"#).unwrap();
+    fs::write(dir.join("odd/theme.css"), "body {}").unwrap();
+
+    let manager = ThemeManager::load(Some(&dir), "dusk");
+    let dark = manager.styles(None, ColorScheme::Auto).dark_stylesheet.unwrap();
+    let css = manager.stylesheet(dark.trim_start_matches("/assets/")).unwrap();
+    assert_eq!(css, ":root {\n    --background: #101010;\n}\nimg { opacity: 0.9; }");
+    assert_eq!(manager.styles(Some("night"), ColorScheme::Light).color_scheme, "dark");
+    assert!(manager.problems().iter().any(|problem| problem.theme == "odd" && problem.message.contains("color_scheme")));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_toggle_stores_the_preference_and_returns() {
+    let app = Router::new(super::test_pool()).create_router();
+    let toggle = |scheme: &str, referer: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/color-scheme")
+            .header(header::HOST, "example.org")
+            .header(header::REFERER, referer)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(format!("scheme={}", scheme)))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(toggle("dark", "https://example.org/campaigns?page=2")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[header::LOCATION], "/campaigns?page=2");
+    assert_eq!(response.headers()[header::SET_COOKIE], ColorScheme::Dark.set_cookie().as_str());
+
+    let response = app.clone().oneshot(toggle("auto", "https://evil.example/phish")).await.unwrap();
+    assert_eq!(response.headers()[header::LOCATION], "/");
+    let response = app.clone().oneshot(toggle("auto", "https://example.org//evil.example")).await.unwrap();
+    assert_eq!(response.headers()[header::LOCATION], "/");
+
+    let response = app.clone().oneshot(toggle("purple", "https://example.org/")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_pages_follow_the_cookie() {
+    let app = Router::new(super::test_pool()).create_router();
+    for preference in ColorScheme::ALL {
+        let request = Request::builder()
+            .uri("/")
+            .header(header::COOKIE, format!("color-scheme={}", preference.as_str()))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        let styles = theme_manager::current().styles(None, preference);
+        assert!(body.contains(&format!("<meta name=\"color-scheme\" content=\"{}\">", styles.color_scheme)), "{}", body);
+        if let Some(dark) = &styles.dark_stylesheet {
+            assert!(body.contains(dark.as_str()));
+            assert!(body.contains("action=\"/color-scheme\""));
+        }
+    }
+}
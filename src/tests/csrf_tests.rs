@@ -8,7 +8,7 @@ use axum::{
 use tower::ServiceExt;
 #[tokio::test]
 async fn test_csrf_protection_blocks_unauthorized_post() {
-    let router = Router::new();
+    let router = Router::new(super::test_pool());
     let app = router.create_router();
 
     let response = app
@@ -28,7 +28,7 @@ async fn test_csrf_protection_blocks_unauthorized_post() {
 
 #[tokio::test]
 async fn test_csrf_token_present_in_form() {
-    let router = Router::new();
+    let router = Router::new(super::test_pool());
     let app = router.create_router();
 
     let response = app
@@ -50,7 +50,6 @@ async fn test_csrf_token_present_in_form() {
 }
 #[tokio::test]
 async fn test_login_with_invalid_csrf_token() {
-    env::set_var("DATABASE_URL", ":memory:");
     env::set_var("ADMIN_USERNAME", "admin");
     env::set_var("ADMIN_PASSWORD", "admin");
     let conn = rusqlite::Connection::open(":memory:").unwrap();
@@ -70,7 +69,7 @@ async fn test_login_with_invalid_csrf_token() {
     )
     .unwrap();
 
-    let router = Router::new();
+    let router = Router::new(super::test_pool());
     let app = router.create_router();
 
     let response = app
@@ -0,0 +1,69 @@
+use crate::auth;
+use crate::db::{self, DatabaseError};
+use crate::error::{AppError, ErrorPage};
+use crate::routes::Router;
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    response::{Html, IntoResponse},
+};
+use tower::ServiceExt;
+
+async fn get(router: &Router, uri: &str, token: Option<&str>) -> (StatusCode, String) {
+    let mut request = Request::builder().uri(uri);
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let response = router
+        .create_router()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_missing_entries_render_404_page() {
+    let pool = super::test_pool();
+    let token = auth::generate_token();
+    {
+        let conn = pool.get().unwrap();
+        let user_id = db::insert_user(&conn, "owner", "unused", "owner").unwrap();
+        db::insert_api_token(&conn, user_id, "tests", &auth::hash_token(&token)).unwrap();
+    }
+    let router = Router::new(pool);
+
+    for uri in ["/protected/about/999", "/protected/contact/999"] {
+        let (status, body) = get(&router, uri, Some(&token)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+        assert!(body.contains("404 - Page Not Found"), "{}", uri);
+    }
+
+    let (status, body) = get(&router, "/campaigns/999", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body.contains("404 - Page Not Found"));
+
+    let (status, _) = get(&router, "/no-such-page", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn test_app_error_responses() {
+    let response = AppError::Invalid(Html("<form></form>".to_string())).into_response();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(response.extensions().get::<ErrorPage>().is_none());
+
+    let response = AppError::Rejected(StatusCode::CONFLICT, "Username already taken".to_string()).into_response();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert!(response.extensions().get::<ErrorPage>().is_none());
+
+    let response = AppError::from(DatabaseError::SchemaTooNew(99)).into_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    match response.extensions().get::<ErrorPage>() {
+        Some(ErrorPage::Internal { correlation_id }) => assert_eq!(correlation_id.len(), 16),
+        other => panic!("unexpected error page: {:?}", other),
+    }
+}
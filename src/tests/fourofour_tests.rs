@@ -4,7 +4,7 @@ use axum::{body::Body, http::Request};
 use tower::ServiceExt;
 #[tokio::test]
 async fn test_404_response() {
-    let router = Router::new();
+    let router = Router::new(super::test_pool());
     let app = router.create_router();
 
     let response = app
@@ -0,0 +1,116 @@
+use crate::i18n::{self, LOCALES};
+use crate::routes::{content_security_policy, Router, INLINE_CONFIRMATIONS};
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use tower::ServiceExt;
+
+fn message_keys(source: &str) -> BTreeSet<&str> {
+    source
+        .lines()
+        .filter(|line| !line.starts_with([' ', '#']))
+        .filter_map(|line| line.split_once(" ="))
+        .map(|(key, _)| key)
+        .collect()
+}
+
+async fn get(uri: &str, headers: &[(header::HeaderName, &str)]) -> (axum::http::HeaderMap, String) {
+    let app = Router::new(super::test_pool()).create_router();
+    let mut request = Request::builder().uri(uri);
+    for (name, value) in headers {
+        request = request.header(name, *value);
+    }
+    let response = app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    let headers = response.headers().clone();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (headers, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[test]
+fn test_catalogs_have_the_same_messages() {
+    let (_, _, default) = LOCALES.iter().find(|(code, _, _)| *code == i18n::DEFAULT_LOCALE).unwrap();
+    for (code, _, source) in LOCALES {
+        assert_eq!(message_keys(source), message_keys(default), "locales/{}.ftl", code);
+        assert_ne!(i18n::translate(code, "nav-home", &[]), "nav-home");
+    }
+    assert_eq!(i18n::translate("pl", "no-such-message", &[]), "no-such-message");
+}
+
+#[test]
+fn test_locale_is_negotiated() {
+    assert_eq!(i18n::from_accept_language("pl-PL,pl;q=0.9,en;q=0.8"), Some("pl"));
+    assert_eq!(i18n::from_accept_language("de;q=0.9, es;q=0.5, en;q=0.4"), Some("es"));
+    assert_eq!(i18n::from_accept_language("en;q=0.1, es-MX"), Some("es"));
+    assert_eq!(i18n::from_accept_language("es;q=0, de"), None);
+    assert_eq!(i18n::from_cookie_header("session=abc; locale=pl"), Some("pl"));
+    assert_eq!(i18n::from_cookie_header("locale=xx"), None);
+
+    assert_eq!(i18n::split_prefix("/pl"), Some(("pl", "")));
+    assert_eq!(i18n::split_prefix("/es/campaigns/3"), Some(("es", "/campaigns/3")));
+    assert_eq!(i18n::split_prefix("/plans"), None);
+    assert_eq!(i18n::split_prefix("/campaigns"), None);
+}
+
+#[tokio::test]
+async fn test_numbers_and_dates_follow_the_locale() {
+    let format = || (i18n::format_number(1234567.5), i18n::format_date("2024-03-09"), i18n::format_datetime("2024-03-09 14:05:00"));
+    assert_eq!(
+        i18n::scope("en", "/".to_string(), async { format() }).await,
+        ("1,234,567.5".to_string(), "Mar 9, 2024".to_string(), "Mar 9, 2024 14:05".to_string())
+    );
+    assert_eq!(
+        i18n::scope("es", "/".to_string(), async { format() }).await,
+        ("1.234.567,5".to_string(), "09/03/2024".to_string(), "09/03/2024 14:05".to_string())
+    );
+    assert_eq!(
+        i18n::scope("pl", "/".to_string(), async { format() }).await,
+        ("1\u{a0}234\u{a0}567,5".to_string(), "09.03.2024".to_string(), "09.03.2024 14:05".to_string())
+    );
+    assert_eq!(i18n::format_number(-12.0), "-12");
+    assert_eq!(i18n::format_date("soon"), "soon");
+    let precise = i18n::scope("pl", "/".to_string(), async { i18n::format_datetime("2024-03-09 14:05:00.123456") }).await;
+    assert_eq!(precise, "09.03.2024 14:05");
+}
+
+#[tokio::test]
+async fn test_url_prefix_selects_and_remembers_the_locale() {
+    for uri in ["/pl", "/pl/"] {
+        let (headers, body) = get(uri, &[]).await;
+        assert!(body.contains("<html lang=\"pl\">"), "{}", uri);
+        assert!(body.contains(&i18n::translate("pl", "footer-powered-by", &[])));
+        assert_eq!(headers[header::CONTENT_LANGUAGE], "pl");
+        assert!(headers[header::SET_COOKIE].to_str().unwrap().starts_with("locale=pl;"));
+    }
+
+    let (_, body) = get("/es/about", &[]).await;
+    assert!(body.contains(&i18n::translate("es", "about-heading", &[])));
+    assert!(body.contains("<a href=\"/pl/about\" hreflang=\"pl\""));
+}
+
+#[tokio::test]
+async fn test_cookie_wins_over_accept_language() {
+    let (headers, body) = get("/", &[(header::ACCEPT_LANGUAGE, "es-ES,es;q=0.9")]).await;
+    assert!(body.contains("<html lang=\"es\">"));
+    assert!(headers[header::VARY].to_str().unwrap().contains("Accept-Language"));
+    assert!(!headers.contains_key(header::SET_COOKIE));
+
+    let (_, body) = get("/", &[(header::ACCEPT_LANGUAGE, "es"), (header::COOKIE, "locale=pl")]).await;
+    assert!(body.contains("<html lang=\"pl\">"));
+
+    let (_, body) = get("/", &[(header::ACCEPT_LANGUAGE, "de")]).await;
+    assert!(body.contains("<html lang=\"en\">"));
+}
+
+#[test]
+fn test_policy_allows_confirmations_in_every_locale() {
+    let policy = content_security_policy();
+    for (code, _, _) in LOCALES {
+        for key in INLINE_CONFIRMATIONS {
+            let handler = i18n::confirm_handler(code, key);
+            let hash = format!("'sha256-{}'", BASE64.encode(Sha256::digest(handler.as_bytes())));
+            assert!(policy.contains(&hash), "{} in {}", key, code);
+        }
+    }
+}
@@ -9,7 +9,7 @@ use tower_sessions::{MemoryStore, Session};
 #[tokio::test]
 async fn test_logout_with_active_session() {
     let session_store = MemoryStore::default();
-    let router = Router::new();
+    let router = Router::new(super::test_pool());
     let app = router.create_router();
 
     let session = Session::new(None, Arc::new(session_store.clone()), None);
@@ -0,0 +1,39 @@
+use crate::markdown::render;
+
+#[test]
+fn test_renders_commonmark_extensions() {
+    let html = render(
+        "# Plan\n\n| Item | Cost |\n|------|------|\n| Disk | 10 |\n\n- [x] Order\n- [ ] Ship\n\nThanks[^1]\n\n[^1]: To everyone.\n",
+    );
+    assert!(html.contains("<h1>Plan</h1>"));
+    assert!(html.contains("<table>"));
+    assert!(html.contains("<td>Disk</td>"));
+    let task = html.lines().find(|line| line.contains("<input") && line.contains("checked")).unwrap();
+    assert!(task.contains(r#"type="checkbox""#) && task.contains(r#"disabled="""#));
+    assert!(html.contains(r##"<sup class="footnote-reference"><a href="#user-content-1""##));
+    assert!(html.contains(r#"<div class="footnote-definition" id="user-content-1">"#));
+}
+
+#[test]
+fn test_raw_html_is_sanitized() {
+    let html = render("Hello <b>there</b>\n\n<script>alert(1)</script>\n\n[link](javascript:alert(2))");
+    assert!(html.contains("<b>there</b>"));
+    assert!(!html.contains("alert(1)"));
+    assert!(!html.contains("javascript:"));
+}
+
+#[test]
+fn test_cached_render_matches_fresh_render() {
+    let source = "*cached* text";
+    let first = render(source);
+    assert_eq!(render(source), first);
+    assert_eq!(first, "<p><em>cached</em> text</p>\n");
+}
+
+#[test]
+fn test_render_stays_correct_past_the_cache_size() {
+    for i in 0..1100 {
+        assert_eq!(render(&format!("entry {}", i)), format!("<p>entry {}</p>\n", i));
+    }
+    assert_eq!(render("entry 0"), "<p>entry 0</p>\n");
+}
@@ -0,0 +1,78 @@
+use crate::cli::Command;
+use crate::db::DatabaseError;
+use crate::migrations::{applied_versions, migrate, MIGRATIONS};
+use rusqlite::Connection;
+
+#[test]
+fn test_migrate_applies_all_once() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    assert!(applied_versions(&conn).unwrap().is_empty());
+
+    let applied = migrate(&mut conn).unwrap();
+    assert_eq!(applied.len(), MIGRATIONS.len());
+    assert_eq!(applied_versions(&conn).unwrap().len(), MIGRATIONS.len());
+
+    assert!(migrate(&mut conn).unwrap().is_empty());
+}
+
+#[test]
+fn test_migrate_adopts_legacy_database() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, username TEXT NOT NULL, password TEXT NOT NULL);
+         INSERT INTO users (username, password) VALUES ('admin', 'admin');",
+    )
+    .unwrap();
+
+    migrate(&mut conn).unwrap();
+
+    let role: String = conn
+        .query_row("SELECT role FROM users WHERE username = 'admin'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(role, "owner");
+}
+
+#[test]
+fn test_migrate_refuses_newer_schema() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    migrate(&mut conn).unwrap();
+    conn.execute("INSERT INTO schema_version (version, name) VALUES (9999, 'future')", [])
+        .unwrap();
+
+    assert!(matches!(migrate(&mut conn), Err(DatabaseError::SchemaTooNew(9999))));
+}
+
+#[test]
+fn test_parse_migrate_commands() {
+    let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+    assert!(matches!(Command::parse(&args(&[])), Ok(Command::Serve)));
+    assert!(matches!(Command::parse(&args(&["migrate", "status"])), Ok(Command::MigrateStatus)));
+    assert!(matches!(Command::parse(&args(&["migrate", "up"])), Ok(Command::MigrateUp)));
+    assert!(Command::parse(&args(&["migrate", "down"])).is_err());
+}
+
+#[test]
+fn test_single_active_page_keeps_newest_and_enforces_uniqueness() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    migrate(&mut conn).unwrap();
+    conn.execute_batch(
+        "DROP INDEX idx_aboutme_single_active;
+         DROP INDEX idx_contact_single_active;
+         DELETE FROM schema_version WHERE version = 4;
+         INSERT INTO aboutme (description, active) VALUES ('older', 1), ('newer', 1);",
+    )
+    .unwrap();
+
+    assert_eq!(migrate(&mut conn).unwrap(), vec![4]);
+    let active: Vec<String> = conn
+        .prepare("SELECT description FROM aboutme WHERE active = 1")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(active, vec!["newer".to_string()]);
+
+    assert!(conn.execute("UPDATE aboutme SET active = 1 WHERE description = 'older'", []).is_err());
+}
@@ -1,6 +1,32 @@
+pub mod account_tests;
+pub mod assets_tests;
 pub mod about_tests;
+pub mod address_change_tests;
+pub mod audit_tests;
 #[cfg(test)]
 pub mod auth_tests;
+pub mod backup_tests;
+pub mod color_scheme_tests;
 pub mod csrf_tests;
+pub mod errors_tests;
 pub mod fourofour_tests;
+pub mod i18n_tests;
 pub mod logout_tests;
+pub mod markdown_tests;
+pub mod migrations_tests;
+pub mod repositories_tests;
+pub mod roles_tests;
+pub mod sanitize_tests;
+pub mod sessions_tests;
+pub mod templates_tests;
+pub mod themes_tests;
+pub mod totp_tests;
+pub mod transfer_tests;
+pub mod translations_tests;
+
+/// A migrated in-memory database for router tests.
+pub fn test_pool() -> crate::db::Pool {
+    let pool = crate::db::create_pool(":memory:").unwrap();
+    crate::db::setup_database(&pool).unwrap();
+    pool
+}
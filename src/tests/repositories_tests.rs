@@ -0,0 +1,245 @@
+use crate::controllers::about::about_content;
+use crate::controllers::campaigns::campaign_details_content;
+use crate::controllers::contact::contact_content;
+use crate::controllers::home::home_content;
+use crate::controllers::revisions::diff_content;
+use crate::diff::{diff_lines, DiffLine};
+use crate::models::{Author, CampaignFilter, NewCampaign};
+use crate::repositories::memory::InMemoryRepository;
+use crate::repositories::{AboutRepository, CampaignRepository, ContactRepository, SqliteRepository};
+
+fn new_campaign(title: &str) -> NewCampaign {
+    NewCampaign {
+        title: title.to_string(),
+        description: "Fund the node".to_string(),
+        creator_id: 1,
+        start_date: "2024-01-01".to_string(),
+        end_date: "2024-12-31".to_string(),
+        goal_amount: 100.0,
+        status: "active".to_string(),
+        xmr_address: "4AdUndXHHZ6cfufTMvppY6JwXNouMBzSkbLYfpAV5Usx".to_string(),
+    }
+}
+
+#[test]
+fn test_home_labels_campaign_dates() {
+    let repository = InMemoryRepository::default();
+    CampaignRepository::insert(&repository, &new_campaign("Node")).unwrap();
+
+    let content = home_content(&repository);
+    assert!(content.contains("Node"));
+    assert!(content.contains("<p>Start Date: Jan 1, 2024</p>"));
+    assert!(content.contains("<p>End Date: Dec 31, 2024</p>"));
+    assert!(!content.contains("Created:"));
+}
+
+#[test]
+fn test_campaign_details_show_goals_and_updates() {
+    let repository = InMemoryRepository::default();
+    let id = CampaignRepository::insert(&repository, &new_campaign("Node")).unwrap();
+    repository.add_goal(id, "Buy the hardware", 50.0).unwrap();
+    repository.add_update(id, "Hardware ordered").unwrap();
+    repository.set_current_amount(id, 12.5).unwrap();
+
+    let content = campaign_details_content(&repository, id, "").unwrap();
+    assert!(content.contains("50 XMR: Buy the hardware"));
+    assert!(content.contains("Hardware ordered"));
+    assert!(content.contains(&format!("{:x}", md5::compute("Hardware ordered"))));
+    assert!(content.contains("Current Amount: 12.5 XMR"));
+
+    assert!(campaign_details_content(&repository, id + 100, "").is_none());
+}
+
+fn author() -> Author {
+    Author { id: Some(1), name: "admin".to_string() }
+}
+
+fn latest_about_revision(repository: &InMemoryRepository, id: i64) -> i64 {
+    AboutRepository::revisions(repository, id).unwrap()[0].id
+}
+
+#[test]
+fn test_publishing_about_revision_deactivates_others() {
+    let repository = InMemoryRepository::default();
+    assert!(about_content(&repository).contains("Please configure about me"));
+
+    let first = AboutRepository::insert(&repository, "First", &author()).unwrap();
+    let second = AboutRepository::insert(&repository, "Second", &author()).unwrap();
+    AboutRepository::publish(&repository, first, latest_about_revision(&repository, first)).unwrap();
+    AboutRepository::publish(&repository, second, latest_about_revision(&repository, second)).unwrap();
+
+    let active = AboutRepository::active(&repository).unwrap().unwrap();
+    assert_eq!(active.id, second);
+    assert!(!AboutRepository::get(&repository, first).unwrap().unwrap().active);
+    assert!(about_content(&repository).contains("Second"));
+}
+
+#[test]
+fn test_saved_revision_is_not_live_until_published() {
+    let repository = InMemoryRepository::default();
+    let id = AboutRepository::insert(&repository, "First draft", &author()).unwrap();
+    let first = latest_about_revision(&repository, id);
+    AboutRepository::publish(&repository, id, first).unwrap();
+
+    let second = AboutRepository::save(&repository, id, "Second draft", &author()).unwrap();
+    assert!(about_content(&repository).contains("First draft"));
+    assert_eq!(AboutRepository::revisions(&repository, id).unwrap().len(), 2);
+
+    AboutRepository::publish(&repository, id, second).unwrap();
+    assert!(about_content(&repository).contains("Second draft"));
+    assert_eq!(AboutRepository::active(&repository).unwrap().unwrap().published_revision_id, Some(second));
+
+    // Publishing a revision of another entry is refused.
+    let other = AboutRepository::insert(&repository, "Other", &author()).unwrap();
+    assert!(AboutRepository::publish(&repository, other, first).is_err());
+}
+
+#[test]
+fn test_deleted_entries_go_to_trash() {
+    let repository = InMemoryRepository::default();
+    let id = ContactRepository::insert(&repository, "Write to us", "team@example.org", &author()).unwrap();
+    let revision = ContactRepository::revisions(&repository, id).unwrap()[0].id;
+    ContactRepository::publish(&repository, id, revision).unwrap();
+
+    ContactRepository::delete(&repository, id).unwrap();
+    assert!(ContactRepository::list(&repository).unwrap().is_empty());
+    assert!(ContactRepository::active(&repository).unwrap().is_none());
+    assert_eq!(ContactRepository::trash(&repository).unwrap()[0].id, id);
+
+    ContactRepository::undelete(&repository, id).unwrap();
+    assert_eq!(ContactRepository::list(&repository).unwrap().len(), 1);
+    assert!(ContactRepository::trash(&repository).unwrap().is_empty());
+
+    ContactRepository::delete(&repository, id).unwrap();
+    ContactRepository::purge(&repository, id).unwrap();
+    assert!(ContactRepository::get(&repository, id).unwrap().is_none());
+    assert!(ContactRepository::revision(&repository, revision).unwrap().is_none());
+}
+
+#[test]
+fn test_contact_page_uses_active_entry() {
+    let repository = InMemoryRepository::default();
+    assert!(contact_content(&repository).contains("admin@example.com"));
+
+    let id = ContactRepository::insert(&repository, "Write to us", "team@example.org", &author()).unwrap();
+    let revision = ContactRepository::revisions(&repository, id).unwrap()[0].id;
+    ContactRepository::publish(&repository, id, revision).unwrap();
+    let content = contact_content(&repository);
+    assert!(content.contains("Write to us"));
+    assert!(content.contains("team@example.org"));
+}
+
+#[test]
+fn test_sqlite_revisions_publish_and_trash() {
+    let pool = super::test_pool();
+    let conn = pool.get().unwrap();
+    let repository = SqliteRepository::new(&conn);
+
+    let id = AboutRepository::insert(&repository, "First", &author()).unwrap();
+    let revision = AboutRepository::save(&repository, id, "Second", &author()).unwrap();
+    let revisions = AboutRepository::revisions(&repository, id).unwrap();
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(revisions[0].id, revision);
+    assert_eq!(revisions[0].author_name, "admin");
+
+    AboutRepository::publish(&repository, id, revision).unwrap();
+    assert_eq!(AboutRepository::active(&repository).unwrap().unwrap().description, "Second");
+
+    AboutRepository::delete(&repository, id).unwrap();
+    assert!(AboutRepository::active(&repository).unwrap().is_none());
+    assert_eq!(AboutRepository::trash(&repository).unwrap().len(), 1);
+    AboutRepository::purge(&repository, id).unwrap();
+    assert!(AboutRepository::revisions(&repository, id).unwrap().is_empty());
+}
+
+#[test]
+fn test_trashed_entries_cannot_be_published() {
+    let pool = super::test_pool();
+    let conn = pool.get().unwrap();
+    let sqlite = SqliteRepository::new(&conn);
+    let live = AboutRepository::insert(&sqlite, "Live", &author()).unwrap();
+    AboutRepository::publish(&sqlite, live, AboutRepository::revisions(&sqlite, live).unwrap()[0].id).unwrap();
+    let trashed = AboutRepository::insert(&sqlite, "Trashed", &author()).unwrap();
+    AboutRepository::delete(&sqlite, trashed).unwrap();
+    let revision = AboutRepository::revisions(&sqlite, trashed).unwrap()[0].id;
+    assert!(AboutRepository::publish(&sqlite, trashed, revision).is_err());
+    assert_eq!(AboutRepository::active(&sqlite).unwrap().unwrap().id, live);
+
+    let memory = InMemoryRepository::default();
+    let live = ContactRepository::insert(&memory, "Live", "team@example.org", &author()).unwrap();
+    ContactRepository::publish(&memory, live, ContactRepository::revisions(&memory, live).unwrap()[0].id).unwrap();
+    let trashed = ContactRepository::insert(&memory, "Trashed", "team@example.org", &author()).unwrap();
+    ContactRepository::delete(&memory, trashed).unwrap();
+    let revision = ContactRepository::revisions(&memory, trashed).unwrap()[0].id;
+    assert!(ContactRepository::publish(&memory, trashed, revision).is_err());
+    assert_eq!(ContactRepository::active(&memory).unwrap().unwrap().id, live);
+}
+
+#[test]
+fn test_diff_lines_marks_changes() {
+    let diff = diff_lines("one\ntwo\nthree", "one\n2\nthree\nfour");
+    assert_eq!(
+        diff,
+        vec![
+            DiffLine::Same("one"),
+            DiffLine::Removed("two"),
+            DiffLine::Added("2"),
+            DiffLine::Same("three"),
+            DiffLine::Added("four"),
+        ]
+    );
+    assert!(diff_content("/protected/about", 1, 1, 2, "<b>", "<i>").contains("- &lt;b&gt;"));
+}
+
+#[test]
+fn test_home_lists_only_active_campaigns() {
+    let repository = InMemoryRepository::default();
+    CampaignRepository::insert(&repository, &new_campaign("Node")).unwrap();
+    CampaignRepository::insert(&repository, &NewCampaign { status: "completed".to_string(), ..new_campaign("Relay") }).unwrap();
+
+    let content = home_content(&repository);
+    assert!(content.contains("Node"));
+    assert!(!content.contains("Relay"));
+}
+
+#[test]
+fn test_sqlite_campaigns_filter_and_page() {
+    let pool = super::test_pool();
+    let conn = pool.get().unwrap();
+    let repository = SqliteRepository::new(&conn);
+    for (title, start_date, end_date, status) in [
+        ("Spring", "2024-03-01", "2024-05-31", "active"),
+        ("Summer", "2024-06-01", "2024-08-31", "active"),
+        ("Autumn", "2024-09-01", "2024-11-30", "completed"),
+    ] {
+        CampaignRepository::insert(
+            &repository,
+            &NewCampaign {
+                start_date: start_date.to_string(),
+                end_date: end_date.to_string(),
+                status: status.to_string(),
+                ..new_campaign(title)
+            },
+        )
+        .unwrap();
+    }
+
+    let titles = |filter: &CampaignFilter, limit, offset| -> Vec<String> {
+        CampaignRepository::list(&repository, filter, limit, offset)
+            .unwrap()
+            .into_iter()
+            .map(|campaign| campaign.title)
+            .collect()
+    };
+    assert_eq!(titles(&CampaignFilter::active(), 10, 0), ["Spring", "Summer"]);
+    assert_eq!(titles(&CampaignFilter::default(), 2, 2), ["Autumn"]);
+
+    let range = CampaignFilter {
+        from: Some("2024-05-15".to_string()),
+        to: Some("2024-06-15".to_string()),
+        status: Some(" ".to_string()),
+    };
+    assert_eq!(titles(&range, 10, 0), ["Spring", "Summer"]);
+    assert_eq!(CampaignRepository::count(&repository, &range).unwrap(), 2);
+    assert!(CampaignRepository::get(&repository, 99).unwrap().is_none());
+}
@@ -0,0 +1,54 @@
+use crate::auth::{CurrentUser, Role};
+use crate::routes::Router;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+
+fn user(id: i64, role: Role) -> CurrentUser {
+    CurrentUser {
+        id,
+        username: format!("user{}", id),
+        role,
+    }
+}
+
+#[test]
+fn test_role_permissions() {
+    let owner = user(1, Role::Owner);
+    let manager = user(2, Role::Manager);
+    let editor = user(3, Role::Editor);
+
+    assert!(owner.can_manage_users());
+    assert!(!manager.can_manage_users());
+    assert!(!editor.can_manage_users());
+
+    assert!(owner.can_manage_campaign(2));
+    assert!(manager.can_manage_campaign(2));
+    assert!(!manager.can_manage_campaign(1));
+    assert!(!editor.can_manage_campaign(3));
+
+    assert!(editor.can_edit_campaign_content(1));
+    assert!(!manager.can_edit_campaign_content(1));
+    assert!(!editor.can_create_campaigns());
+}
+
+#[tokio::test]
+async fn test_user_management_requires_auth() {
+    let router = Router::new(super::test_pool());
+    let app = router.create_router();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/protected/users")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers().get("location").unwrap(), "/login");
+}
@@ -0,0 +1,78 @@
+use crate::controllers::about::about_content;
+use crate::controllers::campaigns::campaign_details_content;
+use crate::models::{Author, NewCampaign};
+use crate::repositories::memory::InMemoryRepository;
+use crate::repositories::{AboutRepository, CampaignRepository};
+use crate::sanitize::rich_text;
+
+#[test]
+fn test_rich_text_keeps_allowed_markup() {
+    let html = r#"<p>Hello <strong>world</strong>, see <a href="https://example.com" title="Example">this</a>.</p><ul><li>One</li></ul>"#;
+    let cleaned = rich_text(html);
+    assert!(cleaned.contains("<strong>world</strong>"));
+    assert!(cleaned.contains(r#"href="https://example.com""#));
+    assert!(cleaned.contains(r#"rel="noopener noreferrer nofollow""#));
+    assert!(cleaned.contains("<ul><li>One</li></ul>"));
+}
+
+#[test]
+fn test_rich_text_drops_scripts_handlers_and_unsafe_urls() {
+    let cleaned = rich_text(
+        r#"<script>alert(1)</script><img src="x.png" onerror="alert(2)"><a href="javascript:alert(3)">link</a><span style="color:red">text</span><iframe src="https://example.com"></iframe>"#,
+    );
+    assert!(!cleaned.contains("alert"));
+    assert!(!cleaned.contains("style"));
+    assert!(!cleaned.contains("<span"));
+    assert!(!cleaned.contains("iframe"));
+    assert!(cleaned.contains(r#"<img src="x.png">"#));
+    assert!(cleaned.contains("<a"));
+    assert!(cleaned.contains("text"));
+}
+
+#[test]
+fn test_rich_text_prefixes_ids_and_their_links() {
+    let cleaned = rich_text(r##"<div id="login-form">Note</div><a href="#login-form">up</a>"##);
+    assert!(cleaned.contains(r#"<div id="user-content-login-form">"#));
+    assert!(cleaned.contains(r##"href="#user-content-login-form""##));
+}
+
+#[test]
+fn test_campaign_fields_are_escaped_and_markdown_sanitized() {
+    let repository = InMemoryRepository::default();
+    let id = CampaignRepository::insert(
+        &repository,
+        &NewCampaign {
+            title: r#"Say "hi" <script>alert(1)</script>"#.to_string(),
+            description: "Some **bold** text <script>alert(3)</script>".to_string(),
+            creator_id: 1,
+            start_date: "2024-01-01".to_string(),
+            end_date: "2024-12-31".to_string(),
+            goal_amount: 100.0,
+            status: "active".to_string(),
+            xmr_address: "4address".to_string(),
+        },
+    )
+    .unwrap();
+    repository.add_update(id, r#"<em>Shipped</em><script>alert(2)</script>"#).unwrap();
+
+    let content = campaign_details_content(&repository, id, "").unwrap();
+    assert!(content.contains("&lt;script&gt;"));
+    assert!(!content.contains("<script>"));
+    assert!(content.contains("<strong>bold</strong>"));
+    assert!(content.contains("<em>Shipped</em>"));
+    assert!(!content.contains("alert(2)"));
+    assert!(!content.contains("alert(3)"));
+}
+
+#[test]
+fn test_about_description_is_sanitized() {
+    let repository = InMemoryRepository::default();
+    let author = Author { id: Some(1), name: "admin".to_string() };
+    let id = AboutRepository::insert(&repository, r#"<h2>Us</h2><img src=x onerror="alert(1)">"#, &author).unwrap();
+    let revision = AboutRepository::revisions(&repository, id).unwrap()[0].id;
+    AboutRepository::publish(&repository, id, revision).unwrap();
+
+    let content = about_content(&repository);
+    assert!(content.contains("<h2>Us</h2>"));
+    assert!(!content.contains("onerror"));
+}
@@ -0,0 +1,73 @@
+use crate::audit::ClientIp;
+use crate::db;
+use crate::routes::Router;
+use crate::auth::hash_token;
+use crate::sessions::{self, UserAgent};
+use axum::{
+    body::Body,
+    extract::FromRequestParts,
+    http::{Request, StatusCode},
+};
+use std::sync::Arc;
+use tower::ServiceExt;
+use tower_sessions::{MemoryStore, Session};
+
+#[tokio::test]
+async fn test_user_agent_is_truncated() {
+    let request = Request::builder()
+        .uri("/")
+        .header("user-agent", "a".repeat(1000))
+        .body(())
+        .unwrap();
+    let (mut parts, _) = request.into_parts();
+
+    let UserAgent(user_agent) = UserAgent::from_request_parts(&mut parts, &()).await.unwrap();
+    assert_eq!(user_agent.len(), 256);
+}
+
+#[test]
+fn test_session_tokens_are_stored_hashed() {
+    let hash = hash_token("secret-token");
+    assert_eq!(hash.len(), 64);
+    assert_ne!(hash, "secret-token");
+    assert_eq!(hash, hash_token("secret-token"));
+}
+
+#[tokio::test]
+async fn test_login_rotates_the_session_id() {
+    let pool = super::test_pool();
+    let user_id = db::insert_user(&pool.get().unwrap(), "owner", "unused", "owner").unwrap();
+    let session = Session::new(None, Arc::new(MemoryStore::default()), None);
+    session.insert("locale", "pl").await.unwrap();
+    session.save().await.unwrap();
+    let planted = session.id();
+
+    let (ip, user_agent) = (ClientIp("127.0.0.1".to_string()), UserAgent("test".to_string()));
+    sessions::start(&pool, &session, user_id, &ip, &user_agent).await.unwrap();
+    session.save().await.unwrap();
+
+    assert!(session.id().is_some());
+    assert_ne!(session.id(), planted);
+    assert_eq!(session.get::<String>("locale").await.unwrap().as_deref(), Some("pl"));
+    assert!(sessions::is_active(&pool, &session, user_id).await);
+}
+
+#[tokio::test]
+async fn test_revoke_all_sessions_requires_auth() {
+    let router = Router::new(super::test_pool());
+    let app = router.create_router();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/protected/sessions/revoke-all")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers().get("location").unwrap(), "/login");
+}
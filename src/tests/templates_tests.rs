@@ -0,0 +1,65 @@
+use crate::views::blocks::header::Header;
+use crate::views::templates::{override_environment, render_with, SOURCES};
+use minijinja::Environment;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn template_files(dir: &Path, prefix: &str, found: &mut Vec<String>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let name = format!("{}{}", prefix, path.file_name().unwrap().to_str().unwrap());
+        if path.is_dir() {
+            template_files(&path, &format!("{}/", name), found);
+        } else {
+            found.push(name);
+        }
+    }
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mrs-funding-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("blocks")).unwrap();
+    dir
+}
+
+#[test]
+fn test_every_template_is_embedded() {
+    let mut found = Vec::new();
+    template_files(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/templates")), "", &mut found);
+    for name in found {
+        assert!(SOURCES.iter().any(|(source, _)| *source == name), "{} is missing from SOURCES", name);
+    }
+}
+
+#[test]
+fn test_built_in_templates_parse_at_runtime() {
+    let mut environment = Environment::new();
+    for (name, source) in SOURCES {
+        environment.add_template(name, source).unwrap_or_else(|e| panic!("{} does not parse: {}", name, e));
+    }
+    let header = render_with(Some(&environment), &Header::new("Site".to_string(), true));
+    assert!(header.contains("<h1>Site</h1>"));
+    assert!(header.contains("/logout"));
+}
+
+#[test]
+fn test_override_replaces_one_template_and_falls_back_for_the_rest() {
+    let dir = scratch_dir("template-overrides");
+    fs::write(
+        dir.join("blocks/header.html"),
+        r#"<header class="custom">{{ title }}</header>{% include "blocks/footer.html" %}"#,
+    )
+    .unwrap();
+    let environment = override_environment(dir.clone());
+
+    let header = render_with(Some(&environment), &Header::new("<Site>".to_string(), false));
+    assert!(header.starts_with(r#"<header class="custom">&lt;Site&gt;</header>"#));
+    assert!(header.contains("Proudly using MRS Funding Software."));
+
+    fs::write(dir.join("blocks/header.html"), "{% if %}").unwrap();
+    let header = render_with(Some(&override_environment(dir.clone())), &Header::new("Site".to_string(), false));
+    assert!(header.contains("/login"), "a broken override falls back to the built-in template");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
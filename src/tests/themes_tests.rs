@@ -0,0 +1,276 @@
+use crate::auth;
+use crate::controllers::themes::check_theme_name;
+use crate::db;
+use crate::i18n;
+use crate::routes::Router;
+use crate::views::blocks::footer::Footer;
+use crate::views::templates;
+use crate::views::themes::package;
+use crate::views::themes::theme_manager::{self, ThemeManager, ThemeSettings, ACTIVE_THEME_SETTING, BUILT_IN, CUSTOM_CSS_SETTING};
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use std::fs;
+use tower::ServiceExt;
+
+#[test]
+fn test_built_in_themes_load_without_a_directory() {
+    let manager = ThemeManager::load(None, "monero");
+    let monero = BUILT_IN.iter().find(|(name, _)| *name == "monero").unwrap().1;
+    assert_eq!(manager.get_active_theme_css(), monero);
+}
+
+#[test]
+fn test_unknown_theme_falls_back_to_default() {
+    let manager = ThemeManager::load(None, "does-not-exist");
+    let default = BUILT_IN.iter().find(|(name, _)| *name == "default").unwrap().1;
+    assert_eq!(manager.get_active_theme_css(), default);
+}
+
+#[test]
+fn test_theme_directory_overrides_and_adds_themes() {
+    let dir = std::env::temp_dir().join(format!("mrs-funding-themes-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("default.css"), "body { color: red; }").unwrap();
+    fs::write(dir.join("custom.css"), "body { color: blue; }").unwrap();
+    fs::write(dir.join("notes.txt"), "not a theme").unwrap();
+
+    assert_eq!(ThemeManager::load(Some(&dir), "default").get_active_theme_css(), "body { color: red; }");
+    assert_eq!(ThemeManager::load(Some(&dir), "custom").get_active_theme_css(), "body { color: blue; }");
+    assert_eq!(ThemeManager::load(Some(&dir), "notes").get_active_theme_css(), "body { color: red; }");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_stylesheet_path_follows_the_content() {
+    let default = ThemeManager::load(None, "default");
+    let monero = ThemeManager::load(None, "monero");
+    assert_ne!(default.stylesheet_path(), monero.stylesheet_path());
+    assert_eq!(default.stylesheet_path(), ThemeManager::load(None, "default").stylesheet_path());
+
+    let file = default.stylesheet_path().trim_start_matches("/assets/").to_string();
+    assert_eq!(default.stylesheet(&file), Some(default.get_active_theme_css()));
+    // Inactive themes are served too, for previews.
+    assert_eq!(monero.stylesheet(&file), Some(default.get_active_theme_css()));
+    assert_eq!(default.stylesheet("theme-.css"), None);
+    assert_eq!(default.stylesheet("theme-0000000000000000.css"), None);
+}
+
+#[test]
+fn test_settings_add_themes_and_custom_css() {
+    let settings = ThemeSettings {
+        active_theme: Some("paper".to_string()),
+        custom_css: "a { color: green; }".to_string(),
+        uploaded: vec![("paper".to_string(), "body { background: white; }".to_string())],
+    };
+    let plain = ThemeManager::load(None, "default");
+    let manager = ThemeManager::load(None, "default").with_settings(&settings);
+
+    assert_eq!(manager.active_theme(), "paper");
+    assert_eq!(manager.get_active_theme_css(), "body { background: white; }\na { color: green; }");
+    assert_eq!(manager.theme_names(), vec!["default", "minimal", "monero", "paper"]);
+
+    let default_path = manager.theme_stylesheet_path("default").unwrap();
+    assert_ne!(default_path, plain.stylesheet_path());
+    let file = default_path.trim_start_matches("/assets/");
+    assert!(manager.stylesheet(file).unwrap().ends_with("a { color: green; }"));
+}
+
+#[test]
+fn test_saved_theme_settings_are_read_back() {
+    let pool = super::test_pool();
+    let conn = pool.get().unwrap();
+    assert_eq!(ThemeSettings::from_db(&conn).unwrap(), ThemeSettings::default());
+
+    db::save_uploaded_theme(&conn, "paper", "body { color: black; }").unwrap();
+    db::save_uploaded_theme(&conn, "paper", "body { color: gray; }").unwrap();
+    db::set_setting(&conn, ACTIVE_THEME_SETTING, "paper").unwrap();
+    db::set_setting(&conn, CUSTOM_CSS_SETTING, "p { margin: 0; }").unwrap();
+
+    let settings = ThemeSettings::from_db(&conn).unwrap();
+    assert_eq!(settings.uploaded, vec![("paper".to_string(), "body { color: gray; }".to_string())]);
+    let manager = ThemeManager::load(None, "default").with_settings(&settings);
+    assert_eq!(manager.get_active_theme_css(), "body { color: gray; }\np { margin: 0; }");
+
+    assert!(db::delete_uploaded_theme(&conn, "paper").unwrap());
+    let manager = ThemeManager::load(None, "minimal").with_settings(&ThemeSettings::from_db(&conn).unwrap());
+    assert_eq!(manager.active_theme(), "default");
+}
+
+#[test]
+fn test_theme_names_are_checked() {
+    assert!(check_theme_name("paper-2_dark").is_ok());
+    assert!(check_theme_name("").is_err());
+    assert!(check_theme_name("Paper").is_err());
+    assert!(check_theme_name("../etc").is_err());
+    assert!(check_theme_name(&"a".repeat(41)).is_err());
+    assert!(check_theme_name("monero").is_err());
+}
+
+#[tokio::test]
+async fn test_theme_package_errors_are_translated() {
+    let message = |locale| i18n::scope(locale, "/".to_string(), async { check_theme_name("Paper").unwrap_err() });
+    assert_eq!(message("en").await, "Theme names may only contain lowercase letters, digits, '-' and '_'");
+    assert_eq!(message("pl").await, "Nazwa motywu może zawierać tylko małe litery, cyfry, '-' i '_'");
+
+    let dir = std::env::temp_dir().join(format!("theme-errors-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("theme.json"), r#"{"name": "paper", "version": "1", "color_scheme": "sepia"}"#).unwrap();
+    let error = i18n::scope("es", "/".to_string(), async { package::load(&dir, &[]).err().unwrap() }).await;
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(error, "color_scheme debe ser 'light' o 'dark', no 'sepia'");
+}
+
+#[tokio::test]
+async fn test_owner_previews_and_adds_themes() {
+    let pool = super::test_pool();
+    let (owner_token, editor_token) = (auth::generate_token(), auth::generate_token());
+    {
+        let conn = pool.get().unwrap();
+        let owner_id = db::insert_user(&conn, "owner", "unused", "owner").unwrap();
+        db::insert_api_token(&conn, owner_id, "tests", &auth::hash_token(&owner_token)).unwrap();
+        let editor_id = db::insert_user(&conn, "editor", "unused", "editor").unwrap();
+        db::insert_api_token(&conn, editor_id, "tests", &auth::hash_token(&editor_token)).unwrap();
+    }
+    let app = Router::new(pool.clone()).create_router();
+    let send = |request: Request<Body>| app.clone().oneshot(request);
+    let get = |uri: &str, token: &str| {
+        Request::builder()
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let post = |uri: &str, form: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", owner_token))
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(form.to_string()))
+            .unwrap()
+    };
+
+    let response = send(get("/protected/themes", &editor_token)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = send(get("/protected/themes?preview=monero", &owner_token)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    let monero = theme_manager::current().theme_stylesheet_path("monero").unwrap();
+    assert!(body.contains(&format!("href=\"{}\"", monero)));
+    assert!(body.contains("Use This Theme"));
+
+    let response = send(post("/protected/themes/upload", "name=Bad+Name&css=body%7B%7D")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send(post("/protected/themes/upload", "name=paper&css=body%7Bcolor%3Ablack%7D")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert!(theme_manager::current().has_theme("paper"));
+    let themes = db::get_uploaded_themes(&pool.get().unwrap()).unwrap();
+    assert_eq!(themes, vec![("paper".to_string(), "body{color:black}".to_string())]);
+
+    let response = send(post("/protected/themes/paper/delete", "")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert!(!theme_manager::current().has_theme("paper"));
+}
+
+fn package_dir(test: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("mrs-funding-packages-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_package(dir: &std::path::Path, manifest: &str, files: &[(&str, &str)]) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join("theme.json"), manifest).unwrap();
+    for (path, content) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+}
+
+#[test]
+fn test_theme_package_loads_variables_fonts_assets_and_templates() {
+    let dir = package_dir("valid");
+    write_package(
+        &dir.join("aurora"),
+        r##"{
+            "name": "aurora",
+            "version": "1.2.0",
+            "author": "Jane",
+            "variables": { "primary": "#123456", "accent": "rgb(1, 2, 3)" },
+            "fonts": [{ "family": "Inter", "file": "fonts/inter.woff2", "weight": "400" }],
+            "assets": ["images/bg.png"],
+            "templates": "templates"
+        }"##,
+        &[
+            ("theme.css", "body { background: url('images/bg.png'); color: var(--primary); }"),
+            ("fonts/inter.woff2", "font bytes"),
+            ("images/bg.png", "png bytes"),
+            ("templates/blocks/footer.html", "<footer>{{ \"primary\"|theme_var }}</footer>"),
+        ],
+    );
+
+    let manager = ThemeManager::load(Some(&dir), "aurora");
+    assert!(manager.problems().is_empty(), "{:?}", manager.problems());
+    assert_eq!(manager.active_theme(), "aurora");
+    assert_eq!(manager.package_details("aurora"), Some(("1.2.0", "Jane")));
+    assert_eq!(manager.variable("primary"), Some("#123456"));
+    assert_eq!(manager.variable("muted"), Some("#e9ecef"));
+
+    let css = manager.get_active_theme_css();
+    assert!(css.starts_with(":root {\n"));
+    assert!(css.contains("    --accent: rgb(1, 2, 3);\n"));
+    assert!(css.contains("font-family: \"Inter\";"));
+
+    let background = manager.asset_path("images/bg.png").unwrap();
+    assert!(css.contains(&format!("url(\"{}\")", background)));
+    let asset = manager.asset(background.trim_start_matches("/assets/")).unwrap();
+    assert_eq!((asset.content_type, asset.bytes.as_slice()), ("image/png", b"png bytes".as_slice()));
+    let font = css.split("src: url(\"/assets/").nth(1).unwrap().split('"').next().unwrap();
+    assert!(font.starts_with("inter-") && font.ends_with(".woff2"));
+    assert_eq!(manager.asset(font).unwrap().content_type, "font/woff2");
+
+    let footer = templates::render_with(manager.template_environment(), &Footer { languages: Vec::new(), color_scheme_toggle: false, schemes: Vec::new() });
+    // The filter reads the globally active theme, so only check that it ran.
+    assert!(footer.starts_with("<footer>#") && !footer.contains("Proudly"), "{}", footer);
+    assert!(ThemeManager::load(Some(&dir), "default").template_environment().is_none());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_broken_theme_packages_are_reported() {
+    let dir = package_dir("broken");
+    let css = [("theme.css", "body {}")];
+    write_package(&dir.join("syntax"), "{ not json", &css);
+    write_package(&dir.join("escape"), r#"{ "name": "escape", "version": "1", "stylesheet": "../x.css" }"#, &css);
+    write_package(&dir.join("variable"), r#"{ "name": "variable", "version": "1", "variables": { "primary": "red; } body {" } }"#, &css);
+    write_package(
+        &dir.join("templates"),
+        r#"{ "name": "templates", "version": "1", "templates": "html" }"#,
+        &[("theme.css", "body {}"), ("html/unknown.html", "<p>")],
+    );
+    write_package(&dir.join("font"), r#"{ "name": "font", "version": "1", "fonts": [{ "family": "X", "file": "x.exe" }] }"#, &[("theme.css", ""), ("x.exe", "")]);
+    write_package(&dir.join("fine"), r#"{ "name": "fine", "version": "1" }"#, &css);
+
+    let manager = ThemeManager::load(Some(&dir), "default");
+    let mut broken: Vec<&str> = manager.problems().iter().map(|problem| problem.theme.as_str()).collect();
+    broken.sort_unstable();
+    assert_eq!(broken, vec!["escape", "font", "syntax", "templates", "variable"]);
+    assert!(manager.has_theme("fine"));
+    assert!(!manager.has_theme("variable"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_built_in_themes_have_variables() {
+    assert_eq!(ThemeManager::load(None, "monero").variable("primary"), Some("#ff6600"));
+    assert_eq!(ThemeManager::load(None, "default").variable("unknown"), None);
+}
@@ -0,0 +1,69 @@
+use crate::db;
+use crate::routes::Router;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_totp_login_without_pending_session_redirects() {
+    let router = Router::new(super::test_pool());
+    let app = router.create_router();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/login/totp")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers().get("location").unwrap(), "/login");
+}
+
+#[tokio::test]
+async fn test_two_factor_settings_require_auth() {
+    let router = Router::new(super::test_pool());
+    let app = router.create_router();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/protected/2fa")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers().get("location").unwrap(), "/login");
+}
+
+#[test]
+fn test_second_factor_attempts_lock_out_the_user() {
+    let pool = super::test_pool();
+    let conn = pool.get().unwrap();
+    let user_id = db::insert_user(&conn, "alice", "hash", "owner").unwrap();
+    db::save_pending_totp_secret(&conn, user_id, "JBSWY3DPEHPK3PXP").unwrap();
+
+    for _ in 0..5 {
+        assert!(db::claim_totp_attempt(&conn, user_id, 5, 15).unwrap());
+    }
+    assert!(!db::claim_totp_attempt(&conn, user_id, 5, 15).unwrap());
+
+    // Once the lock expires, codes are checked again.
+    conn.execute("UPDATE user_totp SET locked_until = datetime('now', '-1 minutes') WHERE user_id = ?", [user_id])
+        .unwrap();
+    assert!(db::claim_totp_attempt(&conn, user_id, 5, 15).unwrap());
+    db::reset_totp_attempts(&conn, user_id).unwrap();
+    for _ in 0..4 {
+        assert!(db::claim_totp_attempt(&conn, user_id, 5, 15).unwrap());
+    }
+    db::reset_totp_attempts(&conn, user_id).unwrap();
+    assert!(db::claim_totp_attempt(&conn, user_id, 5, 15).unwrap());
+}
@@ -0,0 +1,180 @@
+use crate::db::{self, AuditFilter, DatabaseError};
+use crate::models::{Author, CampaignDetails, CampaignFilter, NewCampaign, TranslatedContent, Translation};
+use crate::repositories::memory::InMemoryRepository;
+use crate::repositories::{
+    AboutRepository, CampaignRepository, ContactRepository, SqliteRepository, TranslationRepository,
+};
+use crate::transfer::{self, Bundle, ImportOptions, OnConflict};
+
+fn source() -> InMemoryRepository {
+    let repository = InMemoryRepository::default();
+    let author = Author { id: Some(7), name: "admin".to_string() };
+    let about = AboutRepository::insert(&repository, "About us", &author).unwrap();
+    let revision = AboutRepository::revisions(&repository, about).unwrap()[0].id;
+    AboutRepository::publish(&repository, about, revision).unwrap();
+    ContactRepository::insert(&repository, "Write to us", "team@example.org", &author).unwrap();
+
+    let campaign = CampaignRepository::insert(
+        &repository,
+        &NewCampaign {
+            title: "Node".to_string(),
+            description: "Fund the node".to_string(),
+            creator_id: 7,
+            start_date: "2024-01-01".to_string(),
+            end_date: "2024-12-31".to_string(),
+            goal_amount: 100.0,
+            status: "active".to_string(),
+            xmr_address: "4AdUndXHHZ6cfufTMvppY6JwXNouMBzSkbLYfpAV5Usx".to_string(),
+        },
+    )
+    .unwrap();
+    repository.set_current_amount(campaign, 12.5).unwrap();
+    repository.add_goal(campaign, "Buy the hardware", 50.0).unwrap();
+    let update = repository.add_update_at(campaign, "Hardware ordered", "2024-02-01 10:00:00").unwrap();
+    repository.save_translation(TranslatedContent::Campaign, campaign, &translation("es", "Nodo", "")).unwrap();
+    repository.save_translation(TranslatedContent::CampaignUpdate, update, &translation("es", "", "Hardware pedido")).unwrap();
+    repository.save_translation(TranslatedContent::About, about, &translation("pl", "", "O nas")).unwrap();
+    repository
+}
+
+fn translation(locale: &str, title: &str, body: &str) -> Translation {
+    Translation { locale: locale.to_string(), title: title.to_string(), body: body.to_string() }
+}
+
+fn options(on_conflict: OnConflict, dry_run: bool) -> ImportOptions {
+    ImportOptions {
+        on_conflict,
+        dry_run,
+        creator_id: 1,
+    }
+}
+
+fn campaign_count(repository: &InMemoryRepository) -> i64 {
+    CampaignRepository::count(repository, &CampaignFilter::default()).unwrap()
+}
+
+#[test]
+fn test_export_import_into_empty_instance() {
+    let json = serde_json::to_string(&transfer::export(&source()).unwrap()).unwrap();
+    let bundle: Bundle = serde_json::from_str(&json).unwrap();
+
+    let target = InMemoryRepository::default();
+    assert_eq!(bundle.translations.len(), 3);
+    let dry_run = transfer::import(&target, &bundle, &options(OnConflict::Skip, true)).unwrap().report;
+    assert_eq!(dry_run.len(), 5);
+    assert!(dry_run.iter().all(|line| line.starts_with('+')));
+    assert_eq!(campaign_count(&target), 0);
+
+    transfer::import(&target, &bundle, &options(OnConflict::Skip, false)).unwrap();
+    let campaign = &CampaignRepository::list(&target, &CampaignFilter::default(), 10, 0).unwrap()[0];
+    assert_eq!(campaign.title, "Node");
+    assert_eq!(campaign.creator_id, 1);
+    assert_eq!(campaign.current_amount, 12.5);
+    assert_eq!(target.goals(campaign.id).unwrap()[0].description, "Buy the hardware");
+    assert_eq!(target.updates(campaign.id).unwrap()[0].created_at, "2024-02-01 10:00:00");
+    assert_eq!(AboutRepository::active(&target).unwrap().unwrap().description, "About us");
+    assert_eq!(ContactRepository::list(&target).unwrap().len(), 1);
+
+    let translated = |content, id, locale| target.translation(content, id, locale).unwrap().unwrap();
+    assert_eq!(translated(TranslatedContent::Campaign, campaign.id, "es").title, "Nodo");
+    let update = target.updates(campaign.id).unwrap()[0].id;
+    assert_eq!(translated(TranslatedContent::CampaignUpdate, update, "es").body, "Hardware pedido");
+    let about = AboutRepository::active(&target).unwrap().unwrap().id;
+    assert_eq!(translated(TranslatedContent::About, about, "pl").body, "O nas");
+}
+
+#[test]
+fn test_import_accepts_version_1_bundles() {
+    let mut json = serde_json::to_value(transfer::export(&source()).unwrap()).unwrap();
+    json["version"] = 1.into();
+    json.as_object_mut().unwrap().remove("translations");
+    let bundle: Bundle = serde_json::from_value(json).unwrap();
+
+    let target = InMemoryRepository::default();
+    transfer::import(&target, &bundle, &options(OnConflict::Skip, false)).unwrap();
+    let campaign = &CampaignRepository::list(&target, &CampaignFilter::default(), 10, 0).unwrap()[0];
+    assert_eq!(campaign.title, "Node");
+    assert_eq!(target.translation(TranslatedContent::Campaign, campaign.id, "es").unwrap(), None);
+}
+
+#[test]
+fn test_import_conflict_options() {
+    let repository = source();
+    let bundle = transfer::export(&repository).unwrap();
+    let campaign_id = bundle.campaigns[0].id;
+
+    let skipped = transfer::import(&repository, &bundle, &options(OnConflict::Skip, false)).unwrap().report;
+    assert!(skipped.iter().all(|line| line.starts_with('=')));
+    assert_eq!(campaign_count(&repository), 1);
+
+    let details = CampaignDetails {
+        title: "Renamed".to_string(),
+        description: "Fund the node".to_string(),
+        start_date: "2024-01-01".to_string(),
+        end_date: "2024-12-31".to_string(),
+        goal_amount: 100.0,
+    };
+    repository.update_details(campaign_id, &details).unwrap();
+    let overwritten = transfer::import(&repository, &bundle, &options(OnConflict::Overwrite, false)).unwrap().report;
+    assert!(overwritten.contains(&format!("~ campaign #{}: title, 0 goals and 0 updates added", campaign_id)));
+    assert_eq!(CampaignRepository::get(&repository, campaign_id).unwrap().unwrap().title, "Node");
+    assert_eq!(repository.goals(campaign_id).unwrap().len(), 1);
+
+    transfer::import(&repository, &bundle, &options(OnConflict::Duplicate, false)).unwrap();
+    assert_eq!(campaign_count(&repository), 2);
+    assert_eq!(AboutRepository::list(&repository).unwrap().iter().filter(|entry| entry.active).count(), 1);
+}
+
+#[test]
+fn test_import_rejects_newer_bundle() {
+    let mut bundle = transfer::export(&InMemoryRepository::default()).unwrap();
+    bundle.version = transfer::BUNDLE_VERSION + 1;
+
+    let result = transfer::import(&InMemoryRepository::default(), &bundle, &options(OnConflict::Skip, false));
+    assert!(matches!(result, Err(DatabaseError::BundleTooNew(_))));
+}
+
+#[test]
+fn test_database_import_is_atomic_and_audited() {
+    let bundle = transfer::export(&source()).unwrap();
+    let pool = super::test_pool();
+    let mut conn = pool.get().unwrap();
+    let audit_count = |conn: &rusqlite::Connection, action: &str| {
+        let filter = AuditFilter { action: Some(action.to_string()), ..Default::default() };
+        db::count_audit_entries(conn, &filter).unwrap()
+    };
+    let campaigns = |conn: &rusqlite::Connection| {
+        CampaignRepository::count(&SqliteRepository::new(conn), &CampaignFilter::default()).unwrap()
+    };
+
+    conn.execute_batch("CREATE TEMP TRIGGER fail BEFORE INSERT ON goals BEGIN SELECT RAISE(ABORT, 'disk full'); END")
+        .unwrap();
+    assert!(transfer::import_database(&mut conn, &bundle, &options(OnConflict::Skip, false)).is_err());
+    conn.execute_batch("DROP TRIGGER fail").unwrap();
+    assert_eq!(campaigns(&conn), 0);
+    assert!(AboutRepository::list(&SqliteRepository::new(&conn)).unwrap().is_empty());
+    assert_eq!(audit_count(&conn, "about.import"), 0);
+
+    transfer::import_database(&mut conn, &bundle, &options(OnConflict::Skip, true)).unwrap();
+    assert_eq!(campaigns(&conn), 0);
+    assert_eq!(audit_count(&conn, "campaign.import"), 0);
+
+    transfer::import_database(&mut conn, &bundle, &options(OnConflict::Skip, false)).unwrap();
+    assert_eq!(campaigns(&conn), 1);
+    for action in ["about.import", "contact.import", "campaign.import", "campaign.address_import"] {
+        assert_eq!(audit_count(&conn, action), 1, "{}", action);
+    }
+    assert_eq!(db::verify_audit_chain(&conn).unwrap(), None);
+
+    let repository = SqliteRepository::new(&conn);
+    let about = AboutRepository::active(&repository).unwrap().unwrap().id;
+    repository.save_translation(TranslatedContent::About, about, &translation("pl", "", "Stare")).unwrap();
+    let translated = audit_count(&conn, "about.translate");
+    let mut bundle = bundle;
+    bundle.about[0].id = about;
+    bundle.translations.iter_mut().filter(|bundled| bundled.content == TranslatedContent::About).for_each(|bundled| {
+        bundled.content_id = about;
+    });
+    transfer::import_database(&mut conn, &bundle, &options(OnConflict::Overwrite, false)).unwrap();
+    assert_eq!(audit_count(&conn, "about.translate"), translated + 1);
+}
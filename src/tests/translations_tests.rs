@@ -0,0 +1,156 @@
+use crate::controllers::campaigns::campaign_details_content;
+use crate::i18n;
+use crate::models::{Author, NewCampaign, TranslatedContent, Translation};
+use crate::repositories::memory::InMemoryRepository;
+use crate::repositories::{
+    localize_campaign, AboutRepository, CampaignRepository, ContactRepository, SqliteRepository, TranslationRepository,
+};
+use crate::routes::Router;
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use tower::ServiceExt;
+
+fn new_campaign() -> NewCampaign {
+    NewCampaign {
+        title: "Node".to_string(),
+        description: "Fund the node".to_string(),
+        creator_id: 1,
+        start_date: "2024-01-01".to_string(),
+        end_date: "2024-12-31".to_string(),
+        goal_amount: 100.0,
+        status: "active".to_string(),
+        xmr_address: "4AdUndXHHZ6cfufTMvppY6JwXNouMBzSkbLYfpAV5Usx".to_string(),
+    }
+}
+
+fn translation(locale: &str, title: &str, body: &str) -> Translation {
+    Translation { locale: locale.to_string(), title: title.to_string(), body: body.to_string() }
+}
+
+fn author() -> Author {
+    Author { id: Some(1), name: "admin".to_string() }
+}
+
+fn check_fallback(repository: &(impl CampaignRepository + TranslationRepository)) {
+    let id = repository.insert(&new_campaign()).unwrap();
+    repository.save_translation(TranslatedContent::Campaign, id, &translation("es", "Nodo", "  ")).unwrap();
+
+    let localized = |locale| {
+        let mut campaign = repository.get(id).unwrap().unwrap();
+        localize_campaign(repository, &mut campaign, locale);
+        (campaign.title, campaign.description)
+    };
+    assert_eq!(localized("es"), ("Nodo".to_string(), "Fund the node".to_string()));
+    assert_eq!(localized("pl"), ("Node".to_string(), "Fund the node".to_string()));
+
+    repository.save_translation(TranslatedContent::Campaign, id, &translation("es", "Nodo", "Financia el nodo")).unwrap();
+    assert_eq!(localized("es"), ("Nodo".to_string(), "Financia el nodo".to_string()));
+    assert_eq!(localized("en"), ("Node".to_string(), "Fund the node".to_string()));
+
+    repository.save_translation(TranslatedContent::Campaign, id, &translation("es", "", " ")).unwrap();
+    assert_eq!(repository.translation(TranslatedContent::Campaign, id, "es").unwrap(), None);
+}
+
+#[test]
+fn test_translations_fall_back_to_the_default_language() {
+    check_fallback(&InMemoryRepository::default());
+    let pool = super::test_pool();
+    check_fallback(&SqliteRepository::new(&pool.get().unwrap()));
+}
+
+#[tokio::test]
+async fn test_campaign_details_are_translated() {
+    let repository = InMemoryRepository::default();
+    let id = CampaignRepository::insert(&repository, &new_campaign()).unwrap();
+    let first = repository.add_update(id, "Hardware ordered").unwrap();
+    repository.add_update(id, "Node online").unwrap();
+    repository.save_translation(TranslatedContent::Campaign, id, &translation("es", "Nodo", "")).unwrap();
+    repository.save_translation(TranslatedContent::CampaignUpdate, first, &translation("es", "", "Hardware pedido")).unwrap();
+
+    let content = i18n::scope("es", "/".to_string(), async { campaign_details_content(&repository, id, "") }).await.unwrap();
+    assert!(content.contains("Nodo"));
+    assert!(content.contains("Fund the node"));
+    assert!(content.contains("Hardware pedido"));
+    assert!(content.contains("Node online"));
+    assert!(content.contains(&format!("{:x}", md5::compute("Hardware ordered"))));
+
+    let content = i18n::scope("pl", "/".to_string(), async { campaign_details_content(&repository, id, "") }).await.unwrap();
+    assert!(content.contains("Hardware ordered"));
+    assert!(!content.contains("Nodo"));
+}
+
+async fn get(app: axum::Router, uri: &str) -> String {
+    let response = app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_public_pages_show_translated_content() {
+    let pool = super::test_pool();
+    {
+        let conn = pool.get().unwrap();
+        let repository = SqliteRepository::new(&conn);
+        let about = AboutRepository::insert(&repository, "About the team", &author()).unwrap();
+        AboutRepository::publish(&repository, about, AboutRepository::revisions(&repository, about).unwrap()[0].id).unwrap();
+        repository.save_translation(TranslatedContent::About, about, &translation("es", "", "Sobre el equipo")).unwrap();
+        let contact = ContactRepository::insert(&repository, "Write to us", "team@example.com", &author()).unwrap();
+        ContactRepository::publish(&repository, contact, ContactRepository::revisions(&repository, contact).unwrap()[0].id)
+            .unwrap();
+        repository.save_translation(TranslatedContent::Contact, contact, &translation("pl", "", "Napisz do nas")).unwrap();
+    }
+    let app = || Router::new(pool.clone()).create_router();
+
+    assert!(get(app(), "/es/about").await.contains("Sobre el equipo"));
+    assert!(get(app(), "/pl/about").await.contains("About the team"));
+    assert!(get(app(), "/about").await.contains("About the team"));
+    assert!(get(app(), "/pl/contact").await.contains("Napisz do nas"));
+    assert!(get(app(), "/es/contact").await.contains("Write to us"));
+}
+
+#[tokio::test]
+async fn test_head_links_alternate_languages() {
+    let body = get(Router::new(super::test_pool()).create_router(), "/es/about").await;
+    for (code, _, _) in i18n::LOCALES {
+        assert!(body.contains(&format!("<link rel=\"alternate\" hreflang=\"{}\" href=\"/{}/about\">", code, code)));
+    }
+    assert!(body.contains("<link rel=\"alternate\" hreflang=\"x-default\" href=\"/about\">"));
+}
+
+#[test]
+fn test_deleted_content_loses_its_translations() {
+    let pool = super::test_pool();
+    let conn = pool.get().unwrap();
+    let repository = SqliteRepository::new(&conn);
+    let campaign = CampaignRepository::insert(&repository, &new_campaign()).unwrap();
+    let update = repository.add_update(campaign, "Hardware ordered").unwrap();
+    let about = AboutRepository::insert(&repository, "About the team", &author()).unwrap();
+    repository.save_translation(TranslatedContent::Campaign, campaign, &translation("es", "Nodo", "")).unwrap();
+    repository.save_translation(TranslatedContent::CampaignUpdate, update, &translation("es", "", "Hardware pedido")).unwrap();
+    repository.save_translation(TranslatedContent::About, about, &translation("pl", "", "O zespole")).unwrap();
+
+    CampaignRepository::delete(&repository, campaign).unwrap();
+    assert_eq!(repository.translation(TranslatedContent::Campaign, campaign, "es").unwrap(), None);
+    assert_eq!(repository.translation(TranslatedContent::CampaignUpdate, update, "es").unwrap(), None);
+
+    AboutRepository::delete(&repository, about).unwrap();
+    assert!(repository.translation(TranslatedContent::About, about, "pl").unwrap().is_some());
+    AboutRepository::purge(&repository, about).unwrap();
+    assert_eq!(repository.translation(TranslatedContent::About, about, "pl").unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_saving_translations_requires_auth() {
+    for uri in ["/protected/campaigns/1/translations", "/protected/about/1/translations", "/protected/contact/1/translations"] {
+        let request = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from("locale=es&body=Hola"))
+            .unwrap();
+        let response = Router::new(super::test_pool()).create_router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER, "{}", uri);
+        assert_eq!(response.headers()[header::LOCATION], "/login");
+    }
+}
@@ -0,0 +1,473 @@
+use crate::db::{self, DatabaseError, NewAuditEntry};
+use crate::i18n;
+use crate::models::{
+    AboutEntry, Author, Campaign, CampaignDetails, CampaignFilter, CampaignUpdate, ContactEntry, Goal, NewCampaign,
+    TranslatedContent, Translation,
+};
+use crate::repositories::{
+    AboutRepository, CampaignRepository, ContactRepository, SqliteRepository, TranslationRepository,
+};
+use chrono::Utc;
+use rusqlite::{Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+/// Bumped whenever the bundle layout changes incompatibly.
+pub const BUNDLE_VERSION: u32 = 2;
+
+/// Site content moved between instances. Donations are carried as each
+/// campaign's current amount; users, sessions and the audit log stay behind.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Bundle {
+    pub version: u32,
+    pub exported_at: String,
+    pub about: Vec<AboutEntry>,
+    pub contact: Vec<ContactEntry>,
+    pub campaigns: Vec<Campaign>,
+    pub goals: Vec<Goal>,
+    pub updates: Vec<CampaignUpdate>,
+    /// Added in version 2; older bundles have none.
+    #[serde(default)]
+    pub translations: Vec<BundleTranslation>,
+}
+
+/// A translation of the bundle record `content_id` of type `content`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleTranslation {
+    pub content: TranslatedContent,
+    pub content_id: i64,
+    #[serde(flatten)]
+    pub translation: Translation,
+}
+
+/// What to do with a bundle record whose id already exists here.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnConflict {
+    Skip,
+    Overwrite,
+    Duplicate,
+}
+
+impl FromStr for OnConflict {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "skip" => Ok(OnConflict::Skip),
+            "overwrite" => Ok(OnConflict::Overwrite),
+            "duplicate" => Ok(OnConflict::Duplicate),
+            _ => Err(format!("Unknown conflict option '{}', expected skip, overwrite or duplicate", value)),
+        }
+    }
+}
+
+pub struct ImportOptions {
+    pub on_conflict: OnConflict,
+    /// Report what would change without writing anything.
+    pub dry_run: bool,
+    /// Owner of newly created campaigns; user ids differ between instances.
+    pub creator_id: i64,
+}
+
+/// The stored translations of record `id` into every language but the default one.
+fn translations_of(
+    repository: &impl TranslationRepository,
+    content: TranslatedContent,
+    id: i64,
+) -> Result<Vec<BundleTranslation>, DatabaseError> {
+    let mut translations = Vec::new();
+    for (locale, _, _) in i18n::LOCALES.iter().filter(|(locale, _, _)| *locale != i18n::DEFAULT_LOCALE) {
+        if let Some(translation) = repository.translation(content, id, locale)? {
+            translations.push(BundleTranslation { content, content_id: id, translation });
+        }
+    }
+    Ok(translations)
+}
+
+pub fn export<R>(repository: &R) -> Result<Bundle, DatabaseError>
+where
+    R: AboutRepository + ContactRepository + CampaignRepository + TranslationRepository,
+{
+    let about = AboutRepository::list(repository)?;
+    let contact = ContactRepository::list(repository)?;
+    let campaigns = CampaignRepository::list(repository, &CampaignFilter::default(), i64::MAX, 0)?;
+    let mut goals = Vec::new();
+    let mut updates = Vec::new();
+    let mut translations = Vec::new();
+    for entry in &about {
+        translations.extend(translations_of(repository, TranslatedContent::About, entry.id)?);
+    }
+    for entry in &contact {
+        translations.extend(translations_of(repository, TranslatedContent::Contact, entry.id)?);
+    }
+    for campaign in &campaigns {
+        translations.extend(translations_of(repository, TranslatedContent::Campaign, campaign.id)?);
+        goals.extend(repository.goals(campaign.id)?);
+        for update in repository.updates(campaign.id)? {
+            translations.extend(translations_of(repository, TranslatedContent::CampaignUpdate, update.id)?);
+            updates.push(update);
+        }
+    }
+
+    Ok(Bundle {
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        about,
+        contact,
+        campaigns,
+        goals,
+        updates,
+        translations,
+    })
+}
+
+fn changed_fields(fields: &[(&'static str, bool)]) -> Vec<&'static str> {
+    fields.iter().filter(|(_, changed)| *changed).map(|(name, _)| *name).collect()
+}
+
+/// What an import did: its report, see `import`, and an audit log entry for each
+/// change it wrote, for the caller to store in the same transaction.
+pub struct Imported {
+    pub report: Vec<String>,
+    pub audit: Vec<NewAuditEntry>,
+}
+
+/// The audit log entries of an import, made by `actor_id` as "import".
+struct ImportAudit {
+    actor_id: i64,
+    entries: Vec<NewAuditEntry>,
+}
+
+impl ImportAudit {
+    fn record(&mut self, action: &str, target_type: &str, target_id: i64, before: Option<Value>, after: Option<Value>) {
+        self.entries.push(NewAuditEntry {
+            actor_id: Some(self.actor_id),
+            actor_name: "import".to_string(),
+            action: action.to_string(),
+            target_type: target_type.to_string(),
+            target_id: Some(target_id),
+            before,
+            after,
+            ip: "cli".to_string(),
+        });
+    }
+}
+
+/// Saves the bundle's translations of record `bundle_id` onto record `id`, returning
+/// how many differ from the stored ones. `id` is `None` for a record a dry run would
+/// add, and nothing is written without `audit`.
+fn import_translations(
+    repository: &impl TranslationRepository,
+    bundle: &Bundle,
+    content: TranslatedContent,
+    bundle_id: i64,
+    id: Option<i64>,
+    audit: Option<&mut ImportAudit>,
+) -> Result<usize, DatabaseError> {
+    let mut changed = 0;
+    let mut audit = audit;
+    let translations = bundle
+        .translations
+        .iter()
+        .filter(|bundled| bundled.content == content && bundled.content_id == bundle_id);
+    for BundleTranslation { translation, .. } in translations {
+        if let Some(id) = id {
+            let before = repository.translation(content, id, &translation.locale)?;
+            if before.as_ref() == Some(translation) {
+                continue;
+            }
+            if let Some(audit) = audit.as_deref_mut() {
+                repository.save_translation(content, id, translation)?;
+                let action = format!("{}.translate", content.as_str());
+                let before = before.map(|before| json!(before));
+                audit.record(&action, content.as_str(), id, before, Some(json!(translation)));
+            }
+        }
+        changed += 1;
+    }
+    Ok(changed)
+}
+
+/// Imports `bundle`, reporting one line per record, and one for its translations
+/// if any: `+` added, `~` overwritten, `=` left as is and `!` for differences
+/// that were not applied.
+/// Records are matched on id; added ones get new ids and their goals, updates
+/// and translations follow them.
+pub fn import<R>(repository: &R, bundle: &Bundle, options: &ImportOptions) -> Result<Imported, DatabaseError>
+where
+    R: AboutRepository + ContactRepository + CampaignRepository + TranslationRepository,
+{
+    if bundle.version > BUNDLE_VERSION {
+        return Err(DatabaseError::BundleTooNew(bundle.version));
+    }
+    let write = !options.dry_run;
+    let mut report = Vec::new();
+    let mut audit = ImportAudit { actor_id: options.creator_id, entries: Vec::new() };
+    let author = Author { id: Some(options.creator_id), name: "import".to_string() };
+    let translate = |audit: &mut ImportAudit, content, bundle_id, id| {
+        import_translations(repository, bundle, content, bundle_id, id, Some(audit).filter(|_| write))
+    };
+
+    for entry in &bundle.about {
+        let existing = AboutRepository::get(repository, entry.id)?;
+        match (existing, options.on_conflict) {
+            (Some(_), OnConflict::Skip) => report.push(format!("= about #{} exists, skipped", entry.id)),
+            (Some(existing), OnConflict::Overwrite) => {
+                let changed = changed_fields(&[
+                    ("description", existing.description != entry.description),
+                    ("active", existing.active != entry.active),
+                ]);
+                if changed.is_empty() {
+                    report.push(format!("= about #{} unchanged", entry.id));
+                } else {
+                    report.push(format!("~ about #{}: {}", entry.id, changed.join(", ")));
+                    if write {
+                        let revision_id = AboutRepository::save(repository, existing.id, &entry.description, &author)?;
+                        if entry.active {
+                            AboutRepository::publish(repository, existing.id, revision_id)?;
+                        } else if existing.active {
+                            AboutRepository::unpublish(repository, existing.id)?;
+                        }
+                        let after = AboutRepository::get(repository, existing.id)?;
+                        audit.record("about.import", "about", existing.id, Some(json!(existing)), Some(json!(after)));
+                    }
+                }
+                let translated = translate(&mut audit, TranslatedContent::About, entry.id, Some(existing.id))?;
+                if translated > 0 {
+                    report.push(format!("~ about #{}: {} translations", entry.id, translated));
+                }
+            }
+            (existing, _) => {
+                // Copies of existing entries arrive inactive so they don't replace the live page.
+                let active = entry.active && existing.is_none();
+                report.push(format!("+ about from bundle #{}{}", entry.id, if active { " (active)" } else { "" }));
+                let id = if write {
+                    let id = AboutRepository::insert(repository, &entry.description, &author)?;
+                    // The first revision holds exactly what was just inserted.
+                    if let Some(revision) = AboutRepository::revisions(repository, id)?.first().filter(|_| active) {
+                        AboutRepository::publish(repository, id, revision.id)?;
+                    }
+                    audit.record("about.import", "about", id, None, Some(json!(AboutRepository::get(repository, id)?)));
+                    Some(id)
+                } else {
+                    None
+                };
+                let translated = translate(&mut audit, TranslatedContent::About, entry.id, id)?;
+                if translated > 0 {
+                    report.push(format!("+ about from bundle #{}: {} translations", entry.id, translated));
+                }
+            }
+        }
+    }
+
+    for entry in &bundle.contact {
+        let existing = ContactRepository::get(repository, entry.id)?;
+        match (existing, options.on_conflict) {
+            (Some(_), OnConflict::Skip) => report.push(format!("= contact #{} exists, skipped", entry.id)),
+            (Some(existing), OnConflict::Overwrite) => {
+                let changed = changed_fields(&[
+                    ("description", existing.description != entry.description),
+                    ("admin_mail", existing.admin_mail != entry.admin_mail),
+                    ("active", existing.active != entry.active),
+                ]);
+                if changed.is_empty() {
+                    report.push(format!("= contact #{} unchanged", entry.id));
+                } else {
+                    report.push(format!("~ contact #{}: {}", entry.id, changed.join(", ")));
+                    if write {
+                        let (description, admin_mail) = (&entry.description, &entry.admin_mail);
+                        let revision_id = ContactRepository::save(repository, existing.id, description, admin_mail, &author)?;
+                        if entry.active {
+                            ContactRepository::publish(repository, existing.id, revision_id)?;
+                        } else if existing.active {
+                            ContactRepository::unpublish(repository, existing.id)?;
+                        }
+                        let (before, after) = (json!(existing), json!(ContactRepository::get(repository, existing.id)?));
+                        audit.record("contact.import", "contact", existing.id, Some(before), Some(after));
+                    }
+                }
+                let translated = translate(&mut audit, TranslatedContent::Contact, entry.id, Some(existing.id))?;
+                if translated > 0 {
+                    report.push(format!("~ contact #{}: {} translations", entry.id, translated));
+                }
+            }
+            (existing, _) => {
+                let active = entry.active && existing.is_none();
+                report.push(format!("+ contact from bundle #{}{}", entry.id, if active { " (active)" } else { "" }));
+                let id = if write {
+                    let id = ContactRepository::insert(repository, &entry.description, &entry.admin_mail, &author)?;
+                    // The first revision holds exactly what was just inserted.
+                    if let Some(revision) = ContactRepository::revisions(repository, id)?.first().filter(|_| active) {
+                        ContactRepository::publish(repository, id, revision.id)?;
+                    }
+                    let after = ContactRepository::get(repository, id)?;
+                    audit.record("contact.import", "contact", id, None, Some(json!(after)));
+                    Some(id)
+                } else {
+                    None
+                };
+                let translated = translate(&mut audit, TranslatedContent::Contact, entry.id, id)?;
+                if translated > 0 {
+                    report.push(format!("+ contact from bundle #{}: {} translations", entry.id, translated));
+                }
+            }
+        }
+    }
+
+    for campaign in &bundle.campaigns {
+        let goals: Vec<&Goal> = bundle.goals.iter().filter(|goal| goal.campaign_id == campaign.id).collect();
+        // Oldest first, so ids keep their order.
+        let updates: Vec<&CampaignUpdate> =
+            bundle.updates.iter().rev().filter(|update| update.campaign_id == campaign.id).collect();
+
+        let existing = CampaignRepository::get(repository, campaign.id)?;
+        match (existing, options.on_conflict) {
+            (Some(_), OnConflict::Skip) => report.push(format!("= campaign #{} exists, skipped", campaign.id)),
+            (Some(existing), OnConflict::Overwrite) => {
+                let existing_goals = repository.goals(existing.id)?;
+                let existing_updates = repository.updates(existing.id)?;
+                let new_goals: Vec<&&Goal> = goals
+                    .iter()
+                    .filter(|goal| !existing_goals.iter().any(|existing| existing.description == goal.description))
+                    .collect();
+                let new_updates: Vec<&&CampaignUpdate> = updates
+                    .iter()
+                    .filter(|update| !existing_updates.iter().any(|existing| existing.update_hash == update.update_hash))
+                    .collect();
+                let changed = changed_fields(&[
+                    ("title", existing.title != campaign.title),
+                    ("description", existing.description != campaign.description),
+                    ("start_date", existing.start_date != campaign.start_date),
+                    ("end_date", existing.end_date != campaign.end_date),
+                    ("goal_amount", existing.goal_amount != campaign.goal_amount),
+                    ("current_amount", existing.current_amount != campaign.current_amount),
+                ]);
+
+                let unchanged = changed.is_empty() && new_goals.is_empty() && new_updates.is_empty();
+                if unchanged {
+                    report.push(format!("= campaign #{} unchanged", campaign.id));
+                } else {
+                    report.push(format!(
+                        "~ campaign #{}: {}{} goals and {} updates added",
+                        campaign.id,
+                        changed.iter().map(|field| format!("{}, ", field)).collect::<String>(),
+                        new_goals.len(),
+                        new_updates.len()
+                    ));
+                }
+                if existing.status != campaign.status {
+                    report.push(format!("! campaign #{}: status differs and was not changed", campaign.id));
+                }
+                if existing.xmr_address != campaign.xmr_address {
+                    report.push(format!(
+                        "! campaign #{}: payout address differs; request a change so it goes through approval",
+                        campaign.id
+                    ));
+                }
+
+                if write {
+                    let details = CampaignDetails {
+                        title: campaign.title.clone(),
+                        description: campaign.description.clone(),
+                        start_date: campaign.start_date.clone(),
+                        end_date: campaign.end_date.clone(),
+                        goal_amount: campaign.goal_amount,
+                    };
+                    repository.update_details(existing.id, &details)?;
+                    repository.set_current_amount(existing.id, campaign.current_amount)?;
+                    for goal in new_goals {
+                        repository.add_goal(existing.id, &goal.description, goal.amount)?;
+                    }
+                    if !unchanged {
+                        let after = CampaignRepository::get(repository, existing.id)?;
+                        let before = Some(json!(existing));
+                        audit.record("campaign.import", "campaign", existing.id, before, Some(json!(after)));
+                    }
+                }
+                let id = Some(existing.id);
+                let mut translated = translate(&mut audit, TranslatedContent::Campaign, campaign.id, id)?;
+                for update in &updates {
+                    let id = match existing_updates.iter().find(|existing| existing.update_hash == update.update_hash) {
+                        Some(existing) => Some(existing.id),
+                        None if write => {
+                            Some(repository.add_update_at(existing.id, &update.update_text, &update.created_at)?)
+                        }
+                        None => None,
+                    };
+                    translated += translate(&mut audit, TranslatedContent::CampaignUpdate, update.id, id)?;
+                }
+                if translated > 0 {
+                    report.push(format!("~ campaign #{}: {} translations", campaign.id, translated));
+                }
+            }
+            _ => {
+                report.push(format!(
+                    "+ campaign \"{}\" from bundle #{} with {} goals and {} updates",
+                    campaign.title,
+                    campaign.id,
+                    goals.len(),
+                    updates.len()
+                ));
+                let id = if write {
+                    let id = CampaignRepository::insert(
+                        repository,
+                        &NewCampaign {
+                            title: campaign.title.clone(),
+                            description: campaign.description.clone(),
+                            creator_id: options.creator_id,
+                            start_date: campaign.start_date.clone(),
+                            end_date: campaign.end_date.clone(),
+                            goal_amount: campaign.goal_amount,
+                            status: campaign.status.clone(),
+                            xmr_address: campaign.xmr_address.clone(),
+                        },
+                    )?;
+                    repository.set_current_amount(id, campaign.current_amount)?;
+                    for goal in &goals {
+                        repository.add_goal(id, &goal.description, goal.amount)?;
+                    }
+                    let after = CampaignRepository::get(repository, id)?;
+                    audit.record("campaign.import", "campaign", id, None, Some(json!(after)));
+                    // Payout addresses otherwise change only through approval, so log each one set here.
+                    let address = json!({ "xmr_address": campaign.xmr_address });
+                    audit.record("campaign.address_import", "campaign", id, None, Some(address));
+                    Some(id)
+                } else {
+                    None
+                };
+                let mut translated = translate(&mut audit, TranslatedContent::Campaign, campaign.id, id)?;
+                for update in &updates {
+                    let update_id = match id {
+                        Some(id) => Some(repository.add_update_at(id, &update.update_text, &update.created_at)?),
+                        None => None,
+                    };
+                    translated += translate(&mut audit, TranslatedContent::CampaignUpdate, update.id, update_id)?;
+                }
+                if translated > 0 {
+                    report.push(format!("+ campaign from bundle #{}: {} translations", campaign.id, translated));
+                }
+            }
+        }
+    }
+
+    Ok(Imported { report, audit: audit.entries })
+}
+
+/// Runs `import` against the database in one transaction, together with its audit
+/// log entries, so a failure part way leaves nothing behind. A dry run rolls back.
+pub fn import_database(
+    conn: &mut Connection,
+    bundle: &Bundle,
+    options: &ImportOptions,
+) -> Result<Vec<String>, DatabaseError> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let imported = import(&SqliteRepository::new(&tx), bundle, options)?;
+    for entry in &imported.audit {
+        db::append_audit_entry(&tx, entry)?;
+    }
+    if !options.dry_run {
+        tx.commit()?;
+    }
+    Ok(imported.report)
+}
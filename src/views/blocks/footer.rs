@@ -1,13 +1,45 @@
-pub struct Footer {}
+use crate::i18n;
+use crate::views::templates::{self, filters, view, SelectOption};
+use crate::views::themes::color_scheme::{self, ColorScheme};
+use crate::views::themes::theme_manager;
+use serde::Serialize;
+
+/// A link to the current page in another language.
+#[derive(Serialize)]
+pub struct LanguageLink {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub href: String,
+    pub current: bool,
+}
+
+view! {
+    "blocks/footer.html",
+    pub struct Footer {
+        pub languages: Vec<LanguageLink>,
+        /// Whether the theme has a dark variant to toggle.
+        pub color_scheme_toggle: bool,
+        pub schemes: Vec<SelectOption>,
+    }
+}
 
 impl Footer {
     pub fn render() -> String {
-        r#"
-        <footer>
-            <p class="footer-ad"><a href="https://github.com/KrzysztofMarciniak/MRS-Funding-Software">Proudly using MRS Funding Software.</a></p>
-        </footer>
-        </body>
-        "#
-        .to_string()
+        let options = ColorScheme::ALL.map(|scheme| (scheme.as_str(), scheme.message_key()));
+        templates::render(&Footer {
+            languages: language_links(),
+            color_scheme_toggle: theme_manager::current().has_dark_variant(),
+            schemes: SelectOption::list(&options, color_scheme::current().as_str()),
+        })
     }
 }
+
+/// Links to the page being rendered under each locale's URL prefix.
+pub fn language_links() -> Vec<LanguageLink> {
+    let path = i18n::current_path();
+    let current = i18n::current();
+    i18n::LOCALES
+        .iter()
+        .map(|(code, name, _)| LanguageLink { code, name, href: format!("/{}{}", code, path), current: *code == current })
+        .collect()
+}
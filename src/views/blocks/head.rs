@@ -1,18 +1,33 @@
-pub fn render_head(title: &str, theme_css: &str) -> String {
-    format!(
-        r#"
-        <!DOCTYPE html>
-        <html lang="en">
-        <head>
-            <meta charset="UTF-8">
-            <meta name="viewport" content="width=device-width, initial-scale=1.0">
-            <title>{}</title>
-            <style>
-                {}
-            </style>
-        </head>
-        <body>
-        "#,
-        title, theme_css
-    )
+use crate::i18n;
+use crate::views::blocks::footer::{language_links, LanguageLink};
+use crate::views::templates::{self, view};
+use crate::views::themes::theme_manager::ThemeStyles;
+
+view! {
+    "blocks/head.html",
+    struct Head<'a> {
+        title: &'a str,
+        stylesheet: &'a str,
+        /// Empty when dark mode does not apply.
+        dark_stylesheet: &'a str,
+        /// Empty when dark mode is forced.
+        dark_media: &'a str,
+        color_scheme: &'a str,
+        /// The page in every language, for `hreflang` alternate links.
+        alternates: Vec<LanguageLink>,
+        /// The unprefixed page, which negotiates the language.
+        default_href: String,
+    }
+}
+
+pub fn render_head(title: &str, styles: &ThemeStyles) -> String {
+    templates::render(&Head {
+        title,
+        stylesheet: &styles.stylesheet,
+        dark_stylesheet: styles.dark_stylesheet.as_deref().unwrap_or_default(),
+        dark_media: styles.dark_media.unwrap_or_default(),
+        color_scheme: styles.color_scheme,
+        alternates: language_links(),
+        default_href: i18n::current_path(),
+    })
 }
@@ -1,61 +1,78 @@
+use crate::i18n;
 use crate::views::blocks::footer::Footer;
 use crate::views::blocks::head::render_head;
 use crate::views::blocks::header::Header;
-use crate::views::themes::theme_manager::ThemeManager;
+use crate::views::templates::{self, view};
+use crate::views::themes::{color_scheme, theme_manager};
 use axum::response::Html;
 use tower_sessions::Session;
 
+view! {
+    "layout.html",
+    struct LayoutView<'a> {
+        lang: &'a str,
+        head: &'a str,
+        header: &'a str,
+        content: &'a str,
+        footer: &'a str,
+    }
+}
+
 pub struct Layout {
     title: String,
     content: String,
     is_authenticated: bool,
-    theme_manager: ThemeManager,
+    theme: Option<String>,
 }
 
 impl Layout {
     pub fn new(title: String, content: String, is_authenticated: bool) -> Self {
-        let theme_manager = ThemeManager::new();
         Self {
             title,
             content,
             is_authenticated,
-            theme_manager,
+            theme: None,
         }
     }
 
+    pub fn with_theme(mut self, theme: Option<&str>) -> Self {
+        self.theme = theme.map(str::to_string);
+        self
+    }
+
     pub async fn render(self) -> Html<String> {
-        let content = format!(
-            r#"
-            {}
-                {}
-                <main>
-                    {}
-                </main>
-                {}
-            </body>
-            </html>
-            "#,
-            render_head(&self.title, &self.theme_manager.get_active_theme_css()),
-            Header::new(self.title.clone(), self.is_authenticated).render(),
-            self.content,
-            Footer::render()
-        );
-        Html(content)
+        let styles = theme_manager::current().styles(self.theme.as_deref(), color_scheme::current());
+        let head = render_head(&self.title, &styles);
+        let header = Header::new(self.title.clone(), self.is_authenticated).render();
+        let footer = Footer::render();
+        Html(templates::render(&LayoutView {
+            lang: i18n::current(),
+            head: &head,
+            header: &header,
+            content: &self.content,
+            footer: &footer,
+        }))
     }
 }
 
 pub async fn render_layout(content: &str, session: &Session) -> Html<String> {
+    render_layout_with_theme(content, session, None).await
+}
+
+/// Like `render_layout`, styled with the theme `theme` instead of the active one when given.
+pub async fn render_layout_with_theme(content: &str, session: &Session, theme: Option<&str>) -> Html<String> {
     let website_title =
         std::env::var("WEBSITE_TITLE")
         .unwrap_or_else(|_| "MRS-Funding-Software".to_string());
 
     let is_authenticated = session
-        .get::<String>("user_id")
+        .get::<i64>("user_id")
         .await
         .unwrap_or(None)
         .is_some();
 
     Layout::new(website_title, content.to_string(), is_authenticated)
+        .with_theme(theme)
         .render()
         .await
 }
@@ -1,3 +1,4 @@
 pub mod blocks;
 pub mod layout;
+pub mod templates;
 pub mod themes;
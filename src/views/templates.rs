@@ -0,0 +1,281 @@
+use crate::i18n;
+use crate::views::themes::theme_manager;
+use askama::Template;
+use minijinja::{Environment, Error, ErrorKind, Value};
+use serde::Serialize;
+use std::io;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// A page or block template with its typed context. It is compiled from
+/// `templates/` with Askama, unless `TEMPLATE_DIR` is set or the active theme
+/// brings templates: then every view is rendered at runtime, preferring files
+/// there over the built-in ones.
+/// Built-in templates stick to the syntax both engines share so they work either way.
+pub trait View: Template + Serialize {
+    const NAME: &'static str;
+}
+
+/// Declares a view struct for the template at `templates/<path>`.
+macro_rules! view {
+    ($path:tt, $(#[$attr:meta])* $vis:vis struct $name:ident $(<$lt:lifetime>)? { $($fields:tt)* }) => {
+        #[derive(askama::Template, serde::Serialize)]
+        #[template(path = $path)]
+        $(#[$attr])*
+        $vis struct $name $(<$lt>)? { $($fields)* }
+
+        impl $(<$lt>)? $crate::views::templates::View for $name $(<$lt>)? {
+            const NAME: &'static str = $path;
+        }
+    };
+}
+pub(crate) use view;
+
+macro_rules! sources {
+    ($($name:literal),* $(,)?) => {
+        &[$(($name, include_str!(concat!("../../templates/", $name)))),*]
+    };
+}
+
+/// The built-in templates, for overrides that include or extend ones they don't replace.
+pub const SOURCES: &[(&str, &str)] = sources![
+    "layout.html",
+    "blocks/footer.html",
+    "blocks/form_error.html",
+    "blocks/head.html",
+    "blocks/header.html",
+    "blocks/pagination.html",
+    "blocks/preview.html",
+    "blocks/progress.html",
+    "blocks/translation_tabs.html",
+    "about/all.html",
+    "about/details.html",
+    "about/new.html",
+    "about/public.html",
+    "account/api_tokens.html",
+    "account/page.html",
+    "account/recovery_codes.html",
+    "account/two_factor.html",
+    "address_changes.html",
+    "audit.html",
+    "campaigns/admin_list.html",
+    "campaigns/amount.html",
+    "campaigns/details.html",
+    "campaigns/edit.html",
+    "campaigns/filter.html",
+    "campaigns/list.html",
+    "campaigns/new.html",
+    "campaigns/new_update.html",
+    "contact/all.html",
+    "contact/details.html",
+    "contact/new.html",
+    "contact/public.html",
+    "dashboard.html",
+    "errors/internal.html",
+    "errors/not_found.html",
+    "home.html",
+    "login.html",
+    "login_totp.html",
+    "revisions/diff.html",
+    "revisions/history.html",
+    "revisions/trash.html",
+    "sessions.html",
+    "themes.html",
+    "translations/form.html",
+    "users.html",
+];
+
+/// Filters for messages, formatting, theme variables and assets, registered with the
+/// runtime environments too. Modules whose compiled templates use them import this module.
+pub mod filters {
+    use crate::i18n;
+    use crate::views::themes::theme_manager;
+    use std::fmt::Display;
+
+    /// The message `key` in the request's locale, e.g. `{{ "nav-home"|t }}`.
+    pub fn t<T: Display>(key: T) -> askama::Result<String> {
+        Ok(i18n::t(&key.to_string()))
+    }
+
+    /// A message with one value, e.g. `{{ "campaign-progress"|t1("percent", campaign.progress_percent) }}`.
+    pub fn t1<K: Display, N: Display, V: Display>(key: K, name: N, value: V) -> askama::Result<String> {
+        Ok(i18n::t_args(&key.to_string(), &[(&name.to_string(), value.to_string())]))
+    }
+
+    /// A message with two values.
+    pub fn t2<K: Display, N1: Display, V1: Display, N2: Display, V2: Display>(
+        key: K,
+        name1: N1,
+        value1: V1,
+        name2: N2,
+        value2: V2,
+    ) -> askama::Result<String> {
+        let (name1, name2) = (name1.to_string(), name2.to_string());
+        Ok(i18n::t_args(&key.to_string(), &[(&name1, value1.to_string()), (&name2, value2.to_string())]))
+    }
+
+    /// An `onsubmit` handler asking the confirmation question `key`; see `routes::INLINE_CONFIRMATIONS`.
+    pub fn confirm<T: Display>(key: T) -> askama::Result<String> {
+        Ok(i18n::confirm_handler(i18n::current(), &key.to_string()))
+    }
+
+    /// A number such as an XMR amount, grouped for the request's locale.
+    pub fn amount<T: Display>(value: T) -> askama::Result<String> {
+        let value = value.to_string();
+        Ok(match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => i18n::format_number(number),
+            _ => value,
+        })
+    }
+
+    /// A `YYYY-MM-DD` date in the request's locale.
+    pub fn local_date<T: Display>(value: T) -> askama::Result<String> {
+        Ok(i18n::format_date(&value.to_string()))
+    }
+
+    /// A `YYYY-MM-DD HH:MM[:SS[.fff]]` time in the request's locale.
+    pub fn local_datetime<T: Display>(value: T) -> askama::Result<String> {
+        Ok(i18n::format_datetime(&value.to_string()))
+    }
+
+    /// A variable of the active theme, e.g. `{{ "primary"|theme_var }}`; empty if unset.
+    pub fn theme_var<T: Display>(name: T) -> askama::Result<String> {
+        Ok(theme_manager::current().variable(&name.to_string()).unwrap_or_default().to_string())
+    }
+
+    /// The URL of an image from the active theme's manifest; empty if it has none.
+    pub fn theme_asset<T: Display>(path: T) -> askama::Result<String> {
+        Ok(theme_manager::current().asset_path(&path.to_string()).unwrap_or_default())
+    }
+}
+
+/// A runtime environment loading templates from `dir`, falling back to the built-in ones.
+pub fn override_environment(dir: PathBuf) -> Environment<'static> {
+    layered_environment(vec![dir])
+}
+
+/// A runtime environment loading templates from the first of `dirs` that has them,
+/// falling back to the built-in ones.
+pub fn layered_environment(dirs: Vec<PathBuf>) -> Environment<'static> {
+    let mut environment = Environment::new();
+    environment.set_loader(move |name| {
+        for dir in &dirs {
+            match std::fs::read_to_string(dir.join(name)) {
+                Ok(source) => return Ok(Some(source)),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(Error::new(ErrorKind::InvalidOperation, format!("cannot read template {}", name)).with_source(e))
+                }
+            }
+        }
+        Ok(SOURCES.iter().find(|(source_name, _)| *source_name == name).map(|(_, source)| source.to_string()))
+    });
+    environment.add_filter("t", |key: &str| filters::t(key).unwrap_or_default());
+    environment.add_filter("t1", |key: &str, name: &str, value: Value| filters::t1(key, name, value).unwrap_or_default());
+    environment.add_filter("t2", |key: &str, name1: &str, value1: Value, name2: &str, value2: Value| {
+        filters::t2(key, name1, value1, name2, value2).unwrap_or_default()
+    });
+    environment.add_filter("confirm", |key: &str| filters::confirm(key).unwrap_or_default());
+    environment.add_filter("amount", |value: Value| filters::amount(value).unwrap_or_default());
+    environment.add_filter("local_date", |value: Value| filters::local_date(value).unwrap_or_default());
+    environment.add_filter("local_datetime", |value: Value| filters::local_datetime(value).unwrap_or_default());
+    environment.add_filter("theme_var", |name: &str| filters::theme_var(name).unwrap_or_default());
+    environment.add_filter("theme_asset", |path: &str| filters::theme_asset(path).unwrap_or_default());
+    environment
+}
+
+/// The `TEMPLATE_DIR` setting.
+pub fn template_dir() -> Option<PathBuf> {
+    std::env::var("TEMPLATE_DIR").ok().filter(|dir| !dir.trim().is_empty()).map(PathBuf::from)
+}
+
+fn overrides() -> Option<&'static Environment<'static>> {
+    static OVERRIDES: OnceLock<Option<Environment<'static>>> = OnceLock::new();
+    OVERRIDES
+        .get_or_init(|| {
+            let dir = template_dir()?;
+            println!("Rendering templates from {} where present", dir.display());
+            Some(override_environment(dir))
+        })
+        .as_ref()
+}
+
+/// Renders `view` with `environment` when given, falling back to the compiled template if that fails.
+pub fn render_with<V: View>(environment: Option<&Environment>, view: &V) -> String {
+    if let Some(environment) = environment {
+        match environment.get_template(V::NAME).and_then(|template| template.render(view)) {
+            Ok(html) => return html,
+            Err(e) => eprintln!("Error: template {} failed, using the built-in one: {:#}", V::NAME, e),
+        }
+    }
+    Template::render(view).unwrap_or_else(|e| {
+        eprintln!("Error: template {} failed: {}", V::NAME, e);
+        String::new()
+    })
+}
+
+/// Renders `view` with the active theme's template overrides, else those from `TEMPLATE_DIR`.
+pub fn render<V: View>(view: &V) -> String {
+    let themes = theme_manager::current();
+    match themes.template_environment() {
+        Some(environment) => render_with(Some(environment), view),
+        None => render_with(overrides(), view),
+    }
+}
+
+/// An `<option>` of a select box.
+#[derive(Serialize)]
+pub struct SelectOption {
+    pub value: &'static str,
+    pub label: String,
+    pub selected: bool,
+}
+
+impl SelectOption {
+    /// Options from (value, message key) pairs, labelled in the request's locale.
+    pub fn list(options: &[(&'static str, &'static str)], selected: &str) -> Vec<SelectOption> {
+        options
+            .iter()
+            .map(|(value, label)| SelectOption { value, label: i18n::t(label), selected: *value == selected })
+            .collect()
+    }
+}
+
+/// Previous/next links of a paged list; a link is empty when there is no such page.
+#[derive(Serialize)]
+pub struct Pagination {
+    pub current_page: i64,
+    pub total_pages: i64,
+    pub previous: String,
+    pub next: String,
+    pub previous_label: String,
+    pub next_label: String,
+}
+
+impl Pagination {
+    /// Links to `path` with `query` (already encoded) and the page number.
+    pub fn new(path: &str, query: &str, current_page: i64, total_pages: i64) -> Self {
+        let page_link = |number: i64| {
+            if query.is_empty() {
+                format!("{}?page={}", path, number)
+            } else {
+                format!("{}?{}&page={}", path, query, number)
+            }
+        };
+        Pagination {
+            current_page,
+            total_pages,
+            previous: if current_page > 1 { page_link(current_page - 1) } else { String::new() },
+            next: if current_page < total_pages { page_link(current_page + 1) } else { String::new() },
+            previous_label: i18n::t("pagination-previous"),
+            next_label: i18n::t("pagination-next"),
+        }
+    }
+
+    /// Uses the messages `previous_key` and `next_key` for the links.
+    pub fn with_labels(mut self, previous_key: &str, next_key: &str) -> Self {
+        self.previous_label = i18n::t(previous_key);
+        self.next_label = i18n::t(next_key);
+        self
+    }
+}
@@ -0,0 +1,76 @@
+use std::future::Future;
+
+/// The cookie holding a visitor's choice; no cookie means `Auto`.
+pub const COOKIE: &str = "color-scheme";
+
+/// A visitor's colour scheme preference.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorScheme {
+    /// Follow the browser's `prefers-color-scheme`.
+    #[default]
+    Auto,
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    pub const ALL: [ColorScheme; 3] = [ColorScheme::Auto, ColorScheme::Light, ColorScheme::Dark];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(ColorScheme::Auto),
+            "light" => Some(ColorScheme::Light),
+            "dark" => Some(ColorScheme::Dark),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColorScheme::Auto => "auto",
+            ColorScheme::Light => "light",
+            ColorScheme::Dark => "dark",
+        }
+    }
+
+    /// The message naming the scheme.
+    pub fn message_key(&self) -> &'static str {
+        match self {
+            ColorScheme::Auto => "color-scheme-auto",
+            ColorScheme::Light => "color-scheme-light",
+            ColorScheme::Dark => "color-scheme-dark",
+        }
+    }
+
+    /// The preference stored in a `Cookie` request header.
+    pub fn from_cookie_header(header: &str) -> Self {
+        header
+            .split(';')
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(name, _)| *name == COOKIE)
+            .and_then(|(_, value)| Self::parse(value))
+            .unwrap_or_default()
+    }
+
+    /// The `Set-Cookie` value storing this preference; `Auto` clears the cookie.
+    pub fn set_cookie(&self) -> String {
+        match self {
+            ColorScheme::Auto => format!("{}=; Path=/; Max-Age=0; SameSite=Lax; HttpOnly", COOKIE),
+            scheme => format!("{}={}; Path=/; Max-Age=31536000; SameSite=Lax; HttpOnly", COOKIE, scheme.as_str()),
+        }
+    }
+}
+
+tokio::task_local! {
+    static PREFERENCE: ColorScheme;
+}
+
+/// Runs `request` with `scheme` as the preference pages are rendered for.
+pub async fn scope<F: Future>(scheme: ColorScheme, request: F) -> F::Output {
+    PREFERENCE.scope(scheme, request).await
+}
+
+/// The preference of the request being handled.
+pub fn current() -> ColorScheme {
+    PREFERENCE.try_with(|scheme| *scheme).unwrap_or_default()
+}
@@ -1 +1,3 @@
+pub mod color_scheme;
+pub mod package;
 pub mod theme_manager;
@@ -0,0 +1,318 @@
+use crate::i18n;
+use crate::views::templates::SOURCES;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// The manifest file that makes a directory in `THEME_DIR` a theme package.
+pub const MANIFEST: &str = "theme.json";
+
+/// Largest font or image file a package may ship.
+const MAX_ASSET_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Served file types by extension; fonts and images only.
+const CONTENT_TYPES: &[(&str, &str)] = &[
+    ("woff2", "font/woff2"),
+    ("woff", "font/woff"),
+    ("ttf", "font/ttf"),
+    ("otf", "font/otf"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+    ("ico", "image/x-icon"),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    /// The package's CSS file, `theme.css` unless given.
+    #[serde(default = "default_stylesheet")]
+    pub stylesheet: String,
+    /// Colours and other values, available as `var(--name)` in CSS and `theme_var` in templates.
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    #[serde(default)]
+    pub fonts: Vec<Font>,
+    /// Images, served with hashed names; `url(path)` in the stylesheet is rewritten to them.
+    #[serde(default)]
+    pub assets: Vec<String>,
+    /// A directory of template overrides used while the theme is active.
+    #[serde(default)]
+    pub templates: Option<String>,
+    /// `light` or `dark`: the scheme the stylesheet is designed for, `light` unless given.
+    #[serde(default)]
+    pub color_scheme: Option<String>,
+    /// Overrides applied on top of the stylesheet in dark mode.
+    #[serde(default)]
+    pub dark: Option<DarkVariant>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DarkVariant {
+    #[serde(default)]
+    pub stylesheet: Option<String>,
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Font {
+    pub family: String,
+    pub file: String,
+    #[serde(default = "default_font_weight")]
+    pub weight: String,
+    #[serde(default = "default_font_style")]
+    pub style: String,
+}
+
+fn default_stylesheet() -> String {
+    "theme.css".to_string()
+}
+
+fn default_font_weight() -> String {
+    "normal".to_string()
+}
+
+fn default_font_style() -> String {
+    "normal".to_string()
+}
+
+/// A font or image of a package, under the name it is served as from `/assets/`.
+pub struct Asset {
+    pub file_name: String,
+    pub content_type: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+/// A validated theme package, ready to serve.
+pub struct ThemePackage {
+    pub manifest: Manifest,
+    /// Variables, font faces and the package stylesheet in one.
+    pub css: String,
+    /// Dark variables and stylesheet, loaded after `css` in dark mode.
+    pub dark_css: Option<String>,
+    pub dark_by_default: bool,
+    pub assets: Vec<Asset>,
+    pub templates: Option<PathBuf>,
+}
+
+/// Values copied into generated CSS may not contain these, so they cannot end a declaration.
+fn is_css_value(value: &str) -> bool {
+    !value.chars().any(|c| c.is_control() || ";{}<>\"'\\".contains(c))
+}
+
+/// Theme names are used in URLs: lowercase letters, digits, `-` and `_`.
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 40 {
+        return Err(i18n::t("error-theme-name-length"));
+    }
+    if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_') {
+        return Err(i18n::t("error-theme-name-invalid"));
+    }
+    Ok(())
+}
+
+/// Variable names become CSS custom properties; values must not break out of a declaration.
+pub fn check_variable(name: &str, value: &str) -> Result<(), String> {
+    let valid_name = name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if name.is_empty() || name.len() > 40 || !valid_name {
+        return Err(i18n::t_args("error-theme-variable-name", &[("name", name.to_string())]));
+    }
+    if value.trim().is_empty() || value.len() > 100 || !is_css_value(value) {
+        return Err(i18n::t_args("error-theme-variable-value", &[("name", name.to_string())]));
+    }
+    Ok(())
+}
+
+/// Whether `path` is relative and cannot climb out of the package.
+fn is_inside(path: &str) -> bool {
+    !path.is_empty() && Path::new(path).components().all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// `path` inside the package directory, if it stays there and names a file.
+fn package_file(dir: &Path, path: &str) -> Result<PathBuf, String> {
+    if !is_inside(path) {
+        return Err(i18n::t_args("error-theme-path-outside", &[("path", path.to_string())]));
+    }
+    let full = dir.join(path);
+    if !full.is_file() {
+        return Err(i18n::t_args("error-theme-file-missing", &[("path", path.to_string())]));
+    }
+    Ok(full)
+}
+
+fn unreadable(path: &str, error: std::io::Error) -> String {
+    i18n::t_args("error-theme-unreadable", &[("path", path.to_string()), ("error", error.to_string())])
+}
+
+fn hashed_file_name(path: &str, bytes: &[u8]) -> String {
+    let file = Path::new(path);
+    let stem: String = file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("asset")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    let extension = file.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+    let hash: String = Sha256::digest(bytes).iter().take(8).map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}.{}", stem, hash, extension.to_ascii_lowercase())
+}
+
+/// Points `url(path)` references to manifest assets at their served names.
+fn rewrite_urls(mut css: String, paths: &[String], assets: &[Asset]) -> String {
+    for (path, asset) in paths.iter().zip(assets) {
+        for quote in ["", "\"", "'"] {
+            let url = format!("url({}{}{})", quote, path, quote);
+            css = css.replace(&url, &format!("url(\"/assets/{}\")", asset.file_name));
+        }
+    }
+    css
+}
+
+fn root_variables<'a>(variables: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    let mut css = String::from(":root {\n");
+    for (name, value) in variables {
+        css.push_str(&format!("    --{}: {};\n", name, value));
+    }
+    css.push_str("}\n");
+    css
+}
+
+fn load_asset(dir: &Path, path: &str) -> Result<Asset, String> {
+    let full = package_file(dir, path)?;
+    let extension = full.extension().and_then(|extension| extension.to_str()).unwrap_or_default().to_ascii_lowercase();
+    let content_type = CONTENT_TYPES
+        .iter()
+        .find(|(known, _)| *known == extension)
+        .map(|(_, content_type)| *content_type)
+        .ok_or_else(|| i18n::t_args("error-theme-not-asset", &[("path", path.to_string())]))?;
+    let size = fs::metadata(&full).map_err(|e| unreadable(path, e))?.len();
+    if size > MAX_ASSET_BYTES {
+        let mib = (MAX_ASSET_BYTES / 1024 / 1024).to_string();
+        return Err(i18n::t_args("error-theme-asset-too-large", &[("path", path.to_string()), ("mib", mib)]));
+    }
+    let bytes = fs::read(&full).map_err(|e| unreadable(path, e))?;
+    Ok(Asset { file_name: hashed_file_name(path, &bytes), content_type, bytes })
+}
+
+/// Every file under `dir` must replace a built-in template and parse.
+fn check_templates(dir: &Path) -> Result<(), String> {
+    let environment = crate::views::templates::override_environment(dir.to_path_buf());
+    for path in files_under(dir) {
+        let name = path.strip_prefix(dir).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        if !SOURCES.iter().any(|(source_name, _)| *source_name == name) {
+            return Err(i18n::t_args("error-theme-template-unknown", &[("name", name)]));
+        }
+        if let Err(e) = environment.get_template(&name) {
+            return Err(i18n::t_args("error-theme-template-invalid", &[("name", name), ("error", e.to_string())]));
+        }
+    }
+    Ok(())
+}
+
+fn read_stylesheet(dir: &Path, path: &str) -> Result<String, String> {
+    fs::read_to_string(package_file(dir, path)?).map_err(|e| unreadable(path, e))
+}
+
+/// All files below `dir`, sorted.
+pub fn files_under(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Reads and validates the package in `dir`, explaining the first problem found.
+pub fn load(dir: &Path, defaults: &[(&str, &str)]) -> Result<ThemePackage, String> {
+    let source = fs::read_to_string(dir.join(MANIFEST)).map_err(|e| unreadable(MANIFEST, e))?;
+    let manifest: Manifest = serde_json::from_str(&source).map_err(|e| {
+        i18n::t_args("error-theme-manifest-invalid", &[("path", MANIFEST.to_string()), ("error", e.to_string())])
+    })?;
+    check_name(&manifest.name)?;
+    if manifest.version.trim().is_empty() {
+        return Err(i18n::t("error-theme-version-missing"));
+    }
+
+    let mut variables: BTreeMap<&str, &str> = defaults.iter().copied().collect();
+    for (name, value) in &manifest.variables {
+        check_variable(name, value)?;
+        variables.insert(name, value);
+    }
+
+    let dark_by_default = match manifest.color_scheme.as_deref() {
+        None | Some("light") => false,
+        Some("dark") => true,
+        Some(other) => return Err(i18n::t_args("error-theme-color-scheme", &[("value", other.to_string())])),
+    };
+
+    let stylesheet = read_stylesheet(dir, &manifest.stylesheet)?;
+    let mut assets = manifest.assets.iter().map(|path| load_asset(dir, path)).collect::<Result<Vec<_>, _>>()?;
+    let stylesheet = rewrite_urls(stylesheet, &manifest.assets, &assets);
+
+    let dark_css = match &manifest.dark {
+        Some(dark) => {
+            for (name, value) in &dark.variables {
+                check_variable(name, value)?;
+            }
+            let mut css = root_variables(dark.variables.iter().map(|(name, value)| (name.as_str(), value.as_str())));
+            if let Some(path) = &dark.stylesheet {
+                css.push_str(&rewrite_urls(read_stylesheet(dir, path)?, &manifest.assets, &assets));
+            }
+            Some(css)
+        }
+        None => None,
+    };
+
+    let mut css = root_variables(variables);
+    for font in &manifest.fonts {
+        if font.family.trim().is_empty() || ![&font.family, &font.weight, &font.style].iter().all(|value| is_css_value(value)) {
+            return Err(i18n::t_args("error-theme-font-invalid", &[("family", font.family.clone())]));
+        }
+        let asset = load_asset(dir, &font.file)?;
+        if !asset.content_type.starts_with("font/") {
+            return Err(i18n::t_args("error-theme-not-font", &[("path", font.file.clone())]));
+        }
+        css.push_str(&format!(
+            "@font-face {{\n    font-family: \"{}\";\n    src: url(\"/assets/{}\");\n    font-weight: {};\n    font-style: {};\n    font-display: swap;\n}}\n",
+            font.family, asset.file_name, font.weight, font.style
+        ));
+        assets.push(asset);
+    }
+    css.push_str(&stylesheet);
+
+    let templates = match &manifest.templates {
+        Some(path) => {
+            let templates = dir.join(path);
+            if !is_inside(path) || !templates.is_dir() {
+                return Err(i18n::t_args("error-theme-templates-missing", &[("path", path.clone())]));
+            }
+            check_templates(&templates)?;
+            Some(templates)
+        }
+        None => None,
+    };
+
+    Ok(ThemePackage { manifest, css, dark_css, dark_by_default, assets, templates })
+}
@@ -1,57 +1,499 @@
-use std::collections::HashMap;
+use crate::db::{self, DatabaseError, Pool};
+use crate::views::templates;
+use crate::views::themes::color_scheme::ColorScheme;
+use crate::views::themes::package::{self, Asset, MANIFEST};
+use minijinja::Environment;
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Themes compiled into the binary.
+pub const BUILT_IN: &[(&str, &str)] = &[
+    ("default", include_str!("default.css")),
+    ("minimal", include_str!("minimal.css")),
+    ("monero", include_str!("monero.css")),
+];
+
+/// Dark variants of built-in themes, loaded on top of the theme in dark mode.
+pub const BUILT_IN_DARK: &[(&str, &str)] = &[("default", include_str!("default.dark.css"))];
+
+/// Built-in themes that are dark already.
+const DARK_BY_DEFAULT: &[&str] = &["minimal", "monero"];
+
+/// Variables of the built-in themes. Packages start from the `default` ones.
+pub const BUILT_IN_VARIABLES: &[(&str, &[(&str, &str)])] = &[
+    ("default", &[("background", "#ffffff"), ("muted", "#e9ecef"), ("primary", "#3498db"), ("text", "#333333")]),
+    ("minimal", &[("background", "#000000"), ("muted", "#333333"), ("primary", "#00ffff"), ("text", "#ffffff")]),
+    ("monero", &[("background", "#1a1a1a"), ("muted", "#333333"), ("primary", "#ff6600"), ("text", "#ffffff")]),
+];
+
+fn default_variables() -> &'static [(&'static str, &'static str)] {
+    BUILT_IN_VARIABLES[0].1
+}
+
+/// Setting keys of the theme choices made on the dashboard.
+pub const ACTIVE_THEME_SETTING: &str = "active_theme";
+pub const CUSTOM_CSS_SETTING: &str = "custom_css";
+
+/// Theme choices saved from the dashboard. They win over `THEME_DIR` and `ACTIVE_THEME`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ThemeSettings {
+    pub active_theme: Option<String>,
+    /// Appended to whichever theme is shown.
+    pub custom_css: String,
+    /// Added themes as (name, css).
+    pub uploaded: Vec<(String, String)>,
+}
+
+impl ThemeSettings {
+    pub fn from_db(conn: &Connection) -> Result<Self, DatabaseError> {
+        Ok(Self {
+            active_theme: db::get_setting(conn, ACTIVE_THEME_SETTING)?,
+            custom_css: db::get_setting(conn, CUSTOM_CSS_SETTING)?.unwrap_or_default(),
+            uploaded: db::get_uploaded_themes(conn)?,
+        })
+    }
+}
+
+/// The stylesheets and `color-scheme` a page uses for a theme.
+#[derive(Debug, PartialEq)]
+pub struct ThemeStyles {
+    pub stylesheet: String,
+    /// Loaded after `stylesheet` when dark mode applies.
+    pub dark_stylesheet: Option<String>,
+    /// The media query limiting `dark_stylesheet`, unless dark mode is forced.
+    pub dark_media: Option<&'static str>,
+    /// Content of the `color-scheme` meta tag.
+    pub color_scheme: &'static str,
+}
+
+/// A theme with the custom CSS appended, as served.
+struct Stylesheet {
+    hash: String,
+    css: String,
+}
+
+/// What a theme package brings besides its stylesheet.
+struct PackageInfo {
+    version: String,
+    author: String,
+    variables: BTreeMap<String, String>,
+    /// Manifest asset paths and the names they are served as.
+    asset_names: HashMap<String, String>,
+    templates: Option<PathBuf>,
+}
+
+/// A theme package that could not be loaded.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct ThemeProblem {
+    pub theme: String,
+    pub message: String,
+}
 
 pub struct ThemeManager {
     themes: HashMap<String, String>,
+    dark_variants: HashMap<String, String>,
+    /// Themes designed for a dark background.
+    dark_themes: HashSet<String>,
+    packages: HashMap<String, PackageInfo>,
+    assets: HashMap<String, Asset>,
+    problems: Vec<ThemeProblem>,
     active_theme: String,
+    custom_css: String,
+    stylesheets: HashMap<String, Stylesheet>,
+    dark_stylesheets: HashMap<String, Stylesheet>,
+    /// Template overrides of the active theme, on top of `TEMPLATE_DIR`.
+    environment: Option<Environment<'static>>,
 }
 
 impl ThemeManager {
-    pub fn new() -> Self {
+    /// The built-in themes plus the `.css` files and theme packages in `dir`,
+    /// which replace built-in themes of the same name, with `requested` active.
+    pub fn load(dir: Option<&Path>, requested: &str) -> Self {
         let mut manager = Self {
-            themes: HashMap::new(),
+            themes: BUILT_IN.iter().map(|(name, css)| (name.to_string(), css.to_string())).collect(),
+            dark_variants: BUILT_IN_DARK.iter().map(|(name, css)| (name.to_string(), css.to_string())).collect(),
+            dark_themes: DARK_BY_DEFAULT.iter().map(|name| name.to_string()).collect(),
+            packages: HashMap::new(),
+            assets: HashMap::new(),
+            problems: Vec::new(),
             active_theme: "default".to_string(),
+            custom_css: String::new(),
+            stylesheets: HashMap::new(),
+            dark_stylesheets: HashMap::new(),
+            environment: None,
         };
-        manager.load_themes();
-        manager.set_active_theme_from_env();
+        if let Some(dir) = dir {
+            manager.load_dir(dir);
+        }
+        manager.set_active_theme(requested);
+        manager.build_stylesheets();
         manager
     }
 
-    fn load_themes(&mut self) {
-        let themes_dir = Path::new("src/views/themes");
-        if let Ok(entries) = fs::read_dir(themes_dir) {
-            for entry in entries.flatten() {
-                if let Some(filename) = entry.file_name().to_str() {
-                    if filename.ends_with(".css") {
-                        let theme_name = filename.trim_end_matches(".css").to_string();
-                        if let Ok(content) = fs::read_to_string(entry.path()) {
-                            self.themes.insert(theme_name, content);
-                        }
-                    }
+    /// Adds the uploaded themes and applies the active theme and custom CSS from `settings`.
+    pub fn with_settings(mut self, settings: &ThemeSettings) -> Self {
+        for (name, css) in &settings.uploaded {
+            self.replace_theme(name, css.clone());
+            self.packages.remove(name);
+        }
+        if let Some(active_theme) = &settings.active_theme {
+            self.set_active_theme(active_theme);
+        }
+        self.custom_css = settings.custom_css.clone();
+        self.build_stylesheets();
+        self
+    }
+
+    fn from_env() -> Self {
+        let requested = env::var("ACTIVE_THEME").unwrap_or_else(|_| "default".to_string());
+        Self::load(theme_dir().as_deref(), &requested)
+    }
+
+    /// A theme from elsewhere under `name`; a built-in one's dark variant no longer fits it.
+    fn replace_theme(&mut self, name: &str, css: String) {
+        self.themes.insert(name.to_string(), css);
+        self.dark_variants.remove(name);
+        self.dark_themes.remove(name);
+    }
+
+    fn load_dir(&mut self, dir: &Path) {
+        let entries: Vec<PathBuf> = match fs::read_dir(dir) {
+            Ok(entries) => entries.flatten().map(|entry| entry.path()).collect(),
+            Err(e) => {
+                eprintln!("Error: cannot read themes from {}: {}", dir.display(), e);
+                return;
+            }
+        };
+        let css_files = |dark: bool| {
+            entries.iter().filter_map(move |path| {
+                let name = path.file_name()?.to_str()?.strip_suffix(".css")?;
+                match name.strip_suffix(".dark") {
+                    Some(name) if dark => Some((name.to_string(), path)),
+                    None if !dark => Some((name.to_string(), path)),
+                    _ => None,
+                }
+            })
+        };
+
+        for path in entries.iter().filter(|path| path.join(MANIFEST).is_file()) {
+            self.load_package(path);
+        }
+        for (name, path) in css_files(false) {
+            match fs::read_to_string(path) {
+                Ok(content) => self.replace_theme(&name, content),
+                Err(e) => eprintln!("Error: cannot read theme {}: {}", path.display(), e),
+            }
+        }
+        // `<name>.dark.css` is the dark variant of `<name>.css`.
+        for (name, path) in css_files(true) {
+            match fs::read_to_string(path) {
+                Ok(content) => {
+                    self.dark_variants.insert(name, content);
                 }
+                Err(e) => eprintln!("Error: cannot read theme {}: {}", path.display(), e),
             }
         }
     }
 
-    fn set_active_theme_from_env(&mut self) {
-        let env_theme = env::var("ACTIVE_THEME").unwrap_or_else(|_| "default".to_string());
-        if self.themes.contains_key(&env_theme) {
-            self.active_theme = env_theme;
+    fn load_package(&mut self, dir: &Path) {
+        let package = match package::load(dir, default_variables()) {
+            Ok(package) => package,
+            Err(message) => {
+                let theme = dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                eprintln!("Error: theme {} is broken: {}", theme, message);
+                self.problems.push(ThemeProblem { theme, message });
+                return;
+            }
+        };
+        let manifest = package.manifest;
+        let mut variables: BTreeMap<String, String> =
+            default_variables().iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+        variables.extend(manifest.variables);
+        let asset_names = manifest
+            .assets
+            .iter()
+            .zip(&package.assets)
+            .map(|(path, asset)| (path.clone(), asset.file_name.clone()))
+            .collect();
+        for asset in package.assets {
+            self.assets.insert(asset.file_name.clone(), asset);
+        }
+        self.replace_theme(&manifest.name, package.css);
+        if let Some(dark_css) = package.dark_css {
+            self.dark_variants.insert(manifest.name.clone(), dark_css);
+        }
+        if package.dark_by_default {
+            self.dark_themes.insert(manifest.name.clone());
+        }
+        self.packages.insert(
+            manifest.name,
+            PackageInfo {
+                version: manifest.version,
+                author: manifest.author,
+                variables,
+                asset_names,
+                templates: package.templates,
+            },
+        );
+    }
+
+    fn set_active_theme(&mut self, requested: &str) {
+        if self.themes.contains_key(requested) {
+            self.active_theme = requested.to_string();
         } else {
-            println!(
-                "Error: Theme '{}' not found. Defaulting to 'default'.",
-                env_theme
-            );
+            eprintln!("Error: Theme '{}' not found. Defaulting to 'default'.", requested);
             self.active_theme = "default".to_string();
         }
     }
 
-    pub fn get_active_theme_css(&self) -> String {
-        self.themes
+    fn build_stylesheets(&mut self) {
+        self.stylesheets = self
+            .themes
+            .iter()
+            .map(|(name, css)| {
+                let css = if self.custom_css.trim().is_empty() {
+                    css.clone()
+                } else {
+                    format!("{}\n{}", css, self.custom_css)
+                };
+                (name.clone(), Stylesheet { hash: content_hash(&css), css })
+            })
+            .collect();
+        self.dark_stylesheets = self
+            .dark_variants
+            .iter()
+            .filter(|(name, _)| self.themes.contains_key(*name))
+            .map(|(name, css)| (name.clone(), Stylesheet { hash: content_hash(css), css: css.clone() }))
+            .collect();
+        self.environment = self.packages.get(&self.active_theme).and_then(|package| package.templates.clone()).map(|dir| {
+            let mut dirs = vec![dir];
+            dirs.extend(templates::template_dir());
+            templates::layered_environment(dirs)
+        });
+    }
+
+    pub fn active_theme(&self) -> &str {
+        &self.active_theme
+    }
+
+    pub fn custom_css(&self) -> &str {
+        &self.custom_css
+    }
+
+    /// Names of all available themes, sorted.
+    pub fn theme_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.themes.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn has_theme(&self, name: &str) -> bool {
+        self.themes.contains_key(name)
+    }
+
+    /// Version and author of a theme package.
+    pub fn package_details(&self, name: &str) -> Option<(&str, &str)> {
+        self.packages.get(name).map(|package| (package.version.as_str(), package.author.as_str()))
+    }
+
+    /// Theme packages in `THEME_DIR` that failed to load.
+    pub fn problems(&self) -> &[ThemeProblem] {
+        &self.problems
+    }
+
+    /// A variable of the active theme, such as `primary`.
+    pub fn variable(&self, name: &str) -> Option<&str> {
+        match self.packages.get(&self.active_theme) {
+            Some(package) => package.variables.get(name).map(String::as_str),
+            None => BUILT_IN_VARIABLES
+                .iter()
+                .find(|(theme, _)| *theme == self.active_theme)
+                .map_or(default_variables(), |(_, variables)| variables)
+                .iter()
+                .find(|(variable, _)| *variable == name)
+                .map(|(_, value)| *value),
+        }
+    }
+
+    /// Where an image or font of the active theme's manifest is served.
+    pub fn asset_path(&self, path: &str) -> Option<String> {
+        let package = self.packages.get(&self.active_theme)?;
+        package.asset_names.get(path).map(|file_name| format!("/assets/{}", file_name))
+    }
+
+    /// A font or image of a theme package by its served name.
+    pub fn asset(&self, file: &str) -> Option<&Asset> {
+        self.assets.get(file)
+    }
+
+    /// The template overrides of the active theme package, if it has any.
+    pub fn template_environment(&self) -> Option<&Environment<'static>> {
+        self.environment.as_ref()
+    }
+
+    /// The active theme with the custom CSS appended.
+    #[cfg(test)]
+    pub fn get_active_theme_css(&self) -> &str {
+        self.stylesheets
             .get(&self.active_theme)
-            .cloned()
-            .unwrap_or_else(|| self.themes.get("default").cloned().unwrap_or_default())
+            .or_else(|| self.stylesheets.get("default"))
+            .map(|stylesheet| stylesheet.css.as_str())
+            .unwrap_or_default()
     }
+
+    /// Where the active theme is served; the URL changes whenever its content does.
+    #[cfg(test)]
+    pub fn stylesheet_path(&self) -> String {
+        self.theme_stylesheet_path(&self.active_theme).unwrap_or_default()
+    }
+
+    /// Where the theme `name` is served, to preview it before switching.
+    pub fn theme_stylesheet_path(&self, name: &str) -> Option<String> {
+        self.stylesheets.get(name).map(|stylesheet| format!("/assets/theme-{}.css", stylesheet.hash))
+    }
+
+    /// Whether the active theme has a dark variant to switch to.
+    pub fn has_dark_variant(&self) -> bool {
+        self.dark_stylesheets.contains_key(&self.active_theme)
+    }
+
+    /// What to link for the theme `name`, or the active one, given a visitor's `preference`.
+    pub fn styles(&self, name: Option<&str>, preference: ColorScheme) -> ThemeStyles {
+        let name = name.filter(|name| self.stylesheets.contains_key(*name)).unwrap_or(&self.active_theme);
+        let stylesheet = self.theme_stylesheet_path(name).unwrap_or_default();
+        let Some(dark) = self.dark_stylesheets.get(name) else {
+            let color_scheme = if self.dark_themes.contains(name) { "dark" } else { "light" };
+            return ThemeStyles { stylesheet, dark_stylesheet: None, dark_media: None, color_scheme };
+        };
+        let dark_stylesheet = Some(format!("/assets/theme-{}.css", dark.hash));
+        match preference {
+            ColorScheme::Auto => ThemeStyles {
+                stylesheet,
+                dark_stylesheet,
+                dark_media: Some("(prefers-color-scheme: dark)"),
+                color_scheme: "light dark",
+            },
+            ColorScheme::Light => ThemeStyles { stylesheet, dark_stylesheet: None, dark_media: None, color_scheme: "light" },
+            ColorScheme::Dark => ThemeStyles { stylesheet, dark_stylesheet, dark_media: None, color_scheme: "dark" },
+        }
+    }
+
+    /// The CSS of the theme or dark variant whose current stylesheet name is `file`.
+    pub fn stylesheet(&self, file: &str) -> Option<&str> {
+        let hash = file.strip_prefix("theme-")?.strip_suffix(".css")?;
+        self.stylesheets
+            .values()
+            .chain(self.dark_stylesheets.values())
+            .find(|stylesheet| stylesheet.hash == hash)
+            .map(|stylesheet| stylesheet.css.as_str())
+    }
+}
+
+fn content_hash(css: &str) -> String {
+    Sha256::digest(css.as_bytes())
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn theme_dir() -> Option<PathBuf> {
+    env::var("THEME_DIR").ok().filter(|dir| !dir.trim().is_empty()).map(PathBuf::from)
+}
+
+fn settings() -> &'static RwLock<ThemeSettings> {
+    static SETTINGS: OnceLock<RwLock<ThemeSettings>> = OnceLock::new();
+    SETTINGS.get_or_init(Default::default)
+}
+
+static THEMES: OnceLock<RwLock<Arc<ThemeManager>>> = OnceLock::new();
+
+fn shared() -> &'static RwLock<Arc<ThemeManager>> {
+    THEMES.get_or_init(|| RwLock::new(Arc::new(ThemeManager::from_env())))
+}
+
+/// The loaded themes, shared by every request.
+pub fn current() -> Arc<ThemeManager> {
+    shared().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn reload() {
+    let manager = {
+        let settings = settings().read().unwrap_or_else(|e| e.into_inner());
+        Arc::new(ThemeManager::from_env().with_settings(&settings))
+    };
+    // The first load goes straight in, so themes are not read twice at startup.
+    if let Err(lock) = THEMES.set(RwLock::new(manager)) {
+        *shared().write().unwrap_or_else(|e| e.into_inner()) = lock.into_inner().unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+/// Switches every following request to `settings`.
+fn apply(new_settings: ThemeSettings) {
+    *settings().write().unwrap_or_else(|e| e.into_inner()) = new_settings;
+    reload();
+}
+
+/// Reads the theme settings from the database again and applies them. Runs on
+/// the blocking pool, as reloading reads `THEME_DIR` from disk.
+pub async fn refresh(pool: &Pool) -> Result<(), DatabaseError> {
+    db::run(pool, |conn| {
+        apply(ThemeSettings::from_db(conn)?);
+        Ok(())
+    })
+    .await
+}
+
+/// The files below `dir` with their modification times, to notice edits.
+fn dir_state(dir: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    package::files_under(dir)
+        .into_iter()
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+/// Loads the themes and the dashboard settings and, when `THEME_DIR` is set and
+/// `THEME_WATCH` is on (the default in debug builds), reloads them whenever a file there changes.
+pub fn init(conn: &Connection) {
+    match ThemeSettings::from_db(conn) {
+        Ok(settings) => apply(settings),
+        Err(e) => {
+            eprintln!("Error: cannot load theme settings: {}", e);
+            current();
+        }
+    }
+    let Some(dir) = theme_dir() else {
+        return;
+    };
+    let watch = env::var("THEME_WATCH")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(cfg!(debug_assertions));
+    if !watch {
+        return;
+    }
+
+    println!("Watching {} for theme changes", dir.display());
+    // Walking the directory and reloading block, so poll from a thread of its own.
+    thread::spawn(move || {
+        let mut last = dir_state(&dir);
+        loop {
+            thread::sleep(Duration::from_secs(1));
+            let state = dir_state(&dir);
+            if state != last {
+                last = state;
+                reload();
+                println!("Reloaded themes from {}", dir.display());
+            }
+        }
+    });
 }